@@ -0,0 +1,50 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! The multiset operations inference rules lean on hardest.
+//! Run with `cargo bench`.
+
+#![deny(warnings)]
+
+use core::hint::black_box;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use gentzen::Multiset;
+
+fn clone(c: &mut Criterion) {
+    let mut group = c.benchmark_group("clone a context");
+    for n in [8, 64] {
+        let context: Multiset<usize> = (0..n).collect();
+        let _ = group.bench_with_input(BenchmarkId::from_parameter(n), &context, |b, ctx| {
+            b.iter(|| black_box(ctx).clone());
+        });
+    }
+    group.finish();
+}
+
+fn with(c: &mut Criterion) {
+    let mut group = c.benchmark_group("add one element to a context");
+    for n in [8, 64] {
+        let context: Multiset<usize> = (0..n).collect();
+        let _ = group.bench_with_input(BenchmarkId::from_parameter(n), &context, |b, ctx| {
+            b.iter(|| black_box(ctx).with([0]));
+        });
+    }
+    group.finish();
+}
+
+fn partitions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("split a context every way");
+    for n in [2, 6, 10] {
+        let context: Multiset<usize> = (0..n).collect();
+        let _ = group.bench_with_input(BenchmarkId::from_parameter(n), &context, |b, ctx| {
+            b.iter(|| black_box(ctx).partitions().count());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, clone, with, partitions);
+criterion_main!(benches);
@@ -0,0 +1,31 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Expanding one sequent on a pool of threads versus one item at a time.
+//! Run with `cargo bench --features bench-internals,parallel --bench parallel`.
+
+#![deny(warnings)]
+
+use core::hint::black_box;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use gentzen::bench;
+
+fn expand(c: &mut Criterion) {
+    let mut group = c.benchmark_group("expand \u{2297}s");
+    for n in [2, 4, 8, 12] {
+        let sequent = bench::tensors(n);
+        let _ = group.bench_with_input(BenchmarkId::new("serial", n), &sequent, |b, s| {
+            b.iter(|| bench::expand_serially(black_box(s)));
+        });
+        let _ = group.bench_with_input(BenchmarkId::new("parallel", n), &sequent, |b, s| {
+            b.iter(|| bench::expand(black_box(s)));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, expand);
+criterion_main!(benches);
@@ -0,0 +1,75 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Proof search on formula families that stress different parts of the prover.
+//! Run with `cargo bench --features bench-internals`.
+
+#![deny(warnings)]
+
+use core::hint::black_box;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use gentzen::{
+    bench::{self, Formula},
+    prove,
+    sequents::RhsOnlyWithExchange,
+    Tree,
+};
+
+/// Search for a proof of `formula`, successful or not.
+fn search(formula: Formula) -> Option<Tree<RhsOnlyWithExchange<Formula>>> {
+    prove(formula).ok()
+}
+
+/// Time proof search on each size of one family of formulas.
+fn family(c: &mut Criterion, name: &str, sizes: &[usize], generate: fn(usize) -> Formula) {
+    let mut group = c.benchmark_group(name);
+    for &n in sizes {
+        let formula = generate(n);
+        let _ = group.bench_with_input(BenchmarkId::from_parameter(n), &formula, |b, f| {
+            b.iter(|| search(black_box(f.clone())));
+        });
+    }
+    group.finish();
+}
+
+fn with_chain(c: &mut Criterion) {
+    family(c, "& chain", &[4, 16, 64], bench::with_chain);
+}
+
+fn times_chain(c: &mut Criterion) {
+    family(c, "\u{2297} chain", &[2, 4, 8], bench::times_chain);
+}
+
+fn lollipop_nesting(c: &mut Criterion) {
+    family(c, "\u{22b8} nesting", &[2, 4, 8], bench::lollipop_nesting);
+}
+
+fn unprovable(c: &mut Criterion) {
+    family(c, "unprovable", &[1, 2, 3], bench::unprovable);
+}
+
+fn thunk_push(c: &mut Criterion) {
+    let mut group = c.benchmark_group("queue sequents");
+    let context: RhsOnlyWithExchange<Formula> =
+        RhsOnlyWithExchange::new((0..8).map(Formula::Dual).collect());
+    for n in [16, 256] {
+        let sequents = bench::sequents(n, &context);
+        let _ = group.bench_with_input(BenchmarkId::from_parameter(n), &sequents, |b, s| {
+            b.iter(|| bench::push_all(black_box(s.clone())));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    with_chain,
+    times_chain,
+    lollipop_nesting,
+    unprovable,
+    thunk_push
+);
+criterion_main!(benches);
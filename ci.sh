@@ -7,6 +7,7 @@ rustup toolchain install nightly
 rustup component add miri --toolchain nightly
 
 cargo fmt --check
+cargo check --no-default-features
 cargo clippy --all-targets --no-default-features
 cargo clippy --all-targets --all-features
 
@@ -14,5 +15,8 @@ export MIRIFLAGS=-Zmiri-backtrace=1
 export RUST_BACKTRACE=1
 cargo run --example 2>&1 | grep '^ ' | xargs -n 1 cargo +nightly miri run --example
 cargo +nightly miri test --examples --no-default-features
+cargo test --lib --no-default-features
+cargo test --lib --features linear-logic,propositional-logic
+cargo test --lib -r --all-features
 cargo test --examples
 cargo test --examples -r --all-features
@@ -0,0 +1,24 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Classical propositional logic, straight out of `gentzen::logics::classical_propositional`.
+
+#![deny(warnings)]
+
+use gentzen::logics::classical_propositional::Ast;
+
+fn main() {
+    let (a, b) = (Ast::Atom(0), Ast::Atom(1));
+    println!(
+        "{}",
+        a.clone()
+            .implies(b)
+            .implies(a.clone())
+            .implies(a)
+            .prove()
+            .unwrap()
+    );
+}
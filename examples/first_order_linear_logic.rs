@@ -0,0 +1,537 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Multiplicative-additive linear logic with first-order quantifiers,
+//! using de Bruijn indices for bound variables and ground instantiation for proof search.
+
+#![deny(warnings)]
+#![allow(clippy::needless_borrowed_reference)]
+
+use gentzen::prelude::*;
+use std::{collections::BTreeSet, sync::Arc};
+
+fn main() {
+    let p = |term| Ast::Value(Predicate::new(0, [term]));
+    let ast = Ast::forall(p(Term::Var(0))).lollipop(p(Term::Const(0)));
+    println!("{}", ast.prove().unwrap());
+}
+
+/// Something a predicate can be about.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Term {
+    /// Variable bound by the `n`th quantifier out from here (a de Bruijn index).
+    Var(usize),
+    /// Constant identified by number (for efficient comparison).
+    Const(usize),
+}
+
+impl core::fmt::Display for Term {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::Var(i) => write!(f, "#{i}"),
+            Self::Const(i) => write!(f, "c{i}"),
+        }
+    }
+}
+
+impl Term {
+    /// The same term seen from under `by` more quantifiers.
+    #[must_use]
+    #[inline]
+    pub const fn shifted(self, by: usize) -> Self {
+        match self {
+            Self::Var(i) => Self::Var(i.saturating_add(by)),
+            Self::Const(_) => self,
+        }
+    }
+}
+
+/// A predicate applied to terms, i.e. a first-order atom.
+#[allow(clippy::exhaustive_structs)]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Predicate {
+    /// Predicate symbol identified by number (for efficient comparison).
+    pub name: usize,
+    /// Whatever it's applied to, in order.
+    pub args: Vec<Term>,
+}
+
+impl core::fmt::Display for Predicate {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "P{}", self.name)?;
+        let mut args = self.args.iter();
+        if let Some(first) = args.next() {
+            write!(f, "({first}")?;
+            for next in args {
+                write!(f, ", {next}")?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl Predicate {
+    /// Apply a predicate symbol to these terms.
+    #[must_use]
+    #[inline]
+    pub fn new<I: IntoIterator<Item = Term>>(name: usize, args: I) -> Self {
+        Self {
+            name,
+            args: args.into_iter().collect(),
+        }
+    }
+}
+
+/// Abstract syntax tree for first-order linear logic with sequent-calculus proof search built in.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Ast {
+    /// Unit for multiplicative conjunction.
+    One,
+    /// Unit for multiplicative disjunction.
+    Bottom,
+    /// Unit for additive conjunction.
+    Top,
+    /// Unit for additive disjunction.
+    Zero,
+    /// Predicate applied to terms.
+    Value(Predicate),
+    /// Dual, i.e. linear negation.
+    Dual(Arc<Self>),
+    /// Multiplicative conjunction.
+    Times(Arc<Self>, Arc<Self>),
+    /// Multiplicative disjunction.
+    Par(Arc<Self>, Arc<Self>),
+    /// Additive conjunction.
+    With(Arc<Self>, Arc<Self>),
+    /// Additive disjunction.
+    Plus(Arc<Self>, Arc<Self>),
+    /// Linear implication, which means the same as `~A ⅋ B`.
+    Lollipop(Arc<Self>, Arc<Self>),
+    /// Universal quantifier, binding `Term::Var(0)` in its body.
+    ForAll(Arc<Self>),
+    /// Existential quantifier, binding `Term::Var(0)` in its body.
+    Exists(Arc<Self>),
+}
+
+impl core::fmt::Display for Ast {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::One => write!(f, "1"),
+            Self::Bottom => write!(f, "\u{22a5}"),
+            Self::Top => write!(f, "\u{22a4}"),
+            Self::Zero => write!(f, "0"),
+            Self::Value(ref predicate) => write!(f, "{predicate}"),
+            Self::Dual(ref arg) => {
+                write!(f, "~")?;
+                arg.fmt_operand(f)
+            }
+            Self::ForAll(ref body) => {
+                write!(f, "\u{2200}")?;
+                body.fmt_operand(f)
+            }
+            Self::Exists(ref body) => {
+                write!(f, "\u{2203}")?;
+                body.fmt_operand(f)
+            }
+            Self::Times(ref lhs, ref rhs) => Self::fmt_infix(f, lhs, "\u{2297}", rhs),
+            Self::Par(ref lhs, ref rhs) => Self::fmt_infix(f, lhs, "\u{214b}", rhs),
+            Self::With(ref lhs, ref rhs) => Self::fmt_infix(f, lhs, "&", rhs),
+            Self::Plus(ref lhs, ref rhs) => Self::fmt_infix(f, lhs, "\u{2295}", rhs),
+            Self::Lollipop(ref lhs, ref rhs) => Self::fmt_infix(f, lhs, "\u{22b8}", rhs),
+        }
+    }
+}
+
+impl Ast {
+    /// Whether this is a binary connective, which needs parentheses as an operand.
+    #[inline]
+    const fn is_infix(&self) -> bool {
+        matches!(
+            *self,
+            Self::Times(..) | Self::Par(..) | Self::With(..) | Self::Plus(..) | Self::Lollipop(..)
+        )
+    }
+
+    /// Print this as an operand, in parentheses if it's a binary connective.
+    #[inline]
+    fn fmt_operand(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.is_infix() {
+            write!(f, "({self})")
+        } else {
+            write!(f, "{self}")
+        }
+    }
+
+    /// Print a binary connective, parenthesizing any binary connective on either side.
+    #[inline]
+    fn fmt_infix(
+        f: &mut core::fmt::Formatter<'_>,
+        lhs: &Self,
+        symbol: &str,
+        rhs: &Self,
+    ) -> core::fmt::Result {
+        lhs.fmt_operand(f)?;
+        write!(f, " {symbol} ")?;
+        rhs.fmt_operand(f)
+    }
+
+    /// Linear implication, i.e. `~self ⅋ rhs` (but printed as `self ⊸ rhs`).
+    #[must_use]
+    #[inline(always)]
+    pub fn lollipop(self, rhs: Self) -> Self {
+        Self::Lollipop(Arc::new(self), Arc::new(rhs))
+    }
+
+    /// Par operator, since it's a pain in the ass to type.
+    #[must_use]
+    #[inline(always)]
+    pub fn par(self, rhs: Self) -> Self {
+        Self::Par(Arc::new(self), Arc::new(rhs))
+    }
+
+    /// Universal quantifier over `body`, in which `Term::Var(0)` is the bound variable.
+    #[must_use]
+    #[inline(always)]
+    pub fn forall(body: Self) -> Self {
+        Self::ForAll(Arc::new(body))
+    }
+
+    /// Existential quantifier over `body`, in which `Term::Var(0)` is the bound variable.
+    #[must_use]
+    #[inline(always)]
+    pub fn exists(body: Self) -> Self {
+        Self::Exists(Arc::new(body))
+    }
+
+    /// Attempt to prove this expression, returning the whole derivation if we can.
+    /// # Errors
+    /// If we can't.
+    #[inline(always)]
+    pub fn prove(self) -> Result<Tree<RhsOnlyWithExchange<Self>>, Error> {
+        prove(self)
+    }
+
+    /// Replace every variable with whatever `f` makes of its index and the number of quantifiers around it.
+    #[must_use]
+    #[inline]
+    fn map_vars<F: FnMut(usize, usize) -> Term>(&self, depth: usize, f: &mut F) -> Self {
+        match *self {
+            Self::One | Self::Bottom | Self::Top | Self::Zero => self.clone(),
+            Self::Value(ref predicate) => Self::Value(Predicate {
+                name: predicate.name,
+                args: predicate
+                    .args
+                    .iter()
+                    .map(|&term| match term {
+                        Term::Var(i) => f(i, depth),
+                        Term::Const(_) => term,
+                    })
+                    .collect(),
+            }),
+            Self::Dual(ref arg) => Self::Dual(Arc::new(arg.map_vars(depth, f))),
+            Self::Times(ref lhs, ref rhs) => Self::Times(
+                Arc::new(lhs.map_vars(depth, f)),
+                Arc::new(rhs.map_vars(depth, f)),
+            ),
+            Self::Par(ref lhs, ref rhs) => Self::Par(
+                Arc::new(lhs.map_vars(depth, f)),
+                Arc::new(rhs.map_vars(depth, f)),
+            ),
+            Self::With(ref lhs, ref rhs) => Self::With(
+                Arc::new(lhs.map_vars(depth, f)),
+                Arc::new(rhs.map_vars(depth, f)),
+            ),
+            Self::Plus(ref lhs, ref rhs) => Self::Plus(
+                Arc::new(lhs.map_vars(depth, f)),
+                Arc::new(rhs.map_vars(depth, f)),
+            ),
+            Self::Lollipop(ref lhs, ref rhs) => Self::Lollipop(
+                Arc::new(lhs.map_vars(depth, f)),
+                Arc::new(rhs.map_vars(depth, f)),
+            ),
+            Self::ForAll(ref body) => {
+                Self::ForAll(Arc::new(body.map_vars(depth.saturating_add(1), f)))
+            }
+            Self::Exists(ref body) => {
+                Self::Exists(Arc::new(body.map_vars(depth.saturating_add(1), f)))
+            }
+        }
+    }
+
+    /// The same expression moved under `by` more quantifiers,
+    /// so every variable free in it still refers to the same quantifier outside.
+    #[must_use]
+    #[inline]
+    pub fn shift(&self, by: usize) -> Self {
+        self.map_vars(0, &mut |i, depth| {
+            Term::Var(if i < depth { i } else { i.saturating_add(by) })
+        })
+    }
+
+    /// The body of a quantifier with its bound variable (`Term::Var(0)`) replaced by `term`,
+    /// and every variable bound further out renumbered now that one quantifier is gone.
+    #[must_use]
+    #[inline]
+    pub fn instantiate(&self, term: Term) -> Self {
+        self.map_vars(0, &mut |i, depth| match i.checked_sub(depth) {
+            None => Term::Var(i),
+            Some(0) => term.shifted(depth),
+            Some(_) => Term::Var(i.saturating_sub(1)),
+        })
+    }
+
+    /// Every node in this expression, itself included.
+    #[inline]
+    fn nodes(&self) -> impl Iterator<Item = &Self> {
+        let mut stack = vec![self];
+        core::iter::from_fn(move || {
+            let node = stack.pop()?;
+            match *node {
+                Self::One | Self::Bottom | Self::Top | Self::Zero | Self::Value(_) => {}
+                Self::Dual(ref arg) | Self::ForAll(ref arg) | Self::Exists(ref arg) => {
+                    stack.push(arg);
+                }
+                Self::Times(ref lhs, ref rhs)
+                | Self::Par(ref lhs, ref rhs)
+                | Self::With(ref lhs, ref rhs)
+                | Self::Plus(ref lhs, ref rhs)
+                | Self::Lollipop(ref lhs, ref rhs) => {
+                    stack.push(rhs);
+                    stack.push(lhs);
+                }
+            }
+            Some(node)
+        })
+    }
+
+    /// Every constant in this expression.
+    #[must_use]
+    #[inline]
+    pub fn constants(&self) -> BTreeSet<usize> {
+        self.nodes()
+            .filter_map(|node| match *node {
+                Self::Value(ref predicate) => Some(predicate.args.iter()),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|&term| match term {
+                Term::Const(i) => Some(i),
+                Term::Var(_) => None,
+            })
+            .collect()
+    }
+
+    /// Whether `dual` is syntactically the linear negation of `self`.
+    #[must_use]
+    #[inline]
+    pub fn is_dual_of(&self, dual: &Self) -> bool {
+        matches!(*dual, Self::Dual(ref arg) if **arg == *self)
+    }
+}
+
+/// Every constant anywhere in a sequent, plus one more expression that's been sampled out of it.
+#[inline]
+fn constants_in(context: &RhsOnlyWithExchange<Ast>, ast: &Ast) -> BTreeSet<usize> {
+    let mut constants = ast.constants();
+    for other in context.rhs.keys() {
+        constants.extend(other.constants());
+    }
+    constants
+}
+
+impl Infer<RhsOnlyWithExchange<Self>> for Ast {
+    #[inline]
+    fn above(&self, context: RhsOnlyWithExchange<Self>) -> Vec<Rule<RhsOnlyWithExchange<Self>>> {
+        if context.rhs.contains(&Self::Top)
+            || context
+                .rhs
+                .exactly()
+                .is_some_and(|[other]| self.is_dual_of(other) || other.is_dual_of(self))
+        {
+            return vec![Rule::new("axiom", [])];
+        }
+        match *self {
+            Self::Top => vec![Rule::invertible("\u{22a4}", [])],
+            Self::One if context.is_empty() => vec![Rule::new("1", [])],
+            Self::One | Self::Zero | Self::Value(_) => vec![],
+            Self::Bottom => vec![Rule::invertible("\u{22a5}", [context])],
+            Self::Dual(ref dual) => {
+                vec![Rule::new(
+                    "~",
+                    [context.with([match **dual {
+                        Self::One => Self::Bottom,
+                        Self::Bottom => Self::One,
+                        Self::Top => Self::Zero,
+                        Self::Zero => Self::Top,
+                        Self::Value(_) => return vec![],
+                        Self::Dual(ref arg) => arg.as_ref().clone(),
+                        Self::Times(ref lhs, ref rhs) => Self::Par(
+                            Arc::new(Self::Dual(lhs.clone())),
+                            Arc::new(Self::Dual(rhs.clone())),
+                        ),
+                        Self::Par(ref lhs, ref rhs) => Self::Times(
+                            Arc::new(Self::Dual(lhs.clone())),
+                            Arc::new(Self::Dual(rhs.clone())),
+                        ),
+                        Self::With(ref lhs, ref rhs) => Self::Plus(
+                            Arc::new(Self::Dual(lhs.clone())),
+                            Arc::new(Self::Dual(rhs.clone())),
+                        ),
+                        Self::Plus(ref lhs, ref rhs) => Self::With(
+                            Arc::new(Self::Dual(lhs.clone())),
+                            Arc::new(Self::Dual(rhs.clone())),
+                        ),
+                        Self::Lollipop(ref lhs, ref rhs) => {
+                            Self::Times(lhs.clone(), Arc::new(Self::Dual(rhs.clone())))
+                        }
+                        Self::ForAll(ref body) => Self::Exists(Arc::new(Self::Dual(body.clone()))),
+                        Self::Exists(ref body) => Self::ForAll(Arc::new(Self::Dual(body.clone()))),
+                    }])],
+                )]
+            }
+            Self::Times(ref lhs, ref rhs) => context
+                .rhs
+                .partitions()
+                .map(|(lctx, rctx)| {
+                    Rule::new(
+                        "\u{2297}",
+                        [
+                            RhsOnlyWithExchange::new(lctx.with([lhs.as_ref().clone()])),
+                            RhsOnlyWithExchange::new(rctx.with([rhs.as_ref().clone()])),
+                        ],
+                    )
+                })
+                .collect(),
+            Self::Par(ref lhs, ref rhs) => vec![Rule::invertible(
+                "\u{214b}",
+                [context.with([lhs.as_ref().clone(), rhs.as_ref().clone()])],
+            )],
+            Self::Lollipop(ref lhs, ref rhs) => vec![Rule::new(
+                "\u{22b8}",
+                [context.with([Self::Dual(lhs.clone()), rhs.as_ref().clone()])],
+            )],
+            Self::With(ref lhs, ref rhs) => vec![Rule::invertible(
+                "&",
+                [
+                    context.with([lhs.as_ref().clone()]),
+                    context.with([rhs.as_ref().clone()]),
+                ],
+            )],
+            Self::Plus(ref lhs, ref rhs) => vec![
+                Rule::new("+L", [context.with([lhs.as_ref().clone()])]),
+                Rule::new("+R", [context.with([rhs.as_ref().clone()])]),
+            ],
+            // Eigenvariable: a constant appearing nowhere in the conclusion.
+            Self::ForAll(ref body) => {
+                let fresh = constants_in(&context, self)
+                    .last()
+                    .map_or(0, |&i| i.saturating_add(1));
+                vec![Rule::invertible(
+                    "\u{2200}",
+                    [context.with([body.instantiate(Term::Const(fresh))])],
+                )]
+            }
+            // Every witness already in the sequent, or any one constant if there aren't any.
+            Self::Exists(ref body) => {
+                let mut witnesses = constants_in(&context, self);
+                if witnesses.is_empty() {
+                    let _ = witnesses.insert(0);
+                }
+                witnesses
+                    .into_iter()
+                    .map(|i| {
+                        Rule::new(
+                            "\u{2203}",
+                            [context.with([body.instantiate(Term::Const(i))])],
+                        )
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// `P0(term)`, to keep tests short.
+#[cfg(test)]
+fn p(term: Term) -> Ast {
+    Ast::Value(Predicate::new(0, [term]))
+}
+
+#[test]
+fn prove_universal_instantiation() {
+    let ast = Ast::forall(p(Term::Var(0))).lollipop(p(Term::Const(0)));
+    let proof = ast.prove().unwrap();
+    proof.validate().unwrap();
+}
+
+#[test]
+fn prove_universal_identity() {
+    Ast::forall(p(Term::Var(0)).lollipop(p(Term::Var(0))))
+        .prove()
+        .unwrap();
+}
+
+#[test]
+fn prove_universal_implies_existential() {
+    Ast::forall(p(Term::Var(0)))
+        .lollipop(Ast::exists(p(Term::Var(0))))
+        .prove()
+        .unwrap();
+}
+
+#[test]
+fn cant_prove_existential_implies_universal() {
+    assert_eq!(
+        Ast::exists(p(Term::Var(0)))
+            .lollipop(Ast::forall(p(Term::Var(0))))
+            .prove(),
+        Err(Error::RanOutOfPaths)
+    );
+}
+
+#[test]
+fn cant_prove_an_unrelated_constant() {
+    assert_eq!(
+        p(Term::Const(0)).lollipop(p(Term::Const(1))).prove(),
+        Err(Error::RanOutOfPaths)
+    );
+}
+
+#[test]
+fn instantiate_renumbers_outer_variables() {
+    let r = |x, y| Ast::Value(Predicate::new(1, [x, y]));
+    // Body of `∀x. ∃y. R(x, y) ⅋ R(z, y)`, with `z` bound further out.
+    let body = Ast::exists(r(Term::Var(1), Term::Var(0)).par(r(Term::Var(2), Term::Var(0))));
+    assert_eq!(
+        body.instantiate(Term::Const(5)),
+        Ast::exists(r(Term::Const(5), Term::Var(0)).par(r(Term::Var(1), Term::Var(0)))),
+    );
+}
+
+#[test]
+fn shift_skips_bound_variables() {
+    let ast = Ast::forall(p(Term::Var(0))).par(p(Term::Var(0)));
+    assert_eq!(
+        ast.shift(2),
+        Ast::forall(p(Term::Var(0))).par(p(Term::Var(2)))
+    );
+    assert_eq!(ast.shift(2).instantiate(Term::Const(0)), ast.shift(1));
+}
+
+#[test]
+fn display_quantifiers() {
+    let ast = Ast::forall(p(Term::Var(0)).lollipop(p(Term::Var(0)))).par(p(Term::Const(3)));
+    assert_eq!(
+        ast.to_string(),
+        "\u{2200}(P0(#0) \u{22b8} P0(#0)) \u{214b} P0(c3)"
+    );
+}
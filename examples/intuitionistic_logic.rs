@@ -0,0 +1,467 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Intuitionistic propositional logic with single-conclusion sequent-calculus proof search built in.
+
+#![deny(warnings)]
+#![allow(clippy::needless_borrowed_reference)]
+
+use gentzen::prelude::*;
+use std::collections::BTreeMap;
+
+#[cfg(test)]
+use gentzen::{Ascii, Latex, Unicode};
+
+fn main() {
+    let a = Formula::Atom(0);
+    println!("{}", (a.clone() | a.not()).not().not().prove().unwrap());
+}
+
+/// Propositional formula, with negation written as implying falsehood.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Formula {
+    /// Falsehood.
+    Bottom,
+    /// Propositional variable identified by number (for efficient comparison).
+    Atom(usize),
+    /// Conjunction.
+    And(Box<Self>, Box<Self>),
+    /// Disjunction.
+    Or(Box<Self>, Box<Self>),
+    /// Implication.
+    Implies(Box<Self>, Box<Self>),
+}
+
+impl core::fmt::Display for Formula {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::Bottom => write!(f, "\u{22a5}"),
+            Self::Atom(i) => write!(f, "p{i}"),
+            Self::And(ref lhs, ref rhs) => write!(f, "({lhs} \u{2227} {rhs})"),
+            Self::Or(ref lhs, ref rhs) => write!(f, "({lhs} \u{2228} {rhs})"),
+            Self::Implies(ref lhs, ref rhs) => write!(f, "({lhs} \u{2192} {rhs})"),
+        }
+    }
+}
+
+impl Formula {
+    /// Implication, since there's no operator for it.
+    #[must_use]
+    #[inline(always)]
+    pub fn implies(self, rhs: Self) -> Self {
+        Self::Implies(Box::new(self), Box::new(rhs))
+    }
+
+    /// Negation, i.e. implying falsehood.
+    #[must_use]
+    #[inline(always)]
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        self.implies(Self::Bottom)
+    }
+
+    /// Attempt to prove this formula, returning the whole derivation if we can.
+    /// # Errors
+    /// If we can't.
+    #[inline(always)]
+    pub fn prove(self) -> Result<Tree<IntuitionistWithExchange<Self>>, Error> {
+        prove(Sided::right(self))
+    }
+}
+
+impl core::ops::BitAnd<Self> for Formula {
+    type Output = Self;
+    #[inline(always)]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self::And(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl core::ops::BitOr<Self> for Formula {
+    type Output = Self;
+    #[inline(always)]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self::Or(Box::new(self), Box::new(rhs))
+    }
+}
+
+/// Add assumptions on the left, skipping any already there.
+/// Contraction is admissible, so treating the left as a set loses nothing
+/// and keeps the number of distinct sequents (and so the search) finite.
+#[inline]
+fn assume<I: IntoIterator<Item = Formula>>(
+    context: &IntuitionistWithExchange<Formula>,
+    additions: I,
+) -> IntuitionistWithExchange<Formula> {
+    let mut sequent = context.clone();
+    for formula in additions {
+        if !sequent.lhs.contains(&formula) {
+            sequent = sequent.with([formula]);
+        }
+    }
+    sequent
+}
+
+// Context-sharing rules (G3ip), where the left rule for implication keeps the implication around.
+impl InferLj for Formula {
+    #[inline]
+    fn left(
+        &self,
+        context: IntuitionistWithExchange<Self>,
+    ) -> Vec<Rule<IntuitionistWithExchange<Self>>> {
+        match *self {
+            Self::Bottom => vec![Rule::new("\u{22a5}L", [])],
+            Self::Atom(_) if context.rhs == *self => vec![Rule::new("axiom", [])],
+            Self::Atom(_) => vec![],
+            Self::And(ref lhs, ref rhs) => vec![Rule::new(
+                "\u{2227}L",
+                [assume(
+                    &context,
+                    [lhs.as_ref().clone(), rhs.as_ref().clone()],
+                )],
+            )],
+            Self::Or(ref lhs, ref rhs) => vec![Rule::new(
+                "\u{2228}L",
+                [
+                    assume(&context, [lhs.as_ref().clone()]),
+                    assume(&context, [rhs.as_ref().clone()]),
+                ],
+            )],
+            Self::Implies(ref lhs, ref rhs) => vec![Rule::new(
+                "\u{2192}L",
+                [
+                    assume(&context, [self.clone()]).with_rhs(lhs.as_ref().clone()),
+                    assume(&context, [rhs.as_ref().clone()]),
+                ],
+            )],
+        }
+    }
+
+    #[inline]
+    fn right(
+        &self,
+        context: IntuitionistWithExchange<Self>,
+    ) -> Vec<Rule<IntuitionistWithExchange<Self>>> {
+        match *self {
+            Self::Bottom | Self::Atom(_) => vec![],
+            Self::And(ref lhs, ref rhs) => vec![Rule::new(
+                "\u{2227}R",
+                [
+                    context.with_rhs(lhs.as_ref().clone()),
+                    context.with_rhs(rhs.as_ref().clone()),
+                ],
+            )],
+            Self::Or(ref lhs, ref rhs) => vec![
+                Rule::new("\u{2228}R1", [context.with_rhs(lhs.as_ref().clone())]),
+                Rule::new("\u{2228}R2", [context.with_rhs(rhs.as_ref().clone())]),
+            ],
+            Self::Implies(ref lhs, ref rhs) => vec![Rule::new(
+                "\u{2192}R",
+                [assume(&context, [lhs.as_ref().clone()]).with_rhs(rhs.as_ref().clone())],
+            )],
+        }
+    }
+}
+
+// Curry-Howard: each assumption on the left stands for a term, and each rule builds one for the right.
+impl Extract<IntuitionistWithExchange<Self>> for Formula {
+    /// # Panics
+    /// If the tree doesn't follow the rules above.
+    #[inline]
+    fn extract(tree: &Tree<IntuitionistWithExchange<Self>>) -> Term {
+        let mut expanded = tree.clone();
+        expanded.expand_shared();
+        extract_with(&expanded, &BTreeMap::new(), &mut 0)
+    }
+}
+
+/// Term for the proof `tree`, given a term for each assumption on its left
+/// and the first variable number not yet bound.
+#[inline]
+fn extract_with(
+    tree: &Tree<IntuitionistWithExchange<Formula>>,
+    terms: &BTreeMap<Formula, Term>,
+    next: &mut usize,
+) -> Term {
+    let premise = |sequent: &IntuitionistWithExchange<Formula>| {
+        tree.above
+            .iter()
+            .find(|above| above.below == *sequent)
+            .unwrap_or_else(|| panic!("no proof of {sequent} above {}", tree.below))
+    };
+    let fresh = |next: &mut usize| {
+        let var = *next;
+        *next = next.saturating_add(1);
+        var
+    };
+    let Some(principal) = tree.principal.as_ref() else {
+        panic!("no principal formula for {}", tree.rule);
+    };
+    let formula = &principal.item;
+    match principal.side {
+        Side::Left => {
+            let term = terms
+                .get(formula)
+                .unwrap_or_else(|| panic!("no term for the assumption {formula}"))
+                .clone();
+            let mut context = tree.below.clone();
+            let _ = context.lhs.take(formula);
+            match *formula {
+                Formula::Bottom => Term::Absurd(Box::new(term)),
+                Formula::Atom(_) => term,
+                Formula::And(ref lhs, ref rhs) => {
+                    let mut inner = terms.clone();
+                    let _ = inner
+                        .entry(lhs.as_ref().clone())
+                        .or_insert_with(|| Term::Fst(Box::new(term.clone())));
+                    let _ = inner
+                        .entry(rhs.as_ref().clone())
+                        .or_insert_with(|| Term::Snd(Box::new(term.clone())));
+                    let above = assume(&context, [lhs.as_ref().clone(), rhs.as_ref().clone()]);
+                    extract_with(premise(&above), &inner, next)
+                }
+                Formula::Or(ref lhs, ref rhs) => {
+                    let (left, right) = (fresh(next), fresh(next));
+                    let mut linner = terms.clone();
+                    let _ = linner
+                        .entry(lhs.as_ref().clone())
+                        .or_insert(Term::Var(left));
+                    let mut rinner = terms.clone();
+                    let _ = rinner
+                        .entry(rhs.as_ref().clone())
+                        .or_insert(Term::Var(right));
+                    Term::Case(
+                        Box::new(term),
+                        left,
+                        Box::new(extract_with(
+                            premise(&assume(&context, [lhs.as_ref().clone()])),
+                            &linner,
+                            next,
+                        )),
+                        right,
+                        Box::new(extract_with(
+                            premise(&assume(&context, [rhs.as_ref().clone()])),
+                            &rinner,
+                            next,
+                        )),
+                    )
+                }
+                Formula::Implies(ref lhs, ref rhs) => {
+                    let arg = extract_with(
+                        premise(
+                            &assume(&context, [formula.clone()]).with_rhs(lhs.as_ref().clone()),
+                        ),
+                        terms,
+                        next,
+                    );
+                    let mut inner = terms.clone();
+                    let _ = inner
+                        .entry(rhs.as_ref().clone())
+                        .or_insert_with(|| term.app(arg));
+                    extract_with(
+                        premise(&assume(&context, [rhs.as_ref().clone()])),
+                        &inner,
+                        next,
+                    )
+                }
+            }
+        }
+        Side::Right => {
+            let context = &tree.below;
+            match *formula {
+                Formula::Bottom | Formula::Atom(_) => {
+                    panic!("no rule for {formula} on the right")
+                }
+                Formula::And(ref lhs, ref rhs) => Term::Pair(
+                    Box::new(extract_with(
+                        premise(&context.with_rhs(lhs.as_ref().clone())),
+                        terms,
+                        next,
+                    )),
+                    Box::new(extract_with(
+                        premise(&context.with_rhs(rhs.as_ref().clone())),
+                        terms,
+                        next,
+                    )),
+                ),
+                Formula::Or(ref lhs, ref rhs) => {
+                    if tree.rule == "\u{2228}R1" {
+                        Term::Inl(Box::new(extract_with(
+                            premise(&context.with_rhs(lhs.as_ref().clone())),
+                            terms,
+                            next,
+                        )))
+                    } else {
+                        Term::Inr(Box::new(extract_with(
+                            premise(&context.with_rhs(rhs.as_ref().clone())),
+                            terms,
+                            next,
+                        )))
+                    }
+                }
+                Formula::Implies(ref lhs, ref rhs) => {
+                    let var = fresh(next);
+                    let mut inner = terms.clone();
+                    let _ = inner.entry(lhs.as_ref().clone()).or_insert(Term::Var(var));
+                    let above =
+                        assume(context, [lhs.as_ref().clone()]).with_rhs(rhs.as_ref().clone());
+                    Term::lam(var, extract_with(premise(&above), &inner, next))
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn prove_identity() {
+    let a = Formula::Atom(0);
+    let proof = a.clone().implies(a).prove().unwrap();
+    assert_eq!(proof.rule, "\u{2192}R");
+}
+
+#[test]
+fn prove_conjunction_elimination() {
+    let (a, b) = (Formula::Atom(0), Formula::Atom(1));
+    (a.clone() & b).implies(a).prove().unwrap();
+}
+
+#[test]
+fn prove_double_negated_excluded_middle() {
+    let a = Formula::Atom(0);
+    (a.clone() | a.not()).not().not().prove().unwrap();
+}
+
+#[test]
+fn cant_prove_excluded_middle() {
+    let a = Formula::Atom(0);
+    assert_eq!((a.clone() | a.not()).prove(), Err(Error::RanOutOfPaths));
+}
+
+#[test]
+fn prove_modus_ponens_from_assumptions() {
+    let (a, b) = (Formula::Atom(0), Formula::Atom(1));
+    let assumptions = [a.clone().implies(b.clone()), a.clone()];
+    let goal = IntuitionistWithExchange::new(assumptions.into_iter().collect(), b.clone());
+    let proof = prove_sequent(goal.clone()).unwrap();
+    assert_eq!(proof.below, goal);
+    proof.validate().unwrap();
+    assert_eq!(
+        prove_sequent(IntuitionistWithExchange::new([a].into_iter().collect(), b)),
+        Err(Error::RanOutOfPaths)
+    );
+}
+
+#[test]
+fn cant_prove_double_negation_elimination() {
+    let a = Formula::Atom(0);
+    assert_eq!(
+        a.clone().not().not().implies(a).prove(),
+        Err(Error::RanOutOfPaths),
+    );
+}
+
+#[test]
+fn cant_prove_peirces_law() {
+    let (a, b) = (Formula::Atom(0), Formula::Atom(1));
+    assert_eq!(
+        a.clone().implies(b).implies(a.clone()).implies(a).prove(),
+        Err(Error::RanOutOfPaths),
+    );
+}
+
+#[test]
+fn prove_disjunction_commutes() {
+    let (a, b) = (Formula::Atom(0), Formula::Atom(1));
+    (a.clone() | b.clone()).implies(b | a).prove().unwrap();
+}
+
+#[test]
+fn bottom_on_the_left_proves_anything() {
+    Formula::Bottom.implies(Formula::Atom(0)).prove().unwrap();
+}
+
+#[test]
+fn proofs_validate() {
+    let (a, b) = (Formula::Atom(0), Formula::Atom(1));
+    for formula in [
+        a.clone().implies(a.clone()),
+        (a.clone() & b.clone()).implies(a.clone()),
+        (a.clone() | b.clone()).implies(b | a.clone()),
+        (a.clone() | a.not()).not().not(),
+    ] {
+        formula.prove().unwrap().validate().unwrap();
+    }
+}
+
+#[test]
+fn sequents_display_both_sides() {
+    let (a, b) = (Formula::Atom(0), Formula::Atom(1));
+    let sequent = IntuitionistWithExchange::new([a.clone(), b].into_iter().collect(), a.clone());
+    assert_eq!(sequent.to_string(), "p0, p1 \u{22a2} p0");
+    assert_eq!(
+        IntuitionistWithExchange::new(gentzen::Multiset::new(), a).to_string(),
+        "\u{22a2} p0"
+    );
+}
+
+#[test]
+fn sequents_render_both_sides() {
+    let (a, b) = (Formula::Atom(0), Formula::Atom(1));
+    let sequent =
+        IntuitionistWithExchange::new([a.clone().implies(b.clone()), a].into_iter().collect(), b);
+    assert_eq!(sequent.render(&Unicode), sequent.to_string());
+    assert_eq!(sequent.render(&Ascii), "p0, (p0 -> p1) |- p1");
+    assert_eq!(
+        sequent.render(&Latex::default()),
+        r"p0, (p0 \to p1) \vdash p1"
+    );
+}
+
+#[test]
+fn extract_k_combinator() {
+    let (a, b) = (Formula::Atom(0), Formula::Atom(1));
+    let proof = a.clone().implies(b.implies(a)).prove().unwrap();
+    assert_eq!(
+        Formula::extract(&proof).alpha_normalized().to_string(),
+        "\u{3bb}a. \u{3bb}b. a"
+    );
+}
+
+#[test]
+fn extract_s_combinator() {
+    let (a, b, c) = (Formula::Atom(0), Formula::Atom(1), Formula::Atom(2));
+    let proof = a
+        .clone()
+        .implies(b.clone().implies(c.clone()))
+        .implies(a.clone().implies(b).implies(a.implies(c)))
+        .prove()
+        .unwrap();
+    assert_eq!(
+        Formula::extract(&proof).alpha_normalized().to_string(),
+        "\u{3bb}a. \u{3bb}b. \u{3bb}c. a c (b c)"
+    );
+}
+
+#[test]
+fn extract_pairs_and_cases() {
+    let (a, b) = (Formula::Atom(0), Formula::Atom(1));
+    let swap = (a.clone() & b.clone()).implies(b.clone() & a.clone());
+    assert_eq!(
+        Formula::extract(&swap.prove().unwrap())
+            .alpha_normalized()
+            .to_string(),
+        "\u{3bb}a. (snd a, fst a)"
+    );
+    let commute = (a.clone() | b.clone()).implies(b | a);
+    assert_eq!(
+        Formula::extract(&commute.prove().unwrap())
+            .alpha_normalized()
+            .to_string(),
+        "\u{3bb}a. case a of inl b \u{21d2} inr b | inr c \u{21d2} inl c"
+    );
+}
@@ -0,0 +1,186 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! A tiny Lambek-style categorial grammar as one-sided non-commutative (cyclic) linear logic.
+
+#![deny(warnings)]
+#![allow(clippy::needless_borrowed_reference)]
+
+use gentzen::prelude::*;
+
+fn main() {
+    let sleeps = Category::NOUN_PHRASE.under(Category::SENTENCE);
+    println!(
+        "{}",
+        parse(&[Category::NOUN_PHRASE, sleeps], Category::SENTENCE).unwrap()
+    );
+}
+
+/// Grammatical category, i.e. a formula of non-commutative multiplicative linear logic.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Category {
+    /// Basic category identified by number (for efficient comparison).
+    Atom(usize),
+    /// Dual of a basic category.
+    Dual(usize),
+    /// Multiplicative conjunction (concatenation).
+    Times(Box<Self>, Box<Self>),
+    /// Multiplicative disjunction.
+    Par(Box<Self>, Box<Self>),
+}
+
+impl core::fmt::Display for Category {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::Atom(i) => write!(f, "c{i}"),
+            Self::Dual(i) => write!(f, "c{i}\u{22a5}"),
+            Self::Times(ref lhs, ref rhs) => write!(f, "({lhs} \u{2297} {rhs})"),
+            Self::Par(ref lhs, ref rhs) => write!(f, "({lhs} \u{214b} {rhs})"),
+        }
+    }
+}
+
+impl Category {
+    /// Noun phrase.
+    pub const NOUN_PHRASE: Self = Self::Atom(0);
+    /// Sentence.
+    pub const SENTENCE: Self = Self::Atom(1);
+
+    /// Linear negation, which reverses the order of everything underneath.
+    #[must_use]
+    #[inline]
+    pub fn dual(&self) -> Self {
+        match *self {
+            Self::Atom(i) => Self::Dual(i),
+            Self::Dual(i) => Self::Atom(i),
+            Self::Times(ref lhs, ref rhs) => Self::Par(Box::new(rhs.dual()), Box::new(lhs.dual())),
+            Self::Par(ref lhs, ref rhs) => Self::Times(Box::new(rhs.dual()), Box::new(lhs.dual())),
+        }
+    }
+
+    /// Lambek's `self \ result`: something that becomes `result` with `self` to its left.
+    #[must_use]
+    #[inline]
+    pub fn under(&self, result: Self) -> Self {
+        Self::Par(Box::new(self.dual()), Box::new(result))
+    }
+
+    /// Lambek's `self / argument`: something that becomes `self` with `argument` to its right.
+    #[must_use]
+    #[inline]
+    pub fn over(self, argument: &Self) -> Self {
+        Self::Par(Box::new(self), Box::new(argument.dual()))
+    }
+}
+
+/// Whether these words, in this order, form a phrase of the goal category.
+/// # Errors
+/// If they don't.
+#[inline]
+pub fn parse(words: &[Category], goal: Category) -> Result<Tree<RhsOnlyOrdered<Category>>, Error> {
+    let Some((first, rest)) = words.split_first() else {
+        return prove(goal);
+    };
+    let phrase = rest.iter().fold(first.clone(), |acc, word| {
+        Category::Times(Box::new(acc), Box::new(word.clone()))
+    });
+    prove(Category::Par(Box::new(phrase.dual()), Box::new(goal)))
+}
+
+impl Infer<RhsOnlyOrdered<Self>> for Category {
+    #[inline]
+    fn above(&self, context: RhsOnlyOrdered<Self>) -> Vec<Rule<RhsOnlyOrdered<Self>>> {
+        match *self {
+            Self::Atom(_) | Self::Dual(_) => match context.rhs.as_slice() {
+                [ref other] if *other == self.dual() => vec![Rule::new("axiom", [])],
+                _ => vec![],
+            },
+            // Everything else comes after `self` cyclically, so `⊢ Γ, A ⊗ B, Δ` arrives as `Δ, Γ`.
+            Self::Times(ref lhs, ref rhs) => context
+                .splits()
+                .map(|(delta, gamma)| {
+                    Rule::new(
+                        "\u{2297}",
+                        [
+                            gamma.with([lhs.as_ref().clone()]),
+                            delta.with([rhs.as_ref().clone()]),
+                        ],
+                    )
+                })
+                .collect(),
+            Self::Par(ref lhs, ref rhs) => vec![Rule::new(
+                "\u{214b}",
+                [context.with([lhs.as_ref().clone(), rhs.as_ref().clone()])],
+            )],
+        }
+    }
+}
+
+#[test]
+fn subject_then_verb() {
+    let sleeps = Category::NOUN_PHRASE.under(Category::SENTENCE);
+    parse(&[Category::NOUN_PHRASE, sleeps], Category::SENTENCE).unwrap();
+}
+
+#[test]
+fn verb_then_subject_needs_exchange() {
+    let sleeps = Category::NOUN_PHRASE.under(Category::SENTENCE);
+    assert_eq!(
+        parse(&[sleeps, Category::NOUN_PHRASE], Category::SENTENCE),
+        Err(Error::RanOutOfPaths),
+    );
+}
+
+#[test]
+fn transitive_verb() {
+    let likes = Category::NOUN_PHRASE
+        .under(Category::SENTENCE)
+        .over(&Category::NOUN_PHRASE);
+    parse(
+        &[Category::NOUN_PHRASE, likes.clone(), Category::NOUN_PHRASE],
+        Category::SENTENCE,
+    )
+    .unwrap();
+    assert_eq!(
+        parse(
+            &[Category::NOUN_PHRASE, Category::NOUN_PHRASE, likes],
+            Category::SENTENCE,
+        ),
+        Err(Error::RanOutOfPaths),
+    );
+}
+
+#[test]
+fn dual_is_an_involution() {
+    let likes = Category::NOUN_PHRASE
+        .under(Category::SENTENCE)
+        .over(&Category::NOUN_PHRASE);
+    assert_eq!(likes.dual().dual(), likes);
+}
+
+#[test]
+fn sampling_rotates_instead_of_reordering() {
+    use gentzen::Sequent;
+    let sequent = RhsOnlyOrdered::new(vec![
+        Category::Atom(0),
+        Category::Atom(1),
+        Category::Atom(2),
+    ]);
+    let samples: Vec<_> = sequent.sample().collect();
+    assert_eq!(samples.len(), 3);
+    let (ref item, ref context) = samples[1];
+    assert_eq!(*item, Category::Atom(1));
+    assert_eq!(context.rhs, vec![Category::Atom(2), Category::Atom(0)]);
+}
+
+#[test]
+fn shorter_sequents_come_first() {
+    let short = RhsOnlyOrdered::new(vec![Category::Atom(9)]);
+    let long = RhsOnlyOrdered::new(vec![Category::Atom(0), Category::Atom(0)]);
+    assert!(short < long);
+}
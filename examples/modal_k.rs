@@ -0,0 +1,316 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! The basic modal logic K with labelled sequent-calculus proof search built in.
+
+#![deny(warnings)]
+#![allow(clippy::needless_borrowed_reference)]
+
+use gentzen::prelude::*;
+
+fn main() {
+    let (a, b) = (Formula::Atom(0), Formula::Atom(1));
+    println!(
+        "{}",
+        a.clone()
+            .implies(b.clone())
+            .boxed()
+            .implies(a.boxed().implies(b.boxed()))
+            .prove()
+            .unwrap()
+    );
+}
+
+/// Propositional modal formula.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Formula {
+    /// Falsehood.
+    Bottom,
+    /// Propositional variable identified by number (for efficient comparison).
+    Atom(usize),
+    /// Negation.
+    Not(Box<Self>),
+    /// Conjunction.
+    And(Box<Self>, Box<Self>),
+    /// Disjunction.
+    Or(Box<Self>, Box<Self>),
+    /// Implication.
+    Implies(Box<Self>, Box<Self>),
+    /// Necessity, i.e. truth at every accessible world.
+    Box(Box<Self>),
+    /// Possibility, i.e. truth at some accessible world.
+    Diamond(Box<Self>),
+}
+
+impl core::fmt::Display for Formula {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::Bottom => write!(f, "\u{22a5}"),
+            Self::Atom(i) => write!(f, "p{i}"),
+            Self::Not(ref arg) => write!(f, "\u{ac}{arg}"),
+            Self::And(ref lhs, ref rhs) => write!(f, "({lhs} \u{2227} {rhs})"),
+            Self::Or(ref lhs, ref rhs) => write!(f, "({lhs} \u{2228} {rhs})"),
+            Self::Implies(ref lhs, ref rhs) => write!(f, "({lhs} \u{2192} {rhs})"),
+            Self::Box(ref arg) => write!(f, "\u{25a1}{arg}"),
+            Self::Diamond(ref arg) => write!(f, "\u{25c7}{arg}"),
+        }
+    }
+}
+
+impl Formula {
+    /// Implication, since there's no operator for it.
+    #[must_use]
+    #[inline(always)]
+    pub fn implies(self, rhs: Self) -> Self {
+        Self::Implies(Box::new(self), Box::new(rhs))
+    }
+
+    /// Necessity, since there's no operator for it.
+    #[must_use]
+    #[inline(always)]
+    pub fn boxed(self) -> Self {
+        Self::Box(Box::new(self))
+    }
+
+    /// Possibility, since there's no operator for it.
+    #[must_use]
+    #[inline(always)]
+    pub fn diamond(self) -> Self {
+        Self::Diamond(Box::new(self))
+    }
+
+    /// Attempt to prove this formula at an arbitrary world, returning the whole derivation if we can.
+    /// # Errors
+    /// If we can't.
+    #[inline(always)]
+    pub fn prove(self) -> Result<Tree<Labelled<Self>>, Error> {
+        prove(Sided::right(Element::at(0, self)))
+    }
+}
+
+impl core::ops::BitAnd<Self> for Formula {
+    type Output = Self;
+    #[inline(always)]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self::And(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl core::ops::BitOr<Self> for Formula {
+    type Output = Self;
+    #[inline(always)]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self::Or(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl core::ops::Not for Formula {
+    type Output = Self;
+    #[inline(always)]
+    fn not(self) -> Self::Output {
+        Self::Not(Box::new(self))
+    }
+}
+
+/// Every world accessible from `world` where `formula` isn't already on this side,
+/// each labelling a copy of `formula`.
+#[inline]
+fn spread(
+    context: &Labelled<Formula>,
+    world: usize,
+    formula: &Formula,
+    side: &Multiset<Element<Formula>>,
+) -> Vec<Element<Formula>> {
+    context
+        .accessible_from(world)
+        .map(|to| Element::at(to, formula.clone()))
+        .filter(|element| !side.contains(element))
+        .collect()
+}
+
+// Context-sharing rules for K (G3K), where boxes on the left and diamonds on the right stay put,
+// so they reach every accessible world, including those only discovered later.
+impl InferLabelled for Formula {
+    #[inline]
+    fn left(&self, world: usize, context: Labelled<Self>) -> Vec<Rule<Labelled<Self>>> {
+        match *self {
+            Self::Bottom => vec![Rule::new("\u{22a5}L", [])],
+            Self::Atom(_) if context.rhs.contains(&Element::at(world, self.clone())) => {
+                vec![Rule::new("axiom", [])]
+            }
+            Self::Atom(_) => vec![],
+            Self::Not(ref arg) => vec![Rule::new(
+                "\u{ac}L",
+                [context.with_rhs([Element::at(world, arg.as_ref().clone())])],
+            )],
+            Self::And(ref lhs, ref rhs) => vec![Rule::new(
+                "\u{2227}L",
+                [context.with_lhs([
+                    Element::at(world, lhs.as_ref().clone()),
+                    Element::at(world, rhs.as_ref().clone()),
+                ])],
+            )],
+            Self::Or(ref lhs, ref rhs) => vec![Rule::new(
+                "\u{2228}L",
+                [
+                    context.with_lhs([Element::at(world, lhs.as_ref().clone())]),
+                    context.with_lhs([Element::at(world, rhs.as_ref().clone())]),
+                ],
+            )],
+            Self::Implies(ref lhs, ref rhs) => vec![Rule::new(
+                "\u{2192}L",
+                [
+                    context.with_rhs([Element::at(world, lhs.as_ref().clone())]),
+                    context.with_lhs([Element::at(world, rhs.as_ref().clone())]),
+                ],
+            )],
+            Self::Box(ref arg) => {
+                let new = spread(&context, world, arg, &context.lhs);
+                if new.is_empty() {
+                    return vec![];
+                }
+                vec![Rule::new(
+                    "\u{25a1}L",
+                    [context
+                        .with_lhs(new)
+                        .with_lhs([Element::at(world, self.clone())])],
+                )]
+            }
+            Self::Diamond(ref arg) => {
+                let fresh = context.fresh_world().max(world.saturating_add(1));
+                vec![Rule::new(
+                    "\u{25c7}L",
+                    [context.with_lhs([
+                        Element::Rel(world, fresh),
+                        Element::at(fresh, arg.as_ref().clone()),
+                    ])],
+                )]
+            }
+        }
+    }
+
+    #[inline]
+    fn right(&self, world: usize, context: Labelled<Self>) -> Vec<Rule<Labelled<Self>>> {
+        match *self {
+            Self::Bottom => vec![],
+            Self::Atom(_) if context.lhs.contains(&Element::at(world, self.clone())) => {
+                vec![Rule::new("axiom", [])]
+            }
+            Self::Atom(_) => vec![],
+            Self::Not(ref arg) => vec![Rule::new(
+                "\u{ac}R",
+                [context.with_lhs([Element::at(world, arg.as_ref().clone())])],
+            )],
+            Self::And(ref lhs, ref rhs) => vec![Rule::new(
+                "\u{2227}R",
+                [
+                    context.with_rhs([Element::at(world, lhs.as_ref().clone())]),
+                    context.with_rhs([Element::at(world, rhs.as_ref().clone())]),
+                ],
+            )],
+            Self::Or(ref lhs, ref rhs) => vec![Rule::new(
+                "\u{2228}R",
+                [context.with_rhs([
+                    Element::at(world, lhs.as_ref().clone()),
+                    Element::at(world, rhs.as_ref().clone()),
+                ])],
+            )],
+            Self::Implies(ref lhs, ref rhs) => vec![Rule::new(
+                "\u{2192}R",
+                [context
+                    .with_lhs([Element::at(world, lhs.as_ref().clone())])
+                    .with_rhs([Element::at(world, rhs.as_ref().clone())])],
+            )],
+            Self::Box(ref arg) => {
+                let fresh = context.fresh_world().max(world.saturating_add(1));
+                vec![Rule::new(
+                    "\u{25a1}R",
+                    [context
+                        .with_lhs([Element::Rel(world, fresh)])
+                        .with_rhs([Element::at(fresh, arg.as_ref().clone())])],
+                )]
+            }
+            Self::Diamond(ref arg) => {
+                let new = spread(&context, world, arg, &context.rhs);
+                if new.is_empty() {
+                    return vec![];
+                }
+                vec![Rule::new(
+                    "\u{25c7}R",
+                    [context
+                        .with_rhs(new)
+                        .with_rhs([Element::at(world, self.clone())])],
+                )]
+            }
+        }
+    }
+}
+
+#[test]
+fn prove_distribution() {
+    let (a, b) = (Formula::Atom(0), Formula::Atom(1));
+    let proof = a
+        .clone()
+        .implies(b.clone())
+        .boxed()
+        .implies(a.boxed().implies(b.boxed()))
+        .prove()
+        .unwrap();
+    proof.validate().unwrap();
+    assert!(proof.to_string().contains("\u{25a1}R"));
+}
+
+#[test]
+fn cant_prove_reflexivity() {
+    let a = Formula::Atom(0);
+    assert_eq!(
+        a.clone().boxed().implies(a).prove(),
+        Err(Error::RanOutOfPaths)
+    );
+}
+
+#[test]
+fn cant_prove_seriality() {
+    let a = Formula::Atom(0);
+    assert_eq!(
+        a.clone().boxed().implies(a.diamond()).prove(),
+        Err(Error::RanOutOfPaths)
+    );
+}
+
+#[test]
+fn prove_necessitation_of_a_tautology() {
+    let a = Formula::Atom(0);
+    (a.clone() | !a).boxed().prove().unwrap();
+}
+
+#[test]
+fn prove_diamond_is_dual_to_box() {
+    let a = Formula::Atom(0);
+    a.clone().diamond().implies(!(!a).boxed()).prove().unwrap();
+}
+
+#[test]
+fn fresh_worlds_avoid_every_label() {
+    let a = Formula::Atom(0);
+    let sequent = Labelled::default()
+        .with_lhs([Element::Rel(0, 3)])
+        .with_rhs([Element::at(1, a)]);
+    assert_eq!(sequent.fresh_world(), 4);
+    assert_eq!(Labelled::<Formula>::default().fresh_world(), 0);
+    assert_eq!(sequent.accessible_from(0).collect::<Vec<_>>(), [3]);
+}
+
+#[test]
+fn sequents_display_labels() {
+    let a = Formula::Atom(0);
+    let sequent = Labelled::default()
+        .with_lhs([Element::Rel(0, 1)])
+        .with_rhs([Element::at(1, a)]);
+    assert_eq!(sequent.to_string(), "w0Rw1 \u{22a2} w1: p0");
+}
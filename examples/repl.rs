@@ -0,0 +1,121 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Prove linear-logic formulas one inference at a time, typing commands at a prompt.
+//! Everything but reading and printing lives in `gentzen::interactive::Session`.
+
+#![deny(warnings)]
+#![allow(clippy::needless_borrowed_reference)]
+
+use {
+    gentzen::{interactive::Session, logics::classical_linear::IndexedAst, prelude::*},
+    std::io::{BufRead as _, Write as _},
+};
+
+/// What to type at the prompt.
+const HELP: &str = "\
+prove <formula>   start proving a formula, e.g. `prove P0 -o P0 * 1`
+goals             list open goals, the current one first
+rules             list every rule the current goal offers, numbered
+<focus> <rule>    apply the rule with these numbers to the current goal
+undo              take back the last move
+auto              let proof search finish every open goal
+help              show this message
+quit              leave";
+
+fn main() {
+    println!("{HELP}");
+    let mut session: Option<Session<RhsOnlyWithExchange<IndexedAst>>> = None;
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        print!("> ");
+        drop(std::io::stdout().flush());
+        let Some(Ok(line)) = lines.next() else {
+            break;
+        };
+        let line = line.trim();
+        if line == "quit" {
+            break;
+        }
+        if let Some(formula) = line.strip_prefix("prove ") {
+            match formula.parse::<IndexedAst>() {
+                Ok(ast) => {
+                    session = Some(Session::new(RhsOnlyWithExchange::from_rhs(ast)));
+                    show(session.as_ref());
+                }
+                Err(error) => println!("{error}"),
+            }
+            continue;
+        }
+        let Some(ref mut current) = session else {
+            println!("Nothing to prove yet: try `prove <formula>` (or `help`).");
+            continue;
+        };
+        match line {
+            "goals" => show(Some(current)),
+            "rules" => list_rules(current),
+            "undo" => {
+                if !current.undo() {
+                    println!("Nothing to undo.");
+                }
+                show(Some(current));
+            }
+            "auto" => match current.auto() {
+                Ok(()) => show(Some(current)),
+                Err(error) => println!("{error}"),
+            },
+            "help" => println!("{HELP}"),
+            _ => {
+                let mut numbers = line.split_whitespace().map(str::parse::<usize>);
+                match (numbers.next(), numbers.next(), numbers.next()) {
+                    (Some(Ok(focus)), Some(Ok(rule)), None) => {
+                        if !current.choose(focus, rule) {
+                            println!("No rule {rule} for item {focus}: see `rules`.");
+                        }
+                        show(Some(current));
+                    }
+                    _ => println!("Unrecognized command: see `help`."),
+                }
+            }
+        }
+    }
+}
+
+/// Print every open goal, or the whole proof if there are none.
+fn show(session: Option<&Session<RhsOnlyWithExchange<IndexedAst>>>) {
+    let Some(session) = session else {
+        return;
+    };
+    if let Some(tree) = session.tree() {
+        println!("Proven!\n{tree}");
+        return;
+    }
+    for (i, goal) in session.goals().enumerate() {
+        println!("{}{goal}", if i == 0 { "* " } else { "  " });
+    }
+}
+
+/// Print every rule the current goal offers, numbered as `choose` expects.
+fn list_rules(session: &Session<RhsOnlyWithExchange<IndexedAst>>) {
+    for (focus, (item, rules)) in session.choices().into_iter().enumerate() {
+        if rules.is_empty() {
+            println!("{focus}: {item} (no rules)");
+            continue;
+        }
+        for (index, rule) in rules.into_iter().enumerate() {
+            let premises: Vec<_> = rule.above.iter_repeat().map(ToString::to_string).collect();
+            println!(
+                "{focus} {index}: {item} by {} from {}",
+                rule.name,
+                if premises.is_empty() {
+                    "nothing".to_owned()
+                } else {
+                    premises.join(" and ")
+                },
+            );
+        }
+    }
+}
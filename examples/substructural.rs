@@ -0,0 +1,183 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Linear, affine, and relevant variants of one multiplicative rule set,
+//! differing only in which structural rules the sequent type allows.
+
+#![deny(warnings)]
+#![allow(clippy::needless_borrowed_reference)]
+
+use gentzen::prelude::*;
+
+/// No weakening or contraction.
+pub type Linear = RhsOnlyStructural<Formula, false, false>;
+/// Weakening but no contraction.
+pub type Affine = RhsOnlyStructural<Formula, true, false>;
+/// Contraction but no weakening.
+pub type Relevant = RhsOnlyStructural<Formula, false, true>;
+/// Both weakening and contraction.
+pub type Classical = RhsOnlyStructural<Formula, true, true>;
+
+fn main() {
+    let a = Formula::Atom(0);
+    let copy = a.clone().implies(a.clone() * a);
+    println!("{}", prove::<_, Relevant>(copy).unwrap());
+}
+
+/// Multiplicative formula.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Formula {
+    /// Unit for multiplicative conjunction.
+    One,
+    /// Unit for multiplicative disjunction.
+    Bottom,
+    /// Raw value identified by number (for efficient comparison).
+    Atom(usize),
+    /// Dual of a raw value.
+    Dual(usize),
+    /// Multiplicative conjunction.
+    Times(Box<Self>, Box<Self>),
+    /// Multiplicative disjunction.
+    Par(Box<Self>, Box<Self>),
+}
+
+impl core::fmt::Display for Formula {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::One => write!(f, "1"),
+            Self::Bottom => write!(f, "\u{22a5}"),
+            Self::Atom(i) => write!(f, "p{i}"),
+            Self::Dual(i) => write!(f, "p{i}\u{22a5}"),
+            Self::Times(ref lhs, ref rhs) => write!(f, "({lhs} \u{2297} {rhs})"),
+            Self::Par(ref lhs, ref rhs) => write!(f, "({lhs} \u{214b} {rhs})"),
+        }
+    }
+}
+
+impl Formula {
+    /// Linear negation.
+    #[must_use]
+    #[inline]
+    pub fn dual(&self) -> Self {
+        match *self {
+            Self::One => Self::Bottom,
+            Self::Bottom => Self::One,
+            Self::Atom(i) => Self::Dual(i),
+            Self::Dual(i) => Self::Atom(i),
+            Self::Times(ref lhs, ref rhs) => Self::Par(Box::new(lhs.dual()), Box::new(rhs.dual())),
+            Self::Par(ref lhs, ref rhs) => Self::Times(Box::new(lhs.dual()), Box::new(rhs.dual())),
+        }
+    }
+
+    /// Linear implication, i.e. `self` dual par `rhs`.
+    #[must_use]
+    #[inline]
+    pub fn implies(&self, rhs: Self) -> Self {
+        Self::Par(Box::new(self.dual()), Box::new(rhs))
+    }
+}
+
+impl core::ops::Mul<Self> for Formula {
+    type Output = Self;
+    #[inline(always)]
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::Times(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<const WEAKENING: bool, const CONTRACTION: bool>
+    Infer<RhsOnlyStructural<Self, WEAKENING, CONTRACTION>> for Formula
+{
+    #[inline]
+    fn above(
+        &self,
+        context: RhsOnlyStructural<Self, WEAKENING, CONTRACTION>,
+    ) -> Vec<Rule<RhsOnlyStructural<Self, WEAKENING, CONTRACTION>>> {
+        match *self {
+            Self::One if context.is_empty() => vec![Rule::new("1", [])],
+            Self::Atom(_) | Self::Dual(_) if context.only() == Some(&self.dual()) => {
+                vec![Rule::new("axiom", [])]
+            }
+            Self::One | Self::Atom(_) | Self::Dual(_) => vec![],
+            Self::Bottom => vec![Rule::new("\u{22a5}", [context])],
+            Self::Times(ref lhs, ref rhs) => context
+                .rhs
+                .partitions()
+                .map(|(lctx, rctx)| {
+                    Rule::new(
+                        "\u{2297}",
+                        [
+                            RhsOnlyStructural::new(lctx.with([lhs.as_ref().clone()])),
+                            RhsOnlyStructural::new(rctx.with([rhs.as_ref().clone()])),
+                        ],
+                    )
+                })
+                .collect(),
+            Self::Par(ref lhs, ref rhs) => vec![Rule::new(
+                "\u{214b}",
+                [context.with([lhs.as_ref().clone(), rhs.as_ref().clone()])],
+            )],
+        }
+    }
+}
+
+#[test]
+fn copying_needs_contraction() {
+    let a = Formula::Atom(0);
+    let copy = a.clone().implies(a.clone() * a);
+    assert_eq!(prove::<_, Linear>(copy.clone()), Err(Error::RanOutOfPaths));
+    assert_eq!(prove::<_, Affine>(copy.clone()), Err(Error::RanOutOfPaths));
+    let proof = prove::<_, Relevant>(copy).unwrap();
+    assert!(proof.to_string().contains('C'));
+}
+
+#[test]
+fn discarding_needs_weakening() {
+    let discard = Formula::Atom(0).implies(Formula::One);
+    assert_eq!(
+        prove::<_, Linear>(discard.clone()),
+        Err(Error::RanOutOfPaths)
+    );
+    // Contraction alone could keep copying the atom forever, but the search caps how many copies it looks at.
+    assert_eq!(
+        prove::<_, Relevant>(discard.clone()),
+        Err(Error::Incomplete {
+            max_width: None,
+            max_copies: Some(4),
+        })
+    );
+    let proof = prove::<_, Affine>(discard).unwrap();
+    assert!(proof.to_string().contains('W'));
+}
+
+#[test]
+fn identity_needs_neither() {
+    let a = Formula::Atom(0);
+    let identity = a.implies(a.clone());
+    prove::<_, Linear>(identity.clone()).unwrap();
+    prove::<_, Affine>(identity.clone()).unwrap();
+    prove::<_, Relevant>(identity).unwrap();
+}
+
+#[test]
+fn subsumption_proves_the_same_goals() {
+    let a = Formula::Atom(0);
+    let discard = a.implies(Formula::One);
+    assert!(
+        prove_with_config::<_, Affine, _>(discard.clone(), SearchConfig::new().subsuming()).is_ok()
+    );
+    assert!(prove_with_config::<_, Classical, _>(discard, SearchConfig::new().subsuming()).is_ok());
+    let copy = a.implies(a.clone() * a.clone());
+    assert!(
+        prove_with_config::<_, Classical, _>(copy.clone(), SearchConfig::new().subsuming()).is_ok()
+    );
+    assert_eq!(
+        prove_with_config::<_, Affine, _>(copy, SearchConfig::new().subsuming()),
+        Err(Error::RanOutOfPaths)
+    );
+}
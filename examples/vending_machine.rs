@@ -0,0 +1,219 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! A vending machine as a linear-logic theory: its prices are non-logical axioms, and coins get used up.
+
+#![deny(warnings)]
+#![allow(clippy::needless_borrowed_reference)]
+
+use gentzen::prelude::*;
+
+fn main() {
+    println!(
+        "{}",
+        buy(&[Item::Coin, Item::Coin], &[Item::Candy, Item::Candy]).unwrap()
+    );
+}
+
+/// Anything you can hold, put in the machine, or get out of it.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Item {
+    /// What everything else costs.
+    Coin,
+    /// Costs one coin.
+    Candy,
+    /// Costs two coins.
+    Gum,
+}
+
+impl core::fmt::Display for Item {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::Coin => write!(f, "coin"),
+            Self::Candy => write!(f, "candy"),
+            Self::Gum => write!(f, "gum"),
+        }
+    }
+}
+
+/// Formula of one-sided multiplicative linear logic over items.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Resource {
+    /// Having an item.
+    Have(Item),
+    /// Owing an item, i.e. the dual of having it.
+    Owe(Item),
+    /// Multiplicative conjunction: both at once.
+    Times(Box<Self>, Box<Self>),
+    /// Multiplicative disjunction.
+    Par(Box<Self>, Box<Self>),
+}
+
+impl core::fmt::Display for Resource {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::Have(item) => write!(f, "{item}"),
+            Self::Owe(item) => write!(f, "{item}\u{22a5}"),
+            Self::Times(ref lhs, ref rhs) => write!(f, "({lhs} \u{2297} {rhs})"),
+            Self::Par(ref lhs, ref rhs) => write!(f, "({lhs} \u{214b} {rhs})"),
+        }
+    }
+}
+
+impl Resource {
+    /// Linear negation.
+    #[must_use]
+    #[inline]
+    pub fn dual(&self) -> Self {
+        match *self {
+            Self::Have(item) => Self::Owe(item),
+            Self::Owe(item) => Self::Have(item),
+            Self::Times(ref lhs, ref rhs) => Self::Par(Box::new(lhs.dual()), Box::new(rhs.dual())),
+            Self::Par(ref lhs, ref rhs) => Self::Times(Box::new(lhs.dual()), Box::new(rhs.dual())),
+        }
+    }
+
+    /// All of these items at once (`None` if there are none).
+    #[must_use]
+    #[inline]
+    pub fn all(items: &[Item]) -> Option<Self> {
+        let (&first, rest) = items.split_first()?;
+        Some(rest.iter().fold(Self::Have(first), |acc, &item| {
+            Self::Times(Box::new(acc), Box::new(Self::Have(item)))
+        }))
+    }
+}
+
+impl Infer<RhsOnlyWithExchange<Self>> for Resource {
+    #[inline]
+    fn above(&self, context: RhsOnlyWithExchange<Self>) -> Vec<Rule<RhsOnlyWithExchange<Self>>> {
+        match *self {
+            Self::Have(_) | Self::Owe(_) => {
+                if context.only() == Some(&self.dual()) {
+                    vec![Rule::new("id", [])]
+                } else {
+                    vec![]
+                }
+            }
+            Self::Times(ref lhs, ref rhs) => context
+                .rhs
+                .partitions()
+                .map(|(gamma, delta)| {
+                    Rule::new(
+                        "\u{2297}",
+                        [
+                            RhsOnlyWithExchange::new(gamma).with([lhs.as_ref().clone()]),
+                            RhsOnlyWithExchange::new(delta).with([rhs.as_ref().clone()]),
+                        ],
+                    )
+                })
+                .collect(),
+            Self::Par(ref lhs, ref rhs) => vec![Rule::new(
+                "\u{214b}",
+                [context.with([lhs.as_ref().clone(), rhs.as_ref().clone()])],
+            )],
+        }
+    }
+}
+
+/// What the machine charges, as one axiom `⊢ coin⊥, ..., coin⊥, item` per item on sale.
+#[inline]
+#[must_use]
+pub fn prices() -> Vec<RhsOnlyWithExchange<Resource>> {
+    [(Item::Candy, 1), (Item::Gum, 2)]
+        .into_iter()
+        .map(|(item, coins)| {
+            let mut rhs: Multiset<Resource> =
+                core::iter::repeat_n(Resource::Owe(Item::Coin), coins).collect();
+            let _ = rhs.insert(Resource::Have(item));
+            RhsOnlyWithExchange::new(rhs)
+        })
+        .collect()
+}
+
+/// Whether spending exactly everything we `pay` can buy exactly everything we `want`.
+/// # Errors
+/// If it can't.
+/// # Panics
+/// If we don't pay or want anything.
+#[inline]
+pub fn buy(pay: &[Item], want: &[Item]) -> Result<Tree<RhsOnlyWithExchange<Resource>>, Error> {
+    let (Some(paid), Some(bought)) = (Resource::all(pay), Resource::all(want)) else {
+        panic!("Nothing to pay with or nothing to buy");
+    };
+    prove_with_axioms(
+        Resource::Par(Box::new(paid.dual()), Box::new(bought)),
+        prices(),
+    )
+}
+
+#[test]
+fn one_coin_one_candy() {
+    let tree = buy(&[Item::Coin], &[Item::Candy]).unwrap();
+    let above: Vec<_> = tree.above.iter().collect();
+    assert_eq!(above.len(), 1);
+    assert_eq!(above[0].rule, "axiom");
+    assert!(above[0].above.is_empty());
+}
+
+#[test]
+fn two_coins_two_candies() {
+    let tree = buy(&[Item::Coin, Item::Coin], &[Item::Candy, Item::Candy]).unwrap();
+    assert!(tree.to_string().contains("axiom"));
+}
+
+#[test]
+fn two_coins_one_gum() {
+    buy(&[Item::Coin, Item::Coin], &[Item::Gum]).unwrap();
+}
+
+#[test]
+fn coins_get_used_up() {
+    assert_eq!(
+        buy(&[Item::Coin], &[Item::Candy, Item::Candy]),
+        Err(Error::RanOutOfPaths),
+    );
+    assert_eq!(
+        buy(&[Item::Coin, Item::Coin], &[Item::Candy, Item::Gum]),
+        Err(Error::RanOutOfPaths),
+    );
+}
+
+#[test]
+fn no_change_given() {
+    assert_eq!(
+        buy(&[Item::Coin, Item::Coin], &[Item::Candy]),
+        Err(Error::RanOutOfPaths),
+    );
+}
+
+#[test]
+fn nothing_for_free_without_prices() {
+    assert_eq!(
+        gentzen::prove::<_, RhsOnlyWithExchange<Resource>>(Resource::Par(
+            Box::new(Resource::Owe(Item::Coin)),
+            Box::new(Resource::Have(Item::Candy)),
+        )),
+        Err(Error::RanOutOfPaths),
+    );
+}
+
+#[test]
+fn goal_can_be_an_axiom() {
+    let tree = prove_with_axioms(
+        Resource::Have(Item::Coin),
+        [RhsOnlyWithExchange::new(
+            [Resource::Have(Item::Coin)].into_iter().collect(),
+        )],
+    )
+    .unwrap();
+    assert_eq!(tree.rule, "axiom");
+    assert!(tree.above.is_empty());
+}
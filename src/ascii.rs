@@ -0,0 +1,42 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Plain-ASCII rendering for terminals and logs that mangle Unicode.
+
+use alloc::string::String;
+
+/// Translation of Unicode symbols into plain ASCII, used by the alternate form (`{:#}`) of `Display`.
+pub const ASCII_SYMBOLS: &[(char, &str)] = &[
+    ('\u{22a2}', "|-"),
+    ('\u{2297}', "*"),
+    ('\u{214b}', "par"),
+    ('\u{2295}', "+"),
+    ('\u{22a4}', "top"),
+    ('\u{22a5}', "_|_"),
+    ('\u{22b8}', "-o"),
+    ('\u{ac}', "~"),
+    ('\u{2227}', "/\\"),
+    ('\u{2228}', "\\/"),
+    ('\u{2192}', "->"),
+    ('\u{d7}', "x"),
+];
+
+/// Replace every symbol in `ASCII_SYMBOLS`, and escape anything else outside ASCII as `\u{...}`.
+#[inline]
+#[must_use]
+pub(crate) fn to_ascii(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if c.is_ascii() {
+            out.push(c);
+        } else if let Some(&(_, ascii)) = ASCII_SYMBOLS.iter().find(|&&(symbol, _)| symbol == c) {
+            out.push_str(ascii);
+        } else {
+            out.extend(c.escape_unicode());
+        }
+    }
+    out
+}
@@ -0,0 +1,108 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Which rules an `Infer` impl ever offers, and which formulas get none at all.
+
+use crate::{step::apply, Infer, Sequent};
+use alloc::{
+    borrow::Cow,
+    collections::BTreeSet,
+    string::{String, ToString as _},
+    vec::Vec,
+};
+
+/// Every rule each sampled formula offered on its own, e.g. to spot rules that never fire
+/// or connectives that were never given a rule.
+#[allow(clippy::exhaustive_structs)]
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct AuditReport {
+    /// Each sample as printed, in the order given,
+    /// with the name of every rule it offered (in the order `Infer::above` returned them, each once).
+    pub samples: Vec<(String, Vec<Cow<'static, str>>)>,
+}
+
+impl AuditReport {
+    /// Name of every rule any sample offered.
+    #[inline]
+    #[must_use]
+    pub fn fired(&self) -> BTreeSet<&str> {
+        self.samples
+            .iter()
+            .flat_map(|&(_, ref rules)| rules.iter().map(AsRef::as_ref))
+            .collect()
+    }
+
+    /// Every sample that offered no rule at all, in the order given.
+    #[inline]
+    pub fn stuck(&self) -> impl Iterator<Item = &str> + '_ {
+        self.samples
+            .iter()
+            .filter(|&&(_, ref rules)| rules.is_empty())
+            .map(|&(ref sample, _)| sample.as_str())
+    }
+
+    /// Name of every rule the sample printed as `sample` offered, or `None` if it wasn't sampled.
+    #[inline]
+    #[must_use]
+    pub fn rules_for(&self, sample: &str) -> Option<&[Cow<'static, str>]> {
+        self.samples
+            .iter()
+            .find(|&&(ref printed, _)| printed == sample)
+            .map(|&(_, ref rules)| rules.as_slice())
+    }
+}
+
+impl core::fmt::Display for AuditReport {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let header = "sample";
+        let width = self
+            .samples
+            .iter()
+            .map(|&(ref sample, _)| sample.chars().count())
+            .fold(header.len(), usize::max);
+        writeln!(f, "{header:<width$}  rules")?;
+        for &(ref sample, ref rules) in &self.samples {
+            write!(f, "{sample:<width$}  ")?;
+            if rules.is_empty() {
+                writeln!(f, "(none)")?;
+            } else {
+                writeln!(f, "{}", rules.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run `Infer::above` on each sample alone in a sequent (see `Sequent::from_rhs`)
+/// and record which rules it offered.
+///
+/// There's no way to list an `Infer` impl's constructors generically,
+/// so `samples` should include at least one formula for each (with subformulas of your choice).
+#[inline]
+#[must_use]
+pub fn audit<I: Infer<S> + core::fmt::Display, S: Sequent<Item = I>, T: IntoIterator<Item = I>>(
+    samples: T,
+) -> AuditReport {
+    AuditReport {
+        samples: samples
+            .into_iter()
+            .map(|sample| {
+                let printed = sample.to_string();
+                let mut rules: Vec<Cow<'static, str>> = Vec::new();
+                for rule in apply(&S::from_rhs(sample))
+                    .into_iter()
+                    .flat_map(|(_, offered)| offered)
+                {
+                    if !rules.contains(&rule.name) {
+                        rules.push(rule.name);
+                    }
+                }
+                (printed, rules)
+            })
+            .collect(),
+    }
+}
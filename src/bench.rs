@@ -0,0 +1,228 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Inputs for the benchmarks in `benches/`, which can't reach the examples or crate internals.
+//! Not part of the stable API: this can change at any time.
+
+use crate::{
+    parallel::{acting_on_each, acting_on_each_serially},
+    sequents::RhsOnlyWithExchange,
+    thunk::Thunk,
+    Infer, Rule, Sequent as _,
+};
+use alloc::{boxed::Box, vec, vec::Vec};
+
+/// Multiplicative-additive linear logic formula, as small as proof search can still be interesting on.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Formula {
+    /// Unit for multiplicative conjunction.
+    One,
+    /// Unit for multiplicative disjunction.
+    Bottom,
+    /// Unit for additive conjunction.
+    Top,
+    /// Unit for additive disjunction.
+    Zero,
+    /// Propositional atom identified by number.
+    Atom(usize),
+    /// Dual of a propositional atom.
+    Dual(usize),
+    /// Multiplicative conjunction.
+    Times(Box<Self>, Box<Self>),
+    /// Multiplicative disjunction.
+    Par(Box<Self>, Box<Self>),
+    /// Additive conjunction.
+    With(Box<Self>, Box<Self>),
+    /// Additive disjunction.
+    Plus(Box<Self>, Box<Self>),
+}
+
+impl core::fmt::Display for Formula {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::One => write!(f, "1"),
+            Self::Bottom => write!(f, "\u{22a5}"),
+            Self::Top => write!(f, "\u{22a4}"),
+            Self::Zero => write!(f, "0"),
+            Self::Atom(i) => write!(f, "p{i}"),
+            Self::Dual(i) => write!(f, "~p{i}"),
+            Self::Times(ref lhs, ref rhs) => write!(f, "({lhs} \u{2297} {rhs})"),
+            Self::Par(ref lhs, ref rhs) => write!(f, "({lhs} \u{214b} {rhs})"),
+            Self::With(ref lhs, ref rhs) => write!(f, "({lhs} & {rhs})"),
+            Self::Plus(ref lhs, ref rhs) => write!(f, "({lhs} \u{2295} {rhs})"),
+        }
+    }
+}
+
+impl Formula {
+    /// Dual of this formula, pushed all the way down to the atoms.
+    #[must_use]
+    #[inline]
+    pub fn dual(&self) -> Self {
+        match *self {
+            Self::One => Self::Bottom,
+            Self::Bottom => Self::One,
+            Self::Top => Self::Zero,
+            Self::Zero => Self::Top,
+            Self::Atom(i) => Self::Dual(i),
+            Self::Dual(i) => Self::Atom(i),
+            Self::Times(ref lhs, ref rhs) => Self::Par(Box::new(lhs.dual()), Box::new(rhs.dual())),
+            Self::Par(ref lhs, ref rhs) => Self::Times(Box::new(lhs.dual()), Box::new(rhs.dual())),
+            Self::With(ref lhs, ref rhs) => Self::Plus(Box::new(lhs.dual()), Box::new(rhs.dual())),
+            Self::Plus(ref lhs, ref rhs) => Self::With(Box::new(lhs.dual()), Box::new(rhs.dual())),
+        }
+    }
+
+    /// Linear implication, i.e. the dual of `self` par `rhs`.
+    #[must_use]
+    #[inline]
+    pub fn lollipop(&self, rhs: Self) -> Self {
+        Self::Par(Box::new(self.dual()), Box::new(rhs))
+    }
+}
+
+// One-sided rules, as in the classical linear logic example.
+impl Infer<RhsOnlyWithExchange<Self>> for Formula {
+    #[inline]
+    fn above(&self, context: RhsOnlyWithExchange<Self>) -> Vec<Rule<RhsOnlyWithExchange<Self>>> {
+        if context.rhs.contains(&Self::Top)
+            || context
+                .rhs
+                .exactly()
+                .is_some_and(|[other]| *other == self.dual())
+        {
+            return vec![Rule::new("axiom", [])];
+        }
+        match *self {
+            Self::Top => vec![Rule::invertible("\u{22a4}", [])],
+            Self::One if context.is_empty() => vec![Rule::new("1", [])],
+            Self::One | Self::Zero | Self::Atom(_) | Self::Dual(_) => vec![],
+            Self::Bottom => vec![Rule::invertible("\u{22a5}", [context])],
+            Self::Times(ref lhs, ref rhs) => context
+                .rhs
+                .partitions()
+                .map(|(lctx, rctx)| {
+                    Rule::new(
+                        "\u{2297}",
+                        [
+                            RhsOnlyWithExchange::new(lctx.with([lhs.as_ref().clone()])),
+                            RhsOnlyWithExchange::new(rctx.with([rhs.as_ref().clone()])),
+                        ],
+                    )
+                })
+                .collect(),
+            Self::Par(ref lhs, ref rhs) => vec![Rule::invertible(
+                "\u{214b}",
+                [context.with([lhs.as_ref().clone(), rhs.as_ref().clone()])],
+            )],
+            Self::With(ref lhs, ref rhs) => vec![Rule::invertible(
+                "&",
+                [
+                    context.with([lhs.as_ref().clone()]),
+                    context.with([rhs.as_ref().clone()]),
+                ],
+            )],
+            Self::Plus(ref lhs, ref rhs) => vec![
+                Rule::new("+L", [context.with([lhs.as_ref().clone()])]),
+                Rule::new("+R", [context.with([rhs.as_ref().clone()])]),
+            ],
+        }
+    }
+}
+
+/// `1 & 1 & ... & 1` with `n` ones, which splits into `n` identical branches.
+#[must_use]
+#[inline]
+pub fn with_chain(n: usize) -> Formula {
+    (1..n).fold(Formula::One, |acc, _| {
+        Formula::With(Box::new(Formula::One), Box::new(acc))
+    })
+}
+
+/// `p0 ⊗ p1 ⊗ ... ⊗ p(n - 1) ⅋ ~p0 ⅋ ~p1 ⅋ ... ⅋ ~p(n - 1)`,
+/// which tries every way to split the duals between the atoms.
+#[must_use]
+#[inline]
+pub fn times_chain(n: usize) -> Formula {
+    against_duals(n.saturating_sub(1), n)
+}
+
+/// `1 ⊸ 1 ⊸ ... ⊸ 1 ⊗ 1` with `n` implications,
+/// which leaves `n` bottoms to clear before the tensor can split an empty context.
+#[must_use]
+#[inline]
+pub fn lollipop_nesting(n: usize) -> Formula {
+    let conclusion = Formula::Times(Box::new(Formula::One), Box::new(Formula::One));
+    (0..n).fold(conclusion, |acc, _| Formula::One.lollipop(acc))
+}
+
+/// `p0 ⊗ p1 ⊗ ... ⊗ pn ⅋ ~p0 ⅋ ~p1 ⅋ ... ⅋ ~p(n - 1)`, which is one dual short of provable,
+/// so search has to try every way to split the context before giving up.
+#[must_use]
+#[inline]
+pub fn unprovable(n: usize) -> Formula {
+    against_duals(n, n)
+}
+
+/// `p0 ⊗ p1 ⊗ ... ⊗ p(atoms) ⅋ ~p0 ⅋ ~p1 ⅋ ... ⅋ ~p(duals - 1)`.
+#[inline]
+fn against_duals(atoms: usize, duals: usize) -> Formula {
+    let tensor = (1..=atoms).fold(Formula::Atom(0), |acc, i| {
+        Formula::Times(Box::new(acc), Box::new(Formula::Atom(i)))
+    });
+    (0..duals).fold(tensor, |acc, i| {
+        Formula::Par(Box::new(acc), Box::new(Formula::Dual(i)))
+    })
+}
+
+/// Queue each sequent to be proven, as a search would before expanding anything,
+/// and return how many were actually queued (i.e. not already queued).
+#[must_use]
+#[inline]
+pub fn push_all<I: IntoIterator<Item = RhsOnlyWithExchange<Formula>>>(sequents: I) -> usize {
+    let mut thunk = Thunk::empty(None);
+    thunk.extend(sequents);
+    thunk.queue_len()
+}
+
+/// `n` distinct sequents, each with one atom alongside `context`.
+#[must_use]
+#[inline]
+pub fn sequents(
+    n: usize,
+    context: &RhsOnlyWithExchange<Formula>,
+) -> Vec<RhsOnlyWithExchange<Formula>> {
+    (0..n).map(|i| context.with([Formula::Atom(i)])).collect()
+}
+
+/// `p0 ⊗ ~p0, p1 ⊗ ~p1, ..., p(n - 1) ⊗ ~p(n - 1)`,
+/// whose every item tries every way to split the rest of the sequent.
+#[must_use]
+#[inline]
+pub fn tensors(n: usize) -> RhsOnlyWithExchange<Formula> {
+    RhsOnlyWithExchange::new(
+        (0..n)
+            .map(|i| Formula::Times(Box::new(Formula::Atom(i)), Box::new(Formula::Dual(i))))
+            .collect(),
+    )
+}
+
+/// How many inferences the items in `sequent` offer, expanded as proof search would
+/// (on a pool of threads if it's large enough, with the `parallel` feature).
+#[must_use]
+#[inline]
+pub fn expand(sequent: &RhsOnlyWithExchange<Formula>) -> usize {
+    acting_on_each(sequent).len()
+}
+
+/// How many inferences the items in `sequent` offer, expanded one item at a time even with the `parallel` feature.
+#[must_use]
+#[inline]
+pub fn expand_serially(sequent: &RhsOnlyWithExchange<Formula>) -> usize {
+    acting_on_each_serially(sequent.sample()).len()
+}
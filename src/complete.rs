@@ -0,0 +1,87 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Solving for whatever a sequent is missing to be provable.
+
+use crate::{proof::prove_sequent, Infer as _, Multiset, Sequent, Tree};
+use alloc::{collections::BTreeSet, vec, vec::Vec};
+
+/// Find the fewest atomic formulas (at most `hole_budget` of them) that make `goal` provable once added to it,
+/// returning them alongside the proof.
+///
+/// Candidates are whatever `Infer::candidate_atoms` offers for each item in `goal`
+/// (e.g. every atom it mentions and every atom's dual), added with `Sequent::alongside`.
+/// Tries no additions first, then every multiset of one candidate, then of two, and so on,
+/// each in sorted order, so the answer is always the same.
+///
+/// Experimental: this runs a whole proof search per candidate multiset, so it's only practical for small budgets,
+/// and its signature may change.
+#[inline]
+#[must_use]
+#[allow(clippy::needless_pass_by_value)] // Same signature as `prove_sequent`
+pub fn complete<S: Sequent>(goal: S, hole_budget: usize) -> Option<(Multiset<S::Item>, Tree<S>)> {
+    let candidates: Vec<S::Item> = goal
+        .sample()
+        .flat_map(|(item, _)| item.candidate_atoms())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    for size in 0..=hole_budget {
+        for indices in Picks::new(candidates.len(), size) {
+            let additions: Multiset<S::Item> = indices
+                .iter()
+                .filter_map(|&i| candidates.get(i).cloned())
+                .collect();
+            let extended = goal.alongside(additions.iter_repeat().cloned())?;
+            if let Ok(tree) = prove_sequent(extended) {
+                return Some((additions, tree));
+            }
+        }
+    }
+    None
+}
+
+/// Every way to pick `size` of `choices` indices, with repetition but without regard to order,
+/// each as a non-decreasing list, in lexicographic order.
+#[derive(Clone, Debug)]
+struct Picks {
+    /// How many indices there are to pick from.
+    choices: usize,
+    /// The next pick, or `None` once every pick is done.
+    next: Option<Vec<usize>>,
+}
+
+impl Picks {
+    /// Start from the first pick (all zeros), if there's any pick at all.
+    #[inline]
+    fn new(choices: usize, size: usize) -> Self {
+        Self {
+            choices,
+            next: (size == 0 || choices > 0).then(|| vec![0; size]),
+        }
+    }
+}
+
+impl Iterator for Picks {
+    type Item = Vec<usize>;
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let pick = self.next.take()?;
+        // Bump the rightmost index that can still grow, then flatten everything after it to match.
+        if let Some(i) = pick
+            .iter()
+            .rposition(|&index| index.saturating_add(1) < self.choices)
+        {
+            let mut following = pick.clone();
+            if let Some(bumped) = following.get_mut(i..) {
+                let index = bumped.first().map_or(0, |&index| index.saturating_add(1));
+                bumped.fill(index);
+            }
+            self.next = Some(following);
+        }
+        Some(pick)
+    }
+}
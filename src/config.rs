@@ -0,0 +1,79 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Knobs for `prove_with_config`.
+
+use crate::{hash::RandomState, Subsumes};
+
+/// Knobs for `prove_with_config`.
+/// Start from `SearchConfig::new()` (or `default()` with another hasher) and override only what you need.
+#[allow(clippy::exhaustive_structs)]
+#[derive(Clone, Debug)]
+pub struct SearchConfig<S, H = RandomState> {
+    /// Hashes every sequent the search caches or waits on.
+    /// Something faster than the standard library's DoS-resistant default can pay off on big searches.
+    pub hasher: H,
+    /// Never look at a sequent with more than this many copies of any one item (see `Sequent::most_copies`),
+    /// or than the original sequent starts with if that's more, or `None` to allow any number.
+    /// Four by default.
+    ///
+    /// Without a cap, a contraction rule can keep adding copies forever,
+    /// so a search that can't succeed never runs out of sequents to try.
+    /// With one, running out of sequents fails with `Error::Incomplete` if we skipped any for too many copies,
+    /// since more copies might have led to a proof.
+    pub max_copies: Option<usize>,
+    /// Never look at a sequent with more than this many items in all (see `Sequent::width`),
+    /// or `None` (the default) to allow any number.
+    ///
+    /// Without a cap, rules like `⅋` or contraction can keep widening sequents until memory runs out.
+    /// With one, running out of sequents fails with `Error::Incomplete` if we skipped any for being too wide,
+    /// since a wider sequent might have led to a proof.
+    pub max_width: Option<usize>,
+    /// Whether proving the first sequent proves the second by weakening (see `Subsumes`),
+    /// or `None` (the default) never to check.
+    ///
+    /// With a check, any sequent we're about to expand that some proven sequent subsumes
+    /// is proven by a rule named `weakening` with that sequent above it instead.
+    pub subsumes: Option<fn(&S, &S) -> bool>,
+}
+
+/// Most copies of any one item a search looks at unless told otherwise
+/// (or the original sequent starts with more).
+pub(crate) const DEFAULT_MAX_COPIES: usize = 4;
+
+impl<S, H: Default> Default for SearchConfig<S, H> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            hasher: H::default(),
+            max_copies: Some(DEFAULT_MAX_COPIES),
+            max_width: None,
+            subsumes: None,
+        }
+    }
+}
+
+impl<S> SearchConfig<S> {
+    /// Every knob at its default, hashing with the standard library's `RandomState`
+    /// and allowing at most four copies of any one item beyond what the original sequent starts with.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<S: Subsumes, H> SearchConfig<S, H> {
+    /// Skip re-deriving any sequent that follows by weakening from one we've already proven.
+    #[must_use]
+    #[inline]
+    pub fn subsuming(self) -> Self {
+        Self {
+            subsumes: Some(S::subsumes),
+            ..self
+        }
+    }
+}
@@ -0,0 +1,216 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Proofs that write out each shared subproof once, however many inferences use it.
+
+use crate::{thunk::Thunk, tree::ALREADY_PROVEN, Sequent, Tree};
+use alloc::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    vec,
+    vec::Vec,
+};
+use core::hash::BuildHasher;
+
+/// Name for one proven sequent, given when a search first proves it (counting up from zero)
+/// and kept for as long as that search remembers the proof.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ProofId(pub(crate) usize);
+
+impl ProofId {
+    /// How many sequents the search had proven before this one.
+    #[must_use]
+    #[inline(always)]
+    pub const fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// One inference line in a `ProofDag`, naming the proof of each premise instead of containing it.
+#[allow(clippy::exhaustive_structs)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProofNode<S: Sequent> {
+    /// Proof of each sequent above the inference line, once per copy of that sequent.
+    pub above: Vec<ProofId>,
+    /// Name of the rule that allowed this inference.
+    pub rule: Cow<'static, str>,
+    /// Formula the rule acted on, if it came from one (see `Rule::principal`).
+    pub principal: Option<S::Item>,
+    /// Sequent below the inference line (proven by those above).
+    pub below: S,
+}
+
+/// Proof that writes out each subproof once, however many inferences use it,
+/// where a `Tree` would mark every use after the first as already proven.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProofDag<S: Sequent> {
+    /// ID of the proof of the original sequent.
+    root_id: ProofId,
+    /// Last inference line, proving the original sequent.
+    root: ProofNode<S>,
+    /// Every other inference line, by ID.
+    others: BTreeMap<ProofId, ProofNode<S>>,
+}
+
+impl<S: Sequent> ProofDag<S> {
+    /// Every cached inference that goes into proving `goal`, or `None` if it isn't proven.
+    #[inline]
+    pub(crate) fn cached<H: BuildHasher + Clone>(goal: &S, thunk: &Thunk<S, H>) -> Option<Self> {
+        let root_id = thunk.proof_id(goal)?;
+        let mut others = BTreeMap::new();
+        let mut stack = vec![goal];
+        let mut root = None;
+        while let Some(below) = stack.pop() {
+            let (Some(id), Some(rule)) = (thunk.proof_id(below), thunk.proven(below)) else {
+                // Can't happen: a rule is only cached once all its premises are proven.
+                continue;
+            };
+            if (id == root_id && root.is_some()) || others.contains_key(&id) {
+                continue;
+            }
+            stack.extend(rule.above.iter_unique().map(|(premise, _)| premise));
+            let node = ProofNode {
+                above: rule
+                    .above
+                    .iter_repeat()
+                    .filter_map(|premise| thunk.proof_id(premise))
+                    .collect(),
+                rule: rule.name.clone(),
+                principal: rule.principal.clone(),
+                below: below.clone(),
+            };
+            if id == root_id {
+                root = Some(node);
+            } else {
+                drop(others.insert(id, node));
+            }
+        }
+        Some(Self {
+            root_id,
+            root: root?,
+            others,
+        })
+    }
+
+    /// ID of the proof of the original sequent.
+    #[inline(always)]
+    #[must_use]
+    pub const fn root(&self) -> ProofId {
+        self.root_id
+    }
+
+    /// The inference line with this ID, if it's part of this proof.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, id: ProofId) -> Option<&ProofNode<S>> {
+        if id == self.root_id {
+            Some(&self.root)
+        } else {
+            self.others.get(&id)
+        }
+    }
+
+    /// Every inference line with its ID, the original sequent's first and the rest in the order they were proven.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (ProofId, &ProofNode<S>)> + '_ {
+        core::iter::once((self.root_id, &self.root))
+            .chain(self.others.iter().map(|(&id, node)| (id, node)))
+    }
+
+    /// Number of distinct inference lines, counting each shared subproof once.
+    #[inline]
+    #[must_use]
+    #[allow(clippy::len_without_is_empty)] // Never empty: there's always at least the root.
+    pub fn len(&self) -> usize {
+        self.others.len().saturating_add(1)
+    }
+
+    /// Number of inference lines with each shared subproof written out once per use.
+    #[inline]
+    #[must_use]
+    pub fn tree_size(&self) -> usize {
+        self.size(self.root_id, &mut BTreeMap::new())
+    }
+
+    /// Number of inference lines in the proof with this ID written out in full,
+    /// remembering each answer in `sizes`.
+    #[inline]
+    fn size(&self, id: ProofId, sizes: &mut BTreeMap<ProofId, usize>) -> usize {
+        if let Some(&size) = sizes.get(&id) {
+            return size;
+        }
+        let Some(node) = self.get(id) else {
+            return 0;
+        };
+        // Only a circular proof could ask for this again before we're done.
+        let _ = sizes.insert(id, 1);
+        let size = node.above.iter().fold(1_usize, |acc, &premise| {
+            acc.saturating_add(self.size(premise, sizes))
+        });
+        let _ = sizes.insert(id, size);
+        size
+    }
+
+    /// Each sequent whose proof more than one premise uses, and how many,
+    /// in the order they were proven.
+    #[inline]
+    #[must_use]
+    pub fn shared_lemmas(&self) -> Vec<(&S, usize)> {
+        let mut uses = BTreeMap::new();
+        for (_, node) in self.iter() {
+            for &premise in &node.above {
+                let count = uses.entry(premise).or_insert(0_usize);
+                *count = count.saturating_add(1);
+            }
+        }
+        uses.into_iter()
+            .filter(|&(_, count)| count > 1)
+            .filter_map(|(id, count)| self.get(id).map(|node| (&node.below, count)))
+            .collect()
+    }
+
+    /// The same proof as a `Tree`, with each shared subproof copied in full wherever it's used,
+    /// so no leaf is left marked as already proven.
+    #[inline]
+    #[must_use]
+    pub fn to_tree(&self) -> Tree<S> {
+        self.subtree(&self.root, self.root_id, &mut BTreeSet::new())
+    }
+
+    /// The proof with this ID as a `Tree`,
+    /// except where that proof is still being copied further down (which only a circular proof could ask for).
+    #[inline]
+    fn subtree(
+        &self,
+        node: &ProofNode<S>,
+        id: ProofId,
+        copying: &mut BTreeSet<ProofId>,
+    ) -> Tree<S> {
+        if !copying.insert(id) {
+            return Tree {
+                above: vec![],
+                rule: Cow::Borrowed(ALREADY_PROVEN),
+                principal: None,
+                below: node.below.clone(),
+            };
+        }
+        let tree = Tree {
+            above: node
+                .above
+                .iter()
+                .filter_map(|&premise| {
+                    self.get(premise)
+                        .map(|above| self.subtree(above, premise, copying))
+                })
+                .collect(),
+            rule: node.rule.clone(),
+            principal: node.principal.clone(),
+            below: node.below.clone(),
+        };
+        let _ = copying.remove(&id);
+        tree
+    }
+}
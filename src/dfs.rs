@@ -0,0 +1,88 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Depth-first proof search, with none of the bookkeeping that lets the main search share subgoals.
+
+use crate::{hash::HashSet, parallel::expand, Error, Infer, Multiset, Sequent, Tree};
+use alloc::{vec, vec::Vec};
+
+/// Attempt to prove this expression by plain depth-first backward chaining,
+/// never stacking more than `max_depth` inference lines on any branch.
+///
+/// With no cache, queue, or paused inferences to maintain, this is much cheaper than `prove` on small formulas,
+/// but it proves a subgoal from scratch every time it meets one, so it can take exponentially long on larger ones.
+/// Any proof it finds writes out every subproof in full (no leaves marked as already proven).
+/// # Errors
+/// If there's no proof at all, or none that shallow.
+#[inline]
+pub fn prove_dfs<I: Infer<S>, S: Sequent<Item = I>>(
+    expr: I,
+    max_depth: usize,
+) -> Result<Tree<S>, Error> {
+    let mut search = Dfs {
+        path: HashSet::new(),
+        pruned: false,
+    };
+    search
+        .prove(S::from_rhs(expr), max_depth)
+        .ok_or(if search.pruned {
+            Error::DepthExceeded { max_depth }
+        } else {
+            Error::RanOutOfPaths
+        })
+}
+
+/// State of a depth-first search.
+struct Dfs<S: Sequent> {
+    /// Every sequent between the root and wherever we are now,
+    /// so we never try to prove a sequent on the way to proving itself.
+    path: HashSet<S>,
+    /// Whether we gave up on any branch for being too deep.
+    pruned: bool,
+}
+
+impl<S: Sequent> Dfs<S> {
+    /// Prove this sequent in at most `depth` inference lines, if we can.
+    #[inline]
+    fn prove(&mut self, sequent: S, depth: usize) -> Option<Tree<S>> {
+        let Some(remaining) = depth.checked_sub(1) else {
+            self.pruned = true;
+            return None;
+        };
+        if !self.path.insert(sequent.clone()) {
+            // Already trying to prove this further down.
+            return None;
+        }
+        let mut rules = expand(&sequent);
+        if let Some(i) = rules.iter().position(|rule| rule.invertible) {
+            // Anything that proves this sequent can go through this rule instead.
+            rules = vec![rules.swap_remove(i)];
+        }
+        let found = rules.into_iter().find_map(|rule| {
+            let above = self.premises(rule.above, remaining)?;
+            Some((rule.name, rule.principal, above))
+        });
+        let _ = self.path.remove(&sequent);
+        found.map(|(rule, principal, above)| Tree {
+            above,
+            rule,
+            principal,
+            below: sequent,
+        })
+    }
+
+    /// Prove every distinct premise in at most `depth` inference lines, if we can,
+    /// and copy its proof once per copy of the premise.
+    #[inline]
+    fn premises(&mut self, above: Multiset<S>, depth: usize) -> Option<Vec<Tree<S>>> {
+        let mut trees = Vec::with_capacity(above.len());
+        for (premise, copies) in above.into_counts() {
+            let tree = self.prove(premise, depth)?;
+            trees.extend(core::iter::repeat_n(tree, copies.get()));
+        }
+        Some(trees)
+    }
+}
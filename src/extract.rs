@@ -0,0 +1,274 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Programs read off proofs, Curry-Howard style.
+
+use crate::{Sequent, Tree};
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+};
+
+/// Read a lambda term off a proof, Curry-Howard style.
+pub trait Extract<S: Sequent> {
+    /// The program this proof describes, with a variable bound for each assumption it discharges.
+    fn extract(tree: &Tree<S>) -> Term;
+}
+
+/// Lambda term with pairs and tagged unions, as a proof of an intuitionistic formula describes.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Term {
+    /// Variable identified by number.
+    Var(usize),
+    /// Function binding this variable in its body.
+    Lam(usize, Box<Self>),
+    /// Function applied to an argument.
+    App(Box<Self>, Box<Self>),
+    /// Pair of terms, for a conjunction.
+    Pair(Box<Self>, Box<Self>),
+    /// First half of a pair.
+    Fst(Box<Self>),
+    /// Second half of a pair.
+    Snd(Box<Self>),
+    /// Left injection, for the left side of a disjunction.
+    Inl(Box<Self>),
+    /// Right injection, for the right side of a disjunction.
+    Inr(Box<Self>),
+    /// Case analysis on an injection, binding the first variable in the second term
+    /// if it's on the left or the second variable in the third term if it's on the right.
+    Case(Box<Self>, usize, Box<Self>, usize, Box<Self>),
+    /// Only value of the unit type, for truth.
+    Unit,
+    /// Anything at all, from an impossible value, for falsehood.
+    Absurd(Box<Self>),
+}
+
+impl Term {
+    /// Function binding this variable in its body.
+    #[must_use]
+    #[inline(always)]
+    pub fn lam(var: usize, body: Self) -> Self {
+        Self::Lam(var, Box::new(body))
+    }
+
+    /// Function applied to an argument.
+    #[must_use]
+    #[inline(always)]
+    pub fn app(self, arg: Self) -> Self {
+        Self::App(Box::new(self), Box::new(arg))
+    }
+
+    /// Immediate subterms, left to right.
+    #[inline]
+    fn children(&self) -> impl Iterator<Item = &Self> {
+        let (first, second, third) = match *self {
+            Self::Var(_) | Self::Unit => (None, None, None),
+            Self::Lam(_, ref body)
+            | Self::Fst(ref body)
+            | Self::Snd(ref body)
+            | Self::Inl(ref body)
+            | Self::Inr(ref body)
+            | Self::Absurd(ref body) => (Some(&**body), None, None),
+            Self::App(ref lhs, ref rhs) | Self::Pair(ref lhs, ref rhs) => {
+                (Some(&**lhs), Some(&**rhs), None)
+            }
+            Self::Case(ref scrutinee, _, ref left, _, ref right) => {
+                (Some(&**scrutinee), Some(&**left), Some(&**right))
+            }
+        };
+        first.into_iter().chain(second).chain(third)
+    }
+
+    /// Every variable this term uses without binding it.
+    #[must_use]
+    #[inline]
+    pub fn free_vars(&self) -> BTreeSet<usize> {
+        match *self {
+            Self::Var(i) => BTreeSet::from([i]),
+            Self::Lam(var, ref body) => {
+                let mut free = body.free_vars();
+                let _ = free.remove(&var);
+                free
+            }
+            Self::Case(ref scrutinee, lvar, ref left, rvar, ref right) => {
+                let mut free = scrutinee.free_vars();
+                let mut lfree = left.free_vars();
+                let _ = lfree.remove(&lvar);
+                let mut rfree = right.free_vars();
+                let _ = rfree.remove(&rvar);
+                free.extend(lfree);
+                free.extend(rfree);
+                free
+            }
+            Self::Unit
+            | Self::App(..)
+            | Self::Pair(..)
+            | Self::Fst(_)
+            | Self::Snd(_)
+            | Self::Inl(_)
+            | Self::Inr(_)
+            | Self::Absurd(_) => self.children().flat_map(Self::free_vars).collect(),
+        }
+    }
+
+    /// The same term with bound variables numbered in the order they're bound,
+    /// from zero or (if there are any free variables, which keep their numbers) just past the last free variable,
+    /// so terms equal up to renaming bound variables come out identical.
+    #[must_use]
+    #[inline]
+    pub fn alpha_normalized(&self) -> Self {
+        let mut next = self
+            .free_vars()
+            .last()
+            .map_or(0, |&last| last.saturating_add(1));
+        self.renamed(&BTreeMap::new(), &mut next)
+    }
+
+    /// The same term with every variable in `names` renamed, and every bound variable renamed to `next` onward.
+    #[must_use]
+    #[inline]
+    fn renamed(&self, names: &BTreeMap<usize, usize>, next: &mut usize) -> Self {
+        let bind = |var: usize, counter: &mut usize| {
+            let renamed = *counter;
+            *counter = counter.saturating_add(1);
+            let mut inner = names.clone();
+            let _ = inner.insert(var, renamed);
+            (renamed, inner)
+        };
+        match *self {
+            Self::Var(i) => Self::Var(names.get(&i).copied().unwrap_or(i)),
+            Self::Unit => Self::Unit,
+            Self::Lam(var, ref body) => {
+                let (renamed, inner) = bind(var, next);
+                Self::lam(renamed, body.renamed(&inner, next))
+            }
+            Self::App(ref lhs, ref rhs) => lhs.renamed(names, next).app(rhs.renamed(names, next)),
+            Self::Pair(ref lhs, ref rhs) => Self::Pair(
+                Box::new(lhs.renamed(names, next)),
+                Box::new(rhs.renamed(names, next)),
+            ),
+            Self::Fst(ref arg) => Self::Fst(Box::new(arg.renamed(names, next))),
+            Self::Snd(ref arg) => Self::Snd(Box::new(arg.renamed(names, next))),
+            Self::Inl(ref arg) => Self::Inl(Box::new(arg.renamed(names, next))),
+            Self::Inr(ref arg) => Self::Inr(Box::new(arg.renamed(names, next))),
+            Self::Absurd(ref arg) => Self::Absurd(Box::new(arg.renamed(names, next))),
+            Self::Case(ref scrutinee, lvar, ref left, rvar, ref right) => {
+                let matched = scrutinee.renamed(names, next);
+                let (lrenamed, linner) = bind(lvar, next);
+                let on_left = left.renamed(&linner, next);
+                let (rrenamed, rinner) = bind(rvar, next);
+                let on_right = right.renamed(&rinner, next);
+                Self::Case(
+                    Box::new(matched),
+                    lrenamed,
+                    Box::new(on_left),
+                    rrenamed,
+                    Box::new(on_right),
+                )
+            }
+        }
+    }
+
+    /// How tightly this term holds together when printed:
+    /// binders reach as far right as they can, applications take atomic arguments,
+    /// and everything else is already atomic.
+    #[inline]
+    const fn precedence(&self) -> u8 {
+        match *self {
+            Self::Lam(..) | Self::Case(..) => 0,
+            Self::App(..)
+            | Self::Fst(_)
+            | Self::Snd(_)
+            | Self::Inl(_)
+            | Self::Inr(_)
+            | Self::Absurd(_) => 1,
+            Self::Var(_) | Self::Pair(..) | Self::Unit => 2,
+        }
+    }
+
+    /// Print this term, in parentheses if it holds together less tightly than `precedence`.
+    #[inline]
+    fn fmt_at(&self, f: &mut core::fmt::Formatter<'_>, precedence: u8) -> core::fmt::Result {
+        if self.precedence() < precedence {
+            write!(f, "(")?;
+            self.fmt_at(f, 0)?;
+            return write!(f, ")");
+        }
+        match *self {
+            Self::Var(i) => write_var(f, i),
+            Self::Unit => write!(f, "()"),
+            Self::Lam(var, ref body) => {
+                write!(f, "\u{3bb}")?;
+                write_var(f, var)?;
+                write!(f, ". ")?;
+                body.fmt_at(f, 0)
+            }
+            Self::App(ref lhs, ref rhs) => {
+                lhs.fmt_at(f, 1)?;
+                write!(f, " ")?;
+                rhs.fmt_at(f, 2)
+            }
+            Self::Pair(ref lhs, ref rhs) => {
+                write!(f, "(")?;
+                lhs.fmt_at(f, 0)?;
+                write!(f, ", ")?;
+                rhs.fmt_at(f, 0)?;
+                write!(f, ")")
+            }
+            Self::Fst(ref arg) => {
+                write!(f, "fst ")?;
+                arg.fmt_at(f, 2)
+            }
+            Self::Snd(ref arg) => {
+                write!(f, "snd ")?;
+                arg.fmt_at(f, 2)
+            }
+            Self::Inl(ref arg) => {
+                write!(f, "inl ")?;
+                arg.fmt_at(f, 2)
+            }
+            Self::Inr(ref arg) => {
+                write!(f, "inr ")?;
+                arg.fmt_at(f, 2)
+            }
+            Self::Absurd(ref arg) => {
+                write!(f, "absurd ")?;
+                arg.fmt_at(f, 2)
+            }
+            Self::Case(ref scrutinee, lvar, ref left, rvar, ref right) => {
+                write!(f, "case ")?;
+                scrutinee.fmt_at(f, 0)?;
+                write!(f, " of inl ")?;
+                write_var(f, lvar)?;
+                write!(f, " \u{21d2} ")?;
+                // A binder here would swallow the other branch.
+                left.fmt_at(f, 1)?;
+                write!(f, " | inr ")?;
+                write_var(f, rvar)?;
+                write!(f, " \u{21d2} ")?;
+                right.fmt_at(f, 0)
+            }
+        }
+    }
+}
+
+/// Print a variable as a lowercase letter, or (past `z`) a `v` and its number.
+#[inline]
+fn write_var(f: &mut core::fmt::Formatter<'_>, var: usize) -> core::fmt::Result {
+    match u8::try_from(var).ok().filter(|&i| i < 26) {
+        Some(i) => write!(f, "{}", char::from(b'a'.saturating_add(i))),
+        None => write!(f, "v{var}"),
+    }
+}
+
+impl core::fmt::Display for Term {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.fmt_at(f, 0)
+    }
+}
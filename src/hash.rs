@@ -0,0 +1,18 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Hash maps and sets from `hashbrown`, with or without `std`.
+
+pub(crate) use hashbrown::{hash_map::Entry, HashMap, HashSet};
+
+/// Default hasher for every map and set the search keeps.
+#[cfg(feature = "std")]
+pub(crate) type RandomState = std::hash::RandomState;
+
+/// Default hasher for every map and set the search keeps
+/// (`std`'s needs the operating system to seed it).
+#[cfg(not(feature = "std"))]
+pub(crate) type RandomState = hashbrown::DefaultHashBuilder;
@@ -0,0 +1,43 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Biasing which inferences proof search explores first.
+
+use crate::{Rule, Sequent};
+
+/// Biasing which inferences proof search explores first:
+/// the premises of higher-scoring rules jump ahead of everything scored lower,
+/// and within a score, the search strategy decides as usual.
+///
+/// A sequent keeps the score of whichever rule first asked for it,
+/// and a score changes only the order of the search, never whether it finds a proof.
+pub trait Heuristic<S: Sequent> {
+    /// How promising it looks to prove `below` by `rule` (higher first).
+    fn score(&self, below: &S, rule: &Rule<S>) -> i64;
+}
+
+/// Score everything the same, i.e. search exactly as if unguided.
+impl<S: Sequent> Heuristic<S> for () {
+    #[inline(always)]
+    fn score(&self, _below: &S, _rule: &Rule<S>) -> i64 {
+        0
+    }
+}
+
+/// Score with any function of the conclusion and the rule.
+impl<S: Sequent, F: Fn(&S, &Rule<S>) -> i64> Heuristic<S> for F {
+    #[inline(always)]
+    fn score(&self, below: &S, rule: &Rule<S>) -> i64 {
+        self(below, rule)
+    }
+}
+
+/// Where a score puts a sequent in the queue (lowest first), so higher scores come out first.
+#[inline(always)]
+pub(crate) const fn rank(score: i64) -> u64 {
+    // Flipping the sign bit preserves order from `i64` to `u64`, and flipping every bit reverses it.
+    !(score.cast_unsigned() ^ (1 << 63))
+}
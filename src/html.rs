@@ -0,0 +1,48 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Standalone HTML export for proof trees, with each subproof collapsible.
+
+use alloc::string::String;
+
+/// Everything before the proof itself, up to and including the opening of the `<title>`.
+pub(crate) const HEAD: &str =
+    "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>";
+
+/// Everything between the `<title>` and the proof itself, including every style the proof needs.
+pub(crate) const STYLE: &str = "</title>
+<style>
+body { font-family: serif; margin: 2em; }
+.inference { display: inline-flex; flex-direction: column; align-items: center; vertical-align: bottom; margin: 0 1em; }
+.inference > details { display: flex; flex-direction: column; align-items: center; }
+.premises { display: flex; align-items: flex-end; justify-content: center; }
+summary { color: #888; cursor: pointer; font-size: 0.75em; }
+.conclusion { position: relative; border-top: 1px solid; padding: 0.2em 0.5em 0; white-space: nowrap; }
+.rule { position: absolute; left: 100%; top: -0.7em; padding-left: 0.3em; font-size: 0.8em; white-space: nowrap; }
+</style>
+</head>
+<body>
+";
+
+/// Everything after the proof itself.
+pub(crate) const TAIL: &str = "</body>\n</html>\n";
+
+/// Escape anything HTML would otherwise interpret, in text or in a quoted attribute.
+#[inline]
+pub(crate) fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
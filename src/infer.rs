@@ -6,13 +6,37 @@
 
 //! A set of sequent-calculus inference rules using the specified sequent structure.
 
-use crate::{Rule, Sequent};
+use crate::{MaybeSendSync, Rule, Sequent};
+use alloc::vec::Vec;
 
 /// A set of sequent-calculus inference rules using the specified sequent structure.
-pub trait Infer<S: Sequent<Item = Self>>: Clone {
+pub trait Infer<S: Sequent<Item = Self>>: Clone + MaybeSendSync {
     /// All possible "next moves" in a sequent-calculus proof search.
-    /// Note that each item in the resultant `HashSet` is a _separate_ inference line:
+    /// Note that each item in the resultant `Vec` is a _separate_ inference line:
     /// if you want to place multiple sequents above a single inference line,
-    /// use `below.require_all([first, second, ...])`.
+    /// list them all in one rule, e.g. `Rule::binary(name, first, second)`.
+    /// Build a rule with `Rule::invertible` to have the search commit to it eagerly.
+    ///
+    /// `context` is the rest of the sequent, with this item already taken out,
+    /// so side conditions on the whole context (e.g. `!` needs nothing but `?`s beside it)
+    /// are checks on `context`, and premises may rewrite it freely
+    /// (e.g. strip or add connectives on every formula in it) rather than only add to it.
     fn above(&self, context: S) -> Vec<Rule<S>>;
+    /// Name of the rule that proves `sequent` outright with nothing above it (e.g. an axiom),
+    /// for sequent structures that ask their items, e.g. `RhsOnlyWithExchange` (see `Sequent::closed`).
+    /// `None` by default.
+    #[inline(always)]
+    #[must_use]
+    fn initial(sequent: &S) -> Option<&'static str> {
+        let _ = sequent;
+        None
+    }
+    /// Atomic formulas `complete` may add beside this item to make a sequent provable,
+    /// e.g. every atom it mentions and every atom's dual.
+    /// None by default.
+    #[inline(always)]
+    #[must_use]
+    fn candidate_atoms(&self) -> Vec<Self> {
+        Vec::new()
+    }
 }
@@ -6,9 +6,9 @@
 
 //! A set of sequents above an inference line plus a reference to the sequent below the inference line.
 
-use crate::{thunk::Thunk, Rule, Sequent};
+use crate::{parallel::Shared, Rule, Sequent};
+use alloc::{borrow::ToOwned as _, format, string::String};
 use core::{fmt::Display, hash::Hash};
-use std::rc::Rc;
 
 /// A set of sequents above an inference line plus a reference to the sequent below the inference line.
 #[derive(Clone, Debug)]
@@ -19,13 +19,13 @@ pub(crate) struct Inference<S: Sequent> {
     /// If `self` is proven true/false,
     /// it would immediately follow that
     /// `self.history` is proven the same.
-    pub(crate) below: Rc<S>,
+    pub(crate) below: Shared<S>,
 }
 
 impl<S: Sequent> PartialEq for Inference<S> {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
-        self.rule == other.rule
+        self.rule == other.rule && self.below == other.below
     }
 }
 
@@ -52,13 +52,14 @@ impl<S: Sequent> Hash for Inference<S> {
     #[inline]
     fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.rule.hash(state);
+        self.below.hash(state);
     }
 }
 
 impl<S: Sequent> Display for Inference<S> {
     #[inline]
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{} (would prove {})", self.without_history(), self.below,)
+        write!(f, "{} (would prove {})", self.without_history(), self.below)
     }
 }
 
@@ -77,15 +78,6 @@ impl<S: Sequent> Inference<S> {
             },
         )
     }
-
-    /// Check if we have proofs already cached for each sequent above the inference line.
-    #[inline]
-    pub(crate) fn proven(&self, thunk: &Thunk<S>) -> bool {
-        self.rule
-            .above
-            .iter_unique()
-            .all(|(sequent, _)| thunk.proven(sequent).is_some())
-    }
 }
 
 #[cfg(feature = "quickcheck")]
@@ -94,7 +86,7 @@ impl<S: Sequent + quickcheck::Arbitrary> quickcheck::Arbitrary for Inference<S>
     fn arbitrary(g: &mut quickcheck::Gen) -> Self {
         Self {
             rule: quickcheck::Arbitrary::arbitrary(g),
-            below: Rc::new(quickcheck::Arbitrary::arbitrary(g)),
+            below: Shared::new(quickcheck::Arbitrary::arbitrary(g)),
         }
     }
     #[inline]
@@ -104,7 +96,7 @@ impl<S: Sequent + quickcheck::Arbitrary> quickcheck::Arbitrary for Inference<S>
                 .shrink()
                 .map(|(rule, below)| Self {
                     rule,
-                    below: Rc::new(below),
+                    below: Shared::new(below),
                 }),
         )
     }
@@ -0,0 +1,251 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Proofs built one chosen inference at a time, e.g. behind a proof assistant's interface.
+
+use crate::{apply, proof::prove_sequent, step, Error, Rule, Sequent, Tree};
+use alloc::{borrow::Cow, vec, vec::Vec};
+
+/// How one sequent in a `Session` got proven.
+#[derive(Clone, Debug)]
+enum Proof<S: Sequent> {
+    /// By a rule chosen in the session (or an initial sequent closing itself),
+    /// with each distinct premise at its index in `Session::nodes`.
+    Rule {
+        /// Name of the rule that allowed this inference.
+        name: Cow<'static, str>,
+        /// Formula the rule acted on, if it came from one.
+        principal: Option<S::Item>,
+        /// Index of each distinct premise, once per copy the rule needs.
+        above: Vec<usize>,
+    },
+    /// All at once by proof search.
+    Auto(Tree<S>),
+}
+
+/// One sequent in a `Session`'s partial proof, proven or not.
+#[derive(Clone, Debug)]
+struct Node<S: Sequent> {
+    /// Sequent below the inference line.
+    below: S,
+    /// How it got proven, or `None` if it's still an open goal.
+    proof: Option<Proof<S>>,
+}
+
+/// Everything `Session::undo` needs to take back one move.
+#[derive(Clone, Debug)]
+struct Snapshot {
+    /// Open goals before the move.
+    open: Vec<usize>,
+    /// How many nodes there were before the move.
+    nodes: usize,
+    /// Every node that was open before the move and proven by it.
+    proved: Vec<usize>,
+}
+
+/// A proof built by hand: a stack of open goals, a partial tree above the original sequent,
+/// and every move so far, so any of them can be taken back.
+///
+/// Number choices with `choices` (see `apply` for their order), pick one with `choose`,
+/// and once nothing's left open, collect the whole proof with `tree`.
+/// Premises that close on their own (see `Sequent::closed`) never become goals.
+#[derive(Clone, Debug)]
+pub struct Session<S: Sequent> {
+    /// Every sequent in the partial proof, the original first, each premise after what it's above.
+    nodes: Vec<Node<S>>,
+    /// Index of each open goal, the one to work on next last.
+    open: Vec<usize>,
+    /// What to restore to take back each move, oldest first.
+    history: Vec<Snapshot>,
+}
+
+impl<S: Sequent> Session<S> {
+    /// Start proving this sequent, which is the only goal unless it closes on its own.
+    #[inline]
+    #[must_use]
+    pub fn new(goal: S) -> Self {
+        let mut session = Self {
+            nodes: vec![],
+            open: vec![],
+            history: vec![],
+        };
+        let _ = session.add(goal);
+        session
+    }
+
+    /// Add a node for this sequent, closing it on the spot if it's initial and leaving it open otherwise.
+    /// Returns its index.
+    #[inline]
+    fn add(&mut self, below: S) -> usize {
+        let id = self.nodes.len();
+        let proof = below.closed().map(|name| Proof::Rule {
+            name: Cow::Borrowed(name),
+            principal: None,
+            above: vec![],
+        });
+        if proof.is_none() {
+            self.open.push(id);
+        }
+        self.nodes.push(Node { below, proof });
+        id
+    }
+
+    /// Every open goal, the one `choose` works on first.
+    #[inline]
+    pub fn goals(&self) -> impl Iterator<Item = &S> + '_ {
+        self.open
+            .iter()
+            .rev()
+            .filter_map(|&id| self.nodes.get(id).map(|node| &node.below))
+    }
+
+    /// The goal `choose` works on, or `None` if nothing's left open.
+    #[inline]
+    #[must_use]
+    pub fn current(&self) -> Option<&S> {
+        self.goals().next()
+    }
+
+    /// Whether every goal is proven.
+    #[inline(always)]
+    #[must_use]
+    pub const fn is_done(&self) -> bool {
+        self.open.is_empty()
+    }
+
+    /// How many moves `undo` could take back.
+    #[inline(always)]
+    #[must_use]
+    pub const fn moves(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Every inference the current goal offers, numbered for `choose` as `apply` numbers them,
+    /// or nothing if there's no goal left.
+    #[inline]
+    #[must_use]
+    pub fn choices(&self) -> Vec<(S::Item, Vec<Rule<S>>)> {
+        self.current().map_or_else(Vec::new, apply)
+    }
+
+    /// Prove the current goal by the rule numbered `rule_index` among those the item numbered `focus_index` offers,
+    /// making each of its premises a new goal (first premise first) unless it closes on its own.
+    /// Returns `false`, changing nothing, if there's no goal left or no such rule.
+    #[inline]
+    pub fn choose(&mut self, focus_index: usize, rule_index: usize) -> bool {
+        let Some(&id) = self.open.last() else {
+            return false;
+        };
+        let Some(rule) = self
+            .nodes
+            .get(id)
+            .and_then(|node| step(&node.below, focus_index, rule_index))
+        else {
+            return false;
+        };
+        let snapshot = Snapshot {
+            open: self.open.clone(),
+            nodes: self.nodes.len(),
+            proved: vec![id],
+        };
+        let _ = self.open.pop();
+        let mut above = vec![];
+        let mut opened = vec![];
+        for (premise, &copies) in rule.above.iter_unique() {
+            let before = self.open.len();
+            let index = self.add(premise.clone());
+            above.extend(core::iter::repeat_n(index, copies.get()));
+            opened.extend(self.open.drain(before..));
+        }
+        // Push in reverse so the first premise ends up on top.
+        self.open.extend(opened.into_iter().rev());
+        if let Some(node) = self.nodes.get_mut(id) {
+            node.proof = Some(Proof::Rule {
+                name: rule.name,
+                principal: rule.principal,
+                above,
+            });
+        }
+        self.history.push(snapshot);
+        true
+    }
+
+    /// Prove every open goal by proof search, as one move.
+    /// # Errors
+    /// If any goal has no proof, in which case nothing changes.
+    #[inline]
+    pub fn auto(&mut self) -> Result<(), Error> {
+        let proofs = self
+            .open
+            .iter()
+            .filter_map(|&id| self.nodes.get(id).map(|node| (id, node.below.clone())))
+            .map(|(id, goal)| prove_sequent(goal).map(|tree| (id, tree)))
+            .collect::<Result<Vec<_>, _>>()?;
+        if proofs.is_empty() {
+            return Ok(());
+        }
+        self.history.push(Snapshot {
+            open: core::mem::take(&mut self.open),
+            nodes: self.nodes.len(),
+            proved: proofs.iter().map(|&(id, _)| id).collect(),
+        });
+        for (id, tree) in proofs {
+            if let Some(node) = self.nodes.get_mut(id) {
+                node.proof = Some(Proof::Auto(tree));
+            }
+        }
+        Ok(())
+    }
+
+    /// Take back the last move (whether `choose` or `auto`), or return `false` if there's none.
+    #[inline]
+    pub fn undo(&mut self) -> bool {
+        let Some(snapshot) = self.history.pop() else {
+            return false;
+        };
+        self.nodes.truncate(snapshot.nodes);
+        for id in snapshot.proved {
+            if let Some(node) = self.nodes.get_mut(id) {
+                node.proof = None;
+            }
+        }
+        self.open = snapshot.open;
+        true
+    }
+
+    /// The whole proof, or `None` if any goal is still open.
+    #[inline]
+    #[must_use]
+    pub fn tree(&self) -> Option<Tree<S>> {
+        if self.is_done() {
+            self.subtree(0)
+        } else {
+            None
+        }
+    }
+
+    /// The proof of the node at this index, or `None` if anything above it is still open.
+    #[inline]
+    fn subtree(&self, id: usize) -> Option<Tree<S>> {
+        let node = self.nodes.get(id)?;
+        match *node.proof.as_ref()? {
+            Proof::Auto(ref tree) => Some(tree.clone()),
+            Proof::Rule {
+                ref name,
+                ref principal,
+                ref above,
+            } => Some(Tree {
+                above: above
+                    .iter()
+                    .map(|&premise| self.subtree(premise))
+                    .collect::<Option<Vec<_>>>()?,
+                rule: name.clone(),
+                principal: principal.clone(),
+                below: node.below.clone(),
+            }),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! LaTeX export for proof trees using the `bussproofs` package.
+
+use alloc::string::String;
+
+/// Default translation of Unicode symbols into LaTeX math-mode commands.
+/// Note that `\parr` comes from the `cmll` package.
+pub const LATEX_SYMBOLS: &[(&str, &str)] = &[
+    ("\u{22a2}", "\\vdash"),
+    ("\u{2297}", "\\otimes"),
+    ("\u{214b}", "\\parr"),
+    ("\u{2295}", "\\oplus"),
+    ("\u{22a4}", "\\top"),
+    ("\u{22a5}", "\\bot"),
+    ("\u{22b8}", "\\multimap"),
+    ("\u{ac}", "\\neg"),
+    ("\u{2227}", "\\land"),
+    ("\u{2228}", "\\lor"),
+    ("\u{2192}", "\\to"),
+    ("\u{d7}", "\\times"),
+    ("~", "\\sim"),
+];
+
+/// Translate symbols and escape anything LaTeX would otherwise interpret.
+#[inline]
+pub(crate) fn escape(s: &str, symbols: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(c) = rest.chars().next() {
+        if let Some(&(from, to)) = symbols
+            .iter()
+            .find(|&&(from, _)| !from.is_empty() && rest.starts_with(from))
+        {
+            out.push_str(to);
+            rest = rest.get(from.len()..).unwrap_or_default();
+            // Keep a control word like `\top` from running into a following letter.
+            if to.ends_with(|t: char| t.is_ascii_alphabetic())
+                && rest.starts_with(|r: char| r.is_ascii_alphabetic())
+            {
+                out.push(' ');
+            }
+            continue;
+        }
+        match c {
+            '&' | '%' | '#' | '_' | '{' | '}' | '$' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\\' => out.push_str("\\backslash"),
+            '^' => out.push_str("\\hat{}"),
+            _ => out.push(c),
+        }
+        rest = rest.get(c.len_utf8()..).unwrap_or_default();
+    }
+    out
+}
@@ -0,0 +1,47 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Knobs for `Tree::render`.
+
+/// Knobs for `Tree::render`.
+/// Start from `RenderOptions::new()` and override only what you need.
+#[allow(clippy::exhaustive_structs)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RenderOptions {
+    /// Widest any line should get, in characters, or `None` (the default) for no limit.
+    ///
+    /// Wherever premises side by side wouldn't fit, they're stacked on top of one another instead,
+    /// each indented by `gap` and separated by a blank line.
+    /// A single sequent or inference line wider than this still sticks out, since there's nowhere to break it.
+    pub max_width: Option<usize>,
+    /// Spaces between premises side by side, and indentation for premises stacked instead (3 by default).
+    pub gap: usize,
+    /// Whether to label each inference line with its rule (and the formula it acted on), as by default.
+    pub rule_names: bool,
+    /// Whether to spell out every symbol in plain ASCII, like the alternate form `{:#}` (off by default).
+    pub ascii: bool,
+}
+
+impl Default for RenderOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            max_width: None,
+            gap: 3,
+            rule_names: true,
+            ascii: false,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Every knob at its default, exactly as `Display` prints.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
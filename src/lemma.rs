@@ -0,0 +1,50 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Formulas proof search may cut on.
+
+use crate::{Rule, Sequent};
+use alloc::{vec, vec::Vec};
+
+/// Formulas proof search may cut on, each alongside its dual.
+#[derive(Clone)]
+pub(crate) struct Lemmas<S: Sequent>(Vec<(S::Item, S::Item)>);
+
+impl<S: Sequent> Default for Lemmas<S> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self(vec![])
+    }
+}
+
+impl<S: Sequent> core::fmt::Debug for Lemmas<S> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Lemmas({})", self.0.len())
+    }
+}
+
+impl<S: Sequent> Lemmas<S> {
+    /// Pair each lemma with its dual.
+    #[inline]
+    pub(crate) fn new<F: Fn(&S::Item) -> S::Item>(lemmas: &[S::Item], dual: F) -> Self {
+        Self(
+            lemmas
+                .iter()
+                .map(|lemma| (lemma.clone(), dual(lemma)))
+                .collect(),
+        )
+    }
+
+    /// Every way to conclude this sequent by cutting on one of these lemmas.
+    #[inline]
+    pub(crate) fn cuts(&self, sequent: &S) -> Vec<Rule<S>> {
+        self.0
+            .iter()
+            .flat_map(|&(ref lemma, ref dual)| sequent.cut(lemma, dual))
+            .collect()
+    }
+}
@@ -7,7 +7,7 @@
 //! <https://en.wikipedia.org/wiki/Linear_logic#Sequent_calculus_presentation>
 //! <https://plato.stanford.edu/entries/logic-linear/#SeqCal>
 
-// #![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![deny(warnings)]
 #![allow(unknown_lints)]
 #![warn(
@@ -18,8 +18,6 @@
     clippy::perf,
     clippy::restriction,
     clippy::cargo,
-    elided_lifetimes_in_paths,
-    missing_docs,
     rustdoc::all
 )]
 // https://doc.rust-lang.org/rustc/lints/listing/allowed-by-default.html
@@ -37,7 +35,6 @@
     missing_docs,
     non_ascii_idents,
     noop_method_call,
-    pointer_structural_match,
     rust_2021_incompatible_closure_captures,
     rust_2021_incompatible_or_patterns,
     rust_2021_prefixes_incompatible_syntax,
@@ -56,14 +53,20 @@
     unused_macro_rules,
     unused_qualifications,
     unused_results,
-    unused_tuple_struct_fields,
     variant_size_differences
 )]
 #![allow(
+    clippy::absolute_paths,
+    clippy::allow_attributes,
+    clippy::allow_attributes_without_reason,
+    clippy::arbitrary_source_item_ordering,
     clippy::blanket_clippy_restriction_lints,
+    clippy::doc_paragraphs_missing_punctuation,
     clippy::expect_used,
+    clippy::field_scoped_visibility_modifiers,
     clippy::implicit_return,
     clippy::inline_always,
+    clippy::iter_over_hash_type,
     clippy::let_underscore_untyped,
     clippy::min_ident_chars,
     clippy::missing_trait_methods,
@@ -76,6 +79,7 @@
     clippy::question_mark_used,
     clippy::redundant_pub_crate,
     clippy::ref_patterns,
+    clippy::renamed_function_params,
     clippy::semicolon_outside_block,
     clippy::separated_literal_suffix,
     clippy::similar_names,
@@ -87,38 +91,115 @@
     clippy::wildcard_imports
 )]
 
-/// Print only if debugging.
-#[cfg(debug_assertions)]
+extern crate alloc;
+
+/// Print only if debugging with `std`.
+#[cfg(all(debug_assertions, feature = "std"))]
 macro_rules! dbg_println {
     ($($arg:tt)*) => {
         println!($($arg)*)
     };
 }
 
-/// Print only if debugging.
-#[cfg(not(debug_assertions))]
+/// Print only if debugging with `std`.
+#[cfg(not(all(debug_assertions, feature = "std")))]
 macro_rules! dbg_println {
     ($($arg:tt)*) => {};
 }
 
+mod ascii;
+mod audit;
+#[cfg(feature = "bench-internals")]
+pub mod bench;
+mod cancel;
+mod complete;
+mod config;
+mod dag;
+mod dfs;
+mod extract;
+mod hash;
+mod heuristic;
+mod html;
 mod infer;
 mod inference;
+pub mod interactive;
+mod json;
+mod latex;
+mod layout;
+mod lemma;
+#[cfg(any(feature = "linear-logic", feature = "propositional-logic"))]
+pub mod logics;
 mod multiset;
+mod observe;
+mod parallel;
+mod paused;
+#[cfg(feature = "persist")]
+mod persist;
+pub mod prelude;
+#[cfg(feature = "std")]
+mod progress;
 mod proof;
+mod proofs;
+mod render;
+mod report;
 mod rule;
 mod sequent;
 pub mod sequents;
+mod state;
+mod stats;
+mod step;
+mod strategy;
+mod subsumes;
 mod thunk;
+mod trace;
 mod tree;
 
 pub use {
+    ascii::ASCII_SYMBOLS,
+    audit::{audit, AuditReport},
+    cancel::CancellationToken,
+    complete::complete,
+    config::SearchConfig,
+    dag::{ProofDag, ProofId, ProofNode},
+    dfs::prove_dfs,
+    extract::{Extract, Term},
+    heuristic::Heuristic,
     infer::Infer,
-    multiset::Multiset,
-    proof::{prove, Error},
+    latex::LATEX_SYMBOLS,
+    layout::RenderOptions,
+    multiset::{IterRepeat, Multiset},
+    observe::{SearchEvent, SearchObserver},
+    parallel::MaybeSendSync,
+    proof::{
+        proofs, prove, prove_cancellable, prove_dag, prove_guided, prove_observed,
+        prove_or_explain, prove_sequent, prove_shortest, prove_shortest_within, prove_with_axioms,
+        prove_with_config, prove_with_lemmas, prove_with_limit, prove_with_stats,
+        prove_with_strategy, Error,
+    },
+    render::{render_sequent, Ascii, Html, Latex, SequentRenderer, Unicode},
+    report::FailureReport,
     rule::Rule,
     sequent::Sequent,
-    tree::Tree,
+    state::ProverState,
+    stats::SearchStats,
+    step::{apply, step},
+    strategy::{Priority, SearchStrategy},
+    subsumes::Subsumes,
+    trace::{prove_traced, replay, ReplayError, TraceEvent},
+    tree::{ProofCheckError, Tree, TreeDiff},
+};
+
+#[cfg(feature = "persist")]
+pub use persist::PersistError;
+
+#[cfg(feature = "std")]
+pub use {
+    progress::Progress,
+    proof::{prove_with_deadline, prove_with_progress},
 };
 
+/// Either a successful proof or the reason we couldn't find one.
+pub type Result<T> = core::result::Result<T, Error>;
+
 #[cfg(test)]
 mod test;
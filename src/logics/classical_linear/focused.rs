@@ -0,0 +1,246 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Focused (Andreoli-style) proof search, which commits to far fewer choices.
+
+use super::{Ast, Atom, Polarity};
+use crate::{prove, sequents::RhsOnlyWithExchange, Error, Infer, Multiset, Rule, Tree};
+use alloc::{vec, vec::Vec};
+
+/// A formula in a focused (Andreoli-style) proof search.
+///
+/// Focusing decomposes every negative formula first,
+/// then decides on one positive formula and keeps decomposing it until its subformulas turn negative.
+/// Start from `Focused::from` (or `prove_focused`), which also puts the formula into negation normal form.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Focused<A: Atom = usize> {
+    /// Used exactly once, waiting for its turn.
+    Linear(Ast<A>),
+    /// The one formula being decomposed until it turns negative.
+    Focus(Ast<A>),
+    /// The argument of a `?`, usable any number of times (including none).
+    Unrestricted(Ast<A>),
+}
+
+impl<A: Atom> core::fmt::Display for Focused<A> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::Linear(ref ast) => write!(f, "{ast}"),
+            Self::Focus(ref ast) => write!(f, "[{ast}]"),
+            Self::Unrestricted(ref ast) => {
+                write!(f, "?")?;
+                ast.fmt_within(f, ast.infix().is_some())
+            }
+        }
+    }
+}
+
+impl<A: Atom> From<Ast<A>> for Focused<A> {
+    /// Start a focused search on this formula, in negation normal form.
+    #[inline(always)]
+    fn from(ast: Ast<A>) -> Self {
+        Self::Linear(ast.nnf())
+    }
+}
+
+impl<A: Atom> Focused<A> {
+    /// What a subformula of the focus becomes: still focused while positive, released once negative.
+    #[inline]
+    fn keep_focus(ast: &Ast<A>) -> Self {
+        match ast.polarity() {
+            Polarity::Positive => Self::Focus(ast.clone()),
+            Polarity::Negative => Self::Linear(ast.clone()),
+        }
+    }
+
+    /// Whether this is a negative formula still waiting to be decomposed
+    /// (unlike the dual of a raw value, which stays put until an axiom uses it).
+    #[inline]
+    fn is_asynchronous(&self) -> bool {
+        matches!(*self, Self::Linear(ref ast) if ast.polarity() == Polarity::Negative
+            && !matches!(*ast, Ast::Dual(_)))
+    }
+
+    /// Whether `ast`, once focused, could be proven with exactly `linear` beside it
+    /// (e.g. a raw value only ever closes by an axiom with its dual).
+    #[inline]
+    #[allow(clippy::arithmetic_side_effects)]
+    fn fits(ast: &Ast<A>, linear: &Multiset<Self>, unrestricted: &[Self]) -> bool {
+        match *ast {
+            Ast::Value(_) => linear.only().map_or_else(
+                || linear.is_empty() && unrestricted.contains(&Self::Unrestricted(-ast.clone())),
+                |only| *only == Self::Linear(-ast.clone()),
+            ),
+            Ast::One | Ast::Bang(_) => linear.is_empty(),
+            Ast::Zero => false,
+            Ast::Bottom
+            | Ast::Top
+            | Ast::Quest(_)
+            | Ast::Dual(_)
+            | Ast::Times(..)
+            | Ast::Par(..)
+            | Ast::With(..)
+            | Ast::Plus(..)
+            | Ast::Lollipop(..) => true,
+        }
+    }
+
+    /// Rules for the focused formula `ast` with everything else in `context`.
+    #[inline]
+    fn focus(
+        ast: &Ast<A>,
+        context: &RhsOnlyWithExchange<Self>,
+    ) -> Vec<Rule<RhsOnlyWithExchange<Self>>> {
+        let (used_once, unrestricted): (Vec<_>, Vec<_>) = context
+            .rhs
+            .iter_repeat()
+            .cloned()
+            .partition(|item| matches!(*item, Self::Linear(_)));
+        let linear: Multiset<Self> = used_once.into_iter().collect();
+        if !Self::fits(ast, &linear, &unrestricted) {
+            return vec![];
+        }
+        match *ast {
+            Ast::One => vec![Rule::new("1", [])],
+            Ast::Value(_) => vec![Rule::new("axiom", [])],
+            Ast::Bang(ref arg) => vec![Rule::new(
+                "!",
+                [context.with([Self::Linear(arg.as_ref().clone())])],
+            )],
+            Ast::Times(ref lhs, ref rhs) => {
+                let splits: Vec<_> = if lhs == rhs {
+                    linear.unordered_partitions().collect()
+                } else {
+                    linear.partitions().collect()
+                };
+                splits
+                    .into_iter()
+                    // Skip splits that leave either focus unable to close, rather than enqueueing dead ends.
+                    .filter(|&(ref lctx, ref rctx)| {
+                        Self::fits(lhs, lctx, &unrestricted) && Self::fits(rhs, rctx, &unrestricted)
+                    })
+                    .map(|(lctx, rctx)| {
+                        Rule::new(
+                            "\u{2297}",
+                            [
+                                RhsOnlyWithExchange::new(lctx.with(
+                                    unrestricted.iter().cloned().chain([Self::keep_focus(lhs)]),
+                                )),
+                                RhsOnlyWithExchange::new(rctx.with(
+                                    unrestricted.iter().cloned().chain([Self::keep_focus(rhs)]),
+                                )),
+                            ],
+                        )
+                    })
+                    .collect()
+            }
+            Ast::Plus(ref lhs, ref rhs) => vec![
+                Rule::new("+L", [context.with([Self::keep_focus(lhs)])]),
+                Rule::new("+R", [context.with([Self::keep_focus(rhs)])]),
+            ],
+            Ast::Bottom
+            | Ast::Top
+            | Ast::Zero
+            | Ast::Quest(_)
+            | Ast::Dual(_)
+            | Ast::Par(..)
+            | Ast::With(..)
+            | Ast::Lollipop(..) => vec![],
+        }
+    }
+}
+
+impl<A: Atom> Infer<RhsOnlyWithExchange<Self>> for Focused<A> {
+    #[inline]
+    fn above(&self, context: RhsOnlyWithExchange<Self>) -> Vec<Rule<RhsOnlyWithExchange<Self>>> {
+        if let Self::Linear(ref ast) = *self {
+            if self.is_asynchronous() {
+                return match *ast {
+                    Ast::Top => vec![Rule::invertible("\u{22a4}", [])],
+                    Ast::Bottom => vec![Rule::invertible("\u{22a5}", [context])],
+                    Ast::Par(ref lhs, ref rhs) => vec![Rule::invertible(
+                        "\u{214b}",
+                        [context.with([
+                            Self::Linear(lhs.as_ref().clone()),
+                            Self::Linear(rhs.as_ref().clone()),
+                        ])],
+                    )],
+                    Ast::With(ref lhs, ref rhs) => vec![Rule::invertible(
+                        "&",
+                        [
+                            context.with([Self::Linear(lhs.as_ref().clone())]),
+                            context.with([Self::Linear(rhs.as_ref().clone())]),
+                        ],
+                    )],
+                    Ast::Quest(ref arg) => {
+                        let unrestricted = Self::Unrestricted(arg.as_ref().clone());
+                        vec![Rule::invertible(
+                            "?",
+                            // Already usable as often as we like, so another copy adds nothing.
+                            [if context.rhs.contains(&unrestricted) {
+                                context
+                            } else {
+                                context.with([unrestricted])
+                            }],
+                        )]
+                    }
+                    // Positive, or outside negation normal form, which `prove_focused` never builds.
+                    Ast::One
+                    | Ast::Zero
+                    | Ast::Value(_)
+                    | Ast::Bang(_)
+                    | Ast::Dual(_)
+                    | Ast::Times(..)
+                    | Ast::Plus(..)
+                    | Ast::Lollipop(..) => vec![],
+                };
+            }
+        }
+        if context
+            .rhs
+            .iter_unique()
+            .any(|(item, _)| item.is_asynchronous())
+        {
+            // Finish every invertible rule before committing to anything.
+            return vec![];
+        }
+        match *self {
+            Self::Focus(ref ast) => Self::focus(ast, &context),
+            _ if context
+                .rhs
+                .iter_unique()
+                .any(|(item, _)| matches!(*item, Self::Focus(_))) =>
+            {
+                vec![]
+            }
+            // Deciding on a positive formula and taking its first step happen at once.
+            Self::Linear(ref ast) => match ast.polarity() {
+                Polarity::Positive => Self::focus(ast, &context),
+                Polarity::Negative => vec![],
+            },
+            Self::Unrestricted(ref ast) => match ast.polarity() {
+                Polarity::Positive => Self::focus(ast, &context.with([self.clone()])),
+                Polarity::Negative => vec![Rule::new(
+                    "?D",
+                    [context.with([self.clone(), Self::Linear(ast.clone())])],
+                )],
+            },
+        }
+    }
+}
+
+/// Attempt to prove an expression by focused proof search,
+/// which commits to far fewer choices than `prove` on formulas that alternate `⅋` and `⊗`.
+/// # Errors
+/// If we can't.
+#[inline]
+pub fn prove_focused<A: Atom>(
+    expr: Ast<A>,
+) -> Result<Tree<RhsOnlyWithExchange<Focused<A>>>, Error> {
+    prove(Focused::from(expr))
+}
@@ -0,0 +1,1220 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Classical linear logic in a one-sided sequent calculus,
+//! with formulas that prove themselves, a parser, and a focused variant.
+//! <https://en.wikipedia.org/wiki/Linear_logic#Sequent_calculus_presentation>
+
+mod focused;
+mod parse;
+
+#[cfg(test)]
+mod test;
+
+pub use {
+    focused::{prove_focused, Focused},
+    parse::{parse_ast, ParseError},
+};
+
+use crate::{
+    hash::HashSet, prove, prove_with_stats, sequents::RhsOnlyWithExchange, Error, Infer,
+    MaybeSendSync, Multiset, Rule, SearchStats, Sequent as _, Tree,
+};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
+
+/// Write a formula inline, with the same precedence as `parse_ast`, checked at compile time.
+///
+/// Units are `1`, `0`, `top`, `bot`; atoms `P 0`, `P 1`, ...; any Rust expression in braces, e.g. `{ast}`;
+/// prefix `!`, `?`, `~`; and infix, from loosest to tightest,
+/// `-o` (to the right), `par`, `*`, `+`, `&` (all to the left).
+///
+/// For example, `ll!(!(P 0) * (P 1 + 0) -o bot)` means `(!P0 ⊗ (P1 ⊕ 0)) ⊸ ⊥`.
+#[macro_export]
+macro_rules! ll {
+    // Linear implication: split at the first `-o`, since it associates to the right.
+    (@lolli [] - o $($rest:tt)*) => {
+        compile_error!("expected a formula before `-o`")
+    };
+    (@lolli [$($lhs:tt)+] - o) => {
+        compile_error!("expected a formula after `-o`")
+    };
+    (@lolli [$($lhs:tt)+] - o $($rest:tt)+) => {
+        $crate::logics::classical_linear::Ast::lollipop($crate::ll!(@par [] [] $($lhs)+), $crate::ll!(@lolli [] $($rest)+))
+    };
+    (@lolli [$($lhs:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::ll!(@lolli [$($lhs)* $next] $($rest)*)
+    };
+    (@lolli [$($lhs:tt)+]) => {
+        $crate::ll!(@par [] [] $($lhs)+)
+    };
+
+    // Multiplicative disjunction: fold everything so far into `$acc` at each `par`.
+    (@par [$($acc:tt)?] [] par $($rest:tt)*) => {
+        compile_error!("expected a formula before `par`")
+    };
+    (@par [] [$($cur:tt)+] par $($rest:tt)*) => {
+        $crate::ll!(@par [($crate::ll!(@times [] [] $($cur)+))] [] $($rest)*)
+    };
+    (@par [$acc:tt] [$($cur:tt)+] par $($rest:tt)*) => {
+        $crate::ll!(@par [($acc.par($crate::ll!(@times [] [] $($cur)+)))] [] $($rest)*)
+    };
+    (@par [$($acc:tt)?] [$($cur:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::ll!(@par [$($acc)?] [$($cur)* $next] $($rest)*)
+    };
+    (@par [$acc:tt] []) => {
+        compile_error!("expected a formula after `par`")
+    };
+    (@par [] [$($cur:tt)+]) => {
+        $crate::ll!(@times [] [] $($cur)+)
+    };
+    (@par [$acc:tt] [$($cur:tt)+]) => {
+        $acc.par($crate::ll!(@times [] [] $($cur)+))
+    };
+
+    // Multiplicative conjunction.
+    (@times [$($acc:tt)?] [] * $($rest:tt)*) => {
+        compile_error!("expected a formula before `*`")
+    };
+    (@times [] [$($cur:tt)+] * $($rest:tt)*) => {
+        $crate::ll!(@times [($crate::ll!(@plus [] [] $($cur)+))] [] $($rest)*)
+    };
+    (@times [$acc:tt] [$($cur:tt)+] * $($rest:tt)*) => {
+        $crate::ll!(@times [($acc * $crate::ll!(@plus [] [] $($cur)+))] [] $($rest)*)
+    };
+    (@times [$($acc:tt)?] [$($cur:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::ll!(@times [$($acc)?] [$($cur)* $next] $($rest)*)
+    };
+    (@times [$acc:tt] []) => {
+        compile_error!("expected a formula after `*`")
+    };
+    (@times [] [$($cur:tt)+]) => {
+        $crate::ll!(@plus [] [] $($cur)+)
+    };
+    (@times [$acc:tt] [$($cur:tt)+]) => {
+        ($acc * $crate::ll!(@plus [] [] $($cur)+))
+    };
+
+    // Additive disjunction.
+    (@plus [$($acc:tt)?] [] + $($rest:tt)*) => {
+        compile_error!("expected a formula before `+`")
+    };
+    (@plus [] [$($cur:tt)+] + $($rest:tt)*) => {
+        $crate::ll!(@plus [($crate::ll!(@with [] [] $($cur)+))] [] $($rest)*)
+    };
+    (@plus [$acc:tt] [$($cur:tt)+] + $($rest:tt)*) => {
+        $crate::ll!(@plus [($acc + $crate::ll!(@with [] [] $($cur)+))] [] $($rest)*)
+    };
+    (@plus [$($acc:tt)?] [$($cur:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::ll!(@plus [$($acc)?] [$($cur)* $next] $($rest)*)
+    };
+    (@plus [$acc:tt] []) => {
+        compile_error!("expected a formula after `+`")
+    };
+    (@plus [] [$($cur:tt)+]) => {
+        $crate::ll!(@with [] [] $($cur)+)
+    };
+    (@plus [$acc:tt] [$($cur:tt)+]) => {
+        ($acc + $crate::ll!(@with [] [] $($cur)+))
+    };
+
+    // Additive conjunction.
+    (@with [$($acc:tt)?] [] & $($rest:tt)*) => {
+        compile_error!("expected a formula before `&`")
+    };
+    (@with [] [$($cur:tt)+] & $($rest:tt)*) => {
+        $crate::ll!(@with [($crate::ll!(@prefix $($cur)+))] [] $($rest)*)
+    };
+    (@with [$acc:tt] [$($cur:tt)+] & $($rest:tt)*) => {
+        $crate::ll!(@with [($acc & $crate::ll!(@prefix $($cur)+))] [] $($rest)*)
+    };
+    (@with [$($acc:tt)?] [$($cur:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::ll!(@with [$($acc)?] [$($cur)* $next] $($rest)*)
+    };
+    (@with [$acc:tt] []) => {
+        compile_error!("expected a formula after `&`")
+    };
+    (@with [] [$($cur:tt)+]) => {
+        $crate::ll!(@prefix $($cur)+)
+    };
+    (@with [$acc:tt] [$($cur:tt)+]) => {
+        ($acc & $crate::ll!(@prefix $($cur)+))
+    };
+
+    // Prefix operators, units, atoms, and anything in parentheses or braces.
+    (@prefix ! $($arg:tt)+) => {
+        $crate::logics::classical_linear::bang($crate::ll!(@prefix $($arg)+))
+    };
+    (@prefix ? $($arg:tt)+) => {
+        $crate::logics::classical_linear::quest($crate::ll!(@prefix $($arg)+))
+    };
+    (@prefix ~ $($arg:tt)+) => {
+        -$crate::ll!(@prefix $($arg)+)
+    };
+    (@prefix 1) => {
+        $crate::logics::classical_linear::Ast::One
+    };
+    (@prefix 0) => {
+        $crate::logics::classical_linear::Ast::Zero
+    };
+    (@prefix top) => {
+        $crate::logics::classical_linear::Ast::Top
+    };
+    (@prefix bot) => {
+        $crate::logics::classical_linear::Ast::Bottom
+    };
+    (@prefix P $i:literal) => {
+        $crate::logics::classical_linear::Ast::Value($i)
+    };
+    (@prefix ($($inner:tt)+)) => {
+        $crate::ll!(@lolli [] $($inner)+)
+    };
+    (@prefix {$ast:expr}) => {
+        $ast
+    };
+    (@prefix $($other:tt)*) => {
+        compile_error!(concat!("expected a formula, found `", stringify!($($other)*), "`"))
+    };
+
+    () => {
+        compile_error!("expected a formula")
+    };
+    ($($formula:tt)+) => {
+        $crate::ll!(@lolli [] $($formula)+)
+    };
+}
+
+/// Anything that can stand in for a raw value (i.e. a propositional atom) in an `Ast`.
+pub trait Atom:
+    Clone + core::fmt::Debug + core::fmt::Display + core::hash::Hash + MaybeSendSync + Ord
+{
+    /// Print this atom inside a formula (by default, exactly as `Display` would).
+    /// # Errors
+    /// If the formatter does.
+    #[inline]
+    fn fmt_atom(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self}")
+    }
+}
+
+impl Atom for usize {
+    /// Prefix with `P`, since bare numbers would look like units.
+    #[inline]
+    fn fmt_atom(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "P{self}")
+    }
+}
+
+impl Atom for &'static str {}
+
+impl Atom for String {}
+
+/// Abstract syntax tree whose raw values are plain numbers.
+pub type IndexedAst = Ast<usize>;
+
+/// Abstract syntax tree for linear logic with sequent-calculus proof search built in.
+///
+/// Subexpressions live behind reference counts, so cloning an expression never copies
+/// the tree underneath, and comparing two shared subexpressions checks their pointers first.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Ast<A: Atom = usize> {
+    /// Unit for multiplicative conjunction.
+    One,
+    /// Unit for multiplicative disjunction.
+    Bottom,
+    /// Unit for additive conjunction.
+    Top,
+    /// Unit for additive disjunction.
+    Zero,
+    /// Raw value, i.e. a propositional atom.
+    Value(A),
+    /// The "of course" exponential.
+    Bang(Arc<Self>),
+    /// The "why not" exponential.
+    Quest(Arc<Self>),
+    /// Dual, i.e. linear negation.
+    Dual(Arc<Self>),
+    /// Multiplicative conjunction.
+    Times(Arc<Self>, Arc<Self>),
+    /// Multiplicative disjunction.
+    Par(Arc<Self>, Arc<Self>),
+    /// Additive conjunction.
+    With(Arc<Self>, Arc<Self>),
+    /// Additive disjunction.
+    Plus(Arc<Self>, Arc<Self>),
+    /// Linear implication, which means the same as `~A ⅋ B`.
+    Lollipop(Arc<Self>, Arc<Self>),
+}
+
+impl<A: Atom> core::fmt::Display for Ast<A> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::One => write!(f, "1"),
+            Self::Bottom => write!(f, "\u{22a5}"),
+            Self::Top => write!(f, "\u{22a4}"),
+            Self::Zero => write!(f, "0"),
+            Self::Value(ref atom) => atom.fmt_atom(f),
+            Self::Bang(ref arg) => {
+                write!(f, "!")?;
+                arg.fmt_within(f, arg.infix().is_some())
+            }
+            Self::Quest(ref arg) => {
+                write!(f, "?")?;
+                arg.fmt_within(f, arg.infix().is_some())
+            }
+            Self::Dual(ref arg) => {
+                write!(f, "~")?;
+                arg.fmt_within(f, arg.infix().is_some())
+            }
+            Self::Times(ref lhs, ref rhs)
+            | Self::Par(ref lhs, ref rhs)
+            | Self::With(ref lhs, ref rhs)
+            | Self::Plus(ref lhs, ref rhs)
+            | Self::Lollipop(ref lhs, ref rhs) => {
+                let op = self.infix().unwrap_or(Infix::Par);
+                let right = op.is_right_associative();
+                lhs.fmt_within(
+                    f,
+                    lhs.infix()
+                        .is_some_and(|inner| inner < op || (right && inner == op)),
+                )?;
+                write!(f, " {} ", op.symbol())?;
+                rhs.fmt_within(
+                    f,
+                    rhs.infix()
+                        .is_some_and(|inner| inner < op || (!right && inner == op)),
+                )
+            }
+        }
+    }
+}
+
+/// Infix operator, ordered from loosest to tightest binding.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum Infix {
+    /// Linear implication, associating to the right.
+    Implies,
+    /// Multiplicative disjunction.
+    Par,
+    /// Multiplicative conjunction.
+    Times,
+    /// Additive disjunction.
+    Plus,
+    /// Additive conjunction.
+    With,
+}
+
+impl Infix {
+    /// How this operator prints.
+    #[inline]
+    const fn symbol(self) -> &'static str {
+        match self {
+            Self::Implies => "\u{22b8}",
+            Self::Par => "\u{214b}",
+            Self::Times => "\u{2297}",
+            Self::Plus => "\u{2295}",
+            Self::With => "&",
+        }
+    }
+
+    /// Whether `A op B op C` means `A op (B op C)` rather than `(A op B) op C`.
+    #[inline]
+    fn is_right_associative(self) -> bool {
+        self == Self::Implies
+    }
+
+    /// Combine two operands with this operator.
+    #[inline]
+    #[allow(clippy::arithmetic_side_effects)]
+    fn apply<A: Atom>(self, lhs: Ast<A>, rhs: Ast<A>) -> Ast<A> {
+        match self {
+            Self::Implies => lhs.lollipop(rhs),
+            Self::Par => lhs.par(rhs),
+            Self::Times => lhs * rhs,
+            Self::Plus => lhs + rhs,
+            Self::With => lhs & rhs,
+        }
+    }
+}
+
+/// The "of course" exponential.
+#[must_use]
+#[inline(always)]
+pub fn bang<A: Atom>(arg: Ast<A>) -> Ast<A> {
+    Ast::Bang(Arc::new(arg))
+}
+
+/// The "why not" exponential.
+#[must_use]
+#[inline(always)]
+pub fn quest<A: Atom>(arg: Ast<A>) -> Ast<A> {
+    Ast::Quest(Arc::new(arg))
+}
+
+impl<A: Atom> Ast<A> {
+    /// Outermost infix operator, if any.
+    #[inline]
+    const fn infix(&self) -> Option<Infix> {
+        match *self {
+            Self::Times(..) => Some(Infix::Times),
+            Self::Par(..) => Some(Infix::Par),
+            Self::With(..) => Some(Infix::With),
+            Self::Plus(..) => Some(Infix::Plus),
+            Self::Lollipop(..) => Some(Infix::Implies),
+            Self::One
+            | Self::Bottom
+            | Self::Top
+            | Self::Zero
+            | Self::Value(_)
+            | Self::Bang(_)
+            | Self::Quest(_)
+            | Self::Dual(_) => None,
+        }
+    }
+
+    /// Print this as an operand, in parentheses if `parenthesize` is set.
+    #[inline]
+    fn fmt_within(
+        &self,
+        f: &mut core::fmt::Formatter<'_>,
+        parenthesize: bool,
+    ) -> core::fmt::Result {
+        if parenthesize {
+            write!(f, "({self})")
+        } else {
+            write!(f, "{self}")
+        }
+    }
+
+    /// Linear implication, i.e. `~self ⅋ rhs` (but printed as `self ⊸ rhs`).
+    #[must_use]
+    #[inline(always)]
+    pub fn lollipop(self, rhs: Self) -> Self {
+        Self::Lollipop(Arc::new(self), Arc::new(rhs))
+    }
+
+    /// Par operator, since it's a pain in the ass to type.
+    #[must_use]
+    #[inline(always)]
+    pub fn par(self, rhs: Self) -> Self {
+        Self::Par(Arc::new(self), Arc::new(rhs))
+    }
+
+    /// Attempt to prove this expression, returning the whole derivation if we can.
+    /// # Errors
+    /// If we can't.
+    #[inline(always)]
+    pub fn prove(self) -> Result<Tree<RhsOnlyWithExchange<Self>>, Error> {
+        prove(self)
+    }
+
+    /// Attempt to prove this expression after rewriting it into negation normal form,
+    /// which skips one De Morgan inference per connective under a dual.
+    /// # Errors
+    /// If we can't.
+    #[inline(always)]
+    pub fn prove_nnf(self) -> Result<Tree<RhsOnlyWithExchange<Self>>, Error> {
+        prove(self.nnf())
+    }
+
+    /// Negation normal form: push every dual inward (by De Morgan's laws) until it wraps only a raw value.
+    #[must_use]
+    #[inline(always)]
+    pub fn nnf(self) -> Self {
+        self.nnf_negated(false)
+    }
+
+    /// Negation normal form of either this expression or (if `negate` is set) its dual.
+    #[must_use]
+    #[inline]
+    #[allow(clippy::arithmetic_side_effects)]
+    fn nnf_negated(&self, negate: bool) -> Self {
+        match (self, negate) {
+            (&Self::One, false) | (&Self::Bottom, true) => Self::One,
+            (&Self::Bottom, false) | (&Self::One, true) => Self::Bottom,
+            (&Self::Top, false) | (&Self::Zero, true) => Self::Top,
+            (&Self::Zero, false) | (&Self::Top, true) => Self::Zero,
+            (value @ &Self::Value(_), false) => value.clone(),
+            (value @ &Self::Value(_), true) => Self::Dual(Arc::new(value.clone())),
+            (&Self::Dual(ref arg), _) => arg.nnf_negated(!negate),
+            (&Self::Bang(ref arg), false) => bang(arg.nnf_negated(false)),
+            (&Self::Quest(ref arg), true) => bang(arg.nnf_negated(true)),
+            (&Self::Quest(ref arg), false) => quest(arg.nnf_negated(false)),
+            (&Self::Bang(ref arg), true) => quest(arg.nnf_negated(true)),
+            (&Self::Times(ref lhs, ref rhs), false) => {
+                lhs.nnf_negated(false) * rhs.nnf_negated(false)
+            }
+            (&Self::Par(ref lhs, ref rhs), true) => lhs.nnf_negated(true) * rhs.nnf_negated(true),
+            (&Self::Par(ref lhs, ref rhs), false) => {
+                lhs.nnf_negated(false).par(rhs.nnf_negated(false))
+            }
+            (&Self::Times(ref lhs, ref rhs), true) => {
+                lhs.nnf_negated(true).par(rhs.nnf_negated(true))
+            }
+            (&Self::With(ref lhs, ref rhs), false) => {
+                lhs.nnf_negated(false) & rhs.nnf_negated(false)
+            }
+            (&Self::Plus(ref lhs, ref rhs), true) => lhs.nnf_negated(true) & rhs.nnf_negated(true),
+            (&Self::Plus(ref lhs, ref rhs), false) => {
+                lhs.nnf_negated(false) + rhs.nnf_negated(false)
+            }
+            (&Self::Lollipop(ref lhs, ref rhs), false) => {
+                lhs.nnf_negated(true).par(rhs.nnf_negated(false))
+            }
+            (&Self::Lollipop(ref lhs, ref rhs), true) => {
+                lhs.nnf_negated(false) * rhs.nnf_negated(true)
+            }
+            (&Self::With(ref lhs, ref rhs), true) => lhs.nnf_negated(true) + rhs.nnf_negated(true),
+        }
+    }
+
+    /// Every node in this expression, itself included, without recursing
+    /// (so arbitrarily deep expressions can't overflow the stack).
+    #[inline]
+    fn nodes(&self) -> impl Iterator<Item = &Self> {
+        let mut stack = vec![self];
+        core::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children());
+            Some(node)
+        })
+    }
+
+    /// Immediate subexpressions, left to right.
+    #[inline]
+    fn children(&self) -> impl Iterator<Item = &Self> {
+        let (first, second) = match *self {
+            Self::One | Self::Bottom | Self::Top | Self::Zero | Self::Value(_) => (None, None),
+            Self::Bang(ref arg) | Self::Quest(ref arg) | Self::Dual(ref arg) => {
+                (Some(&**arg), None)
+            }
+            Self::Times(ref lhs, ref rhs)
+            | Self::Par(ref lhs, ref rhs)
+            | Self::With(ref lhs, ref rhs)
+            | Self::Plus(ref lhs, ref rhs)
+            | Self::Lollipop(ref lhs, ref rhs) => (Some(&**lhs), Some(&**rhs)),
+        };
+        first.into_iter().chain(second)
+    }
+
+    /// Total number of connectives, units, and raw values.
+    #[must_use]
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.nodes().count()
+    }
+
+    /// Number of nodes on the longest path from the outermost connective to a unit or raw value.
+    #[must_use]
+    #[inline]
+    pub fn depth(&self) -> usize {
+        let mut deepest = 0;
+        let mut stack = vec![(self, 1_usize)];
+        while let Some((node, depth)) = stack.pop() {
+            deepest = deepest.max(depth);
+            stack.extend(
+                node.children()
+                    .map(|child| (child, depth.saturating_add(1))),
+            );
+        }
+        deepest
+    }
+
+    /// Every distinct raw value in this expression.
+    #[must_use]
+    #[inline]
+    pub fn atoms(&self) -> BTreeSet<A> {
+        self.nodes().filter_map(Self::value).cloned().collect()
+    }
+
+    /// Every raw value in this expression, as many times as it occurs.
+    #[must_use]
+    #[inline]
+    pub fn atom_occurrences(&self) -> Multiset<A> {
+        self.nodes().filter_map(Self::value).cloned().collect()
+    }
+
+    /// The atom itself, if this is a raw value.
+    #[must_use]
+    #[inline]
+    const fn value(&self) -> Option<&A> {
+        match *self {
+            Self::Value(ref atom) => Some(atom),
+            Self::One
+            | Self::Bottom
+            | Self::Top
+            | Self::Zero
+            | Self::Bang(_)
+            | Self::Quest(_)
+            | Self::Dual(_)
+            | Self::Times(..)
+            | Self::Par(..)
+            | Self::With(..)
+            | Self::Plus(..)
+            | Self::Lollipop(..) => None,
+        }
+    }
+
+    /// Replace every raw value that appears in `map` with a copy of the expression it maps to.
+    #[must_use]
+    #[inline]
+    pub fn substitute(&self, map: &BTreeMap<A, Self>) -> Self {
+        self.map_values(&mut |atom| {
+            map.get(atom)
+                .cloned()
+                .unwrap_or_else(|| Self::Value(atom.clone()))
+        })
+    }
+
+    /// Rename every raw value, possibly into a different type of atom.
+    #[must_use]
+    #[inline]
+    pub fn rename_atoms<B: Atom, F: FnMut(A) -> B>(&self, mut f: F) -> Ast<B> {
+        self.map_values(&mut |atom| Ast::Value(f(atom.clone())))
+    }
+
+    /// Replace every raw value with whatever `f` makes of it, keeping the structure around it.
+    #[must_use]
+    #[inline]
+    #[allow(clippy::arithmetic_side_effects)]
+    fn map_values<B: Atom, F: FnMut(&A) -> Ast<B>>(&self, f: &mut F) -> Ast<B> {
+        match *self {
+            Self::One => Ast::One,
+            Self::Bottom => Ast::Bottom,
+            Self::Top => Ast::Top,
+            Self::Zero => Ast::Zero,
+            Self::Value(ref atom) => f(atom),
+            Self::Bang(ref arg) => bang(arg.map_values(f)),
+            Self::Quest(ref arg) => quest(arg.map_values(f)),
+            Self::Dual(ref arg) => Ast::Dual(Arc::new(arg.map_values(f))),
+            Self::Times(ref lhs, ref rhs) => lhs.map_values(f) * rhs.map_values(f),
+            Self::Par(ref lhs, ref rhs) => lhs.map_values(f).par(rhs.map_values(f)),
+            Self::With(ref lhs, ref rhs) => lhs.map_values(f) & rhs.map_values(f),
+            Self::Plus(ref lhs, ref rhs) => lhs.map_values(f) + rhs.map_values(f),
+            Self::Lollipop(ref lhs, ref rhs) => lhs.map_values(f).lollipop(rhs.map_values(f)),
+        }
+    }
+
+    /// Whether every dual in this expression wraps only a raw value.
+    #[must_use]
+    #[inline]
+    pub fn is_nnf(&self) -> bool {
+        match *self {
+            Self::One | Self::Bottom | Self::Top | Self::Zero | Self::Value(_) => true,
+            Self::Dual(ref arg) => matches!(**arg, Self::Value(_)),
+            // Implication hides a dual on its left.
+            Self::Lollipop(..) => false,
+            Self::Bang(ref arg) | Self::Quest(ref arg) => arg.is_nnf(),
+            Self::Times(ref lhs, ref rhs)
+            | Self::Par(ref lhs, ref rhs)
+            | Self::With(ref lhs, ref rhs)
+            | Self::Plus(ref lhs, ref rhs) => lhs.is_nnf() && rhs.is_nnf(),
+        }
+    }
+
+    /// Whether `dual` is syntactically the linear negation of `self`.
+    #[must_use]
+    #[inline]
+    pub fn is_dual_of(&self, dual: &Self) -> bool {
+        matches!(*dual, Self::Dual(ref arg) if **arg == *self)
+    }
+
+    /// Whether this formula's rule is invertible (negative) or commits to a choice (positive),
+    /// with raw values positive and their duals negative.
+    #[must_use]
+    #[inline]
+    pub fn polarity(&self) -> Polarity {
+        match *self {
+            Self::One
+            | Self::Zero
+            | Self::Value(_)
+            | Self::Bang(_)
+            | Self::Times(..)
+            | Self::Plus(..) => Polarity::Positive,
+            Self::Bottom
+            | Self::Top
+            | Self::Quest(_)
+            | Self::Par(..)
+            | Self::With(..)
+            | Self::Lollipop(..) => Polarity::Negative,
+            Self::Dual(ref arg) => match arg.polarity() {
+                Polarity::Positive => Polarity::Negative,
+                Polarity::Negative => Polarity::Positive,
+            },
+        }
+    }
+    /// The smallest fragment of linear logic this formula lies in, judging by which connectives occur.
+    #[must_use]
+    #[inline]
+    pub fn fragment(&self) -> Fragment {
+        let (mut additive, mut exponential) = (false, false);
+        for node in self.nodes() {
+            match *node {
+                Self::Top | Self::Zero | Self::With(..) | Self::Plus(..) => additive = true,
+                Self::Bang(_) | Self::Quest(_) => exponential = true,
+                Self::One
+                | Self::Bottom
+                | Self::Value(_)
+                | Self::Dual(_)
+                | Self::Times(..)
+                | Self::Par(..)
+                | Self::Lollipop(..) => {}
+            }
+        }
+        match (additive, exponential) {
+            (false, false) => Fragment::Mll,
+            (true, false) => Fragment::Mall,
+            (false, true) => Fragment::Mell,
+            (true, true) => Fragment::Full,
+        }
+    }
+
+    /// Add one to each raw value's count for every positive occurrence (as it would appear in negation normal form)
+    /// and subtract one for every negative occurrence, or the other way around if `negate` is set.
+    #[inline]
+    fn count_signed_atoms(&self, negate: bool, counts: &mut BTreeMap<A, i64>) {
+        match *self {
+            Self::One | Self::Bottom | Self::Top | Self::Zero => {}
+            Self::Value(ref atom) => {
+                let count = counts.entry(atom.clone()).or_default();
+                *count = if negate {
+                    count.saturating_sub(1)
+                } else {
+                    count.saturating_add(1)
+                };
+            }
+            Self::Dual(ref arg) => arg.count_signed_atoms(!negate, counts),
+            Self::Bang(ref arg) | Self::Quest(ref arg) => arg.count_signed_atoms(negate, counts),
+            Self::Lollipop(ref lhs, ref rhs) => {
+                lhs.count_signed_atoms(!negate, counts);
+                rhs.count_signed_atoms(negate, counts);
+            }
+            Self::Times(ref lhs, ref rhs)
+            | Self::Par(ref lhs, ref rhs)
+            | Self::With(ref lhs, ref rhs)
+            | Self::Plus(ref lhs, ref rhs) => {
+                lhs.count_signed_atoms(negate, counts);
+                rhs.count_signed_atoms(negate, counts);
+            }
+        }
+    }
+}
+
+/// Whether every raw value occurs as often positively as negatively across a whole sequent:
+/// every axiom uses up one of each, so no MLL sequent that isn't balanced is provable.
+#[must_use]
+#[inline]
+pub fn is_balanced<A: Atom>(sequent: &RhsOnlyWithExchange<Ast<A>>) -> bool {
+    let mut counts = BTreeMap::new();
+    for ast in sequent.rhs.iter_repeat() {
+        ast.count_signed_atoms(false, &mut counts);
+    }
+    counts.values().all(|&count| count == 0)
+}
+
+/// Shares one allocation among all structurally equal subexpressions passed through it,
+/// so large formulas with repeated parts take only as much memory as their distinct parts.
+#[derive(Clone, Debug)]
+pub struct Interner<A: Atom = usize> {
+    /// Every distinct subexpression seen so far.
+    seen: HashSet<Arc<Ast<A>>>,
+}
+
+impl<A: Atom> Default for Interner<A> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Atom> Interner<A> {
+    /// Nothing interned yet.
+    #[must_use]
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self {
+            seen: HashSet::new(),
+        }
+    }
+
+    /// Number of distinct subexpressions interned so far.
+    #[must_use]
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    /// Whether nothing has been interned yet.
+    #[must_use]
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+
+    /// Equal to `ast`, but sharing every subexpression with any equal one interned before.
+    #[must_use]
+    #[inline]
+    pub fn intern(&mut self, ast: &Ast<A>) -> Ast<A> {
+        match *ast {
+            Ast::One | Ast::Bottom | Ast::Top | Ast::Zero | Ast::Value(_) => ast.clone(),
+            Ast::Bang(ref arg) => Ast::Bang(self.share(arg)),
+            Ast::Quest(ref arg) => Ast::Quest(self.share(arg)),
+            Ast::Dual(ref arg) => Ast::Dual(self.share(arg)),
+            Ast::Times(ref lhs, ref rhs) => Ast::Times(self.share(lhs), self.share(rhs)),
+            Ast::Par(ref lhs, ref rhs) => Ast::Par(self.share(lhs), self.share(rhs)),
+            Ast::With(ref lhs, ref rhs) => Ast::With(self.share(lhs), self.share(rhs)),
+            Ast::Plus(ref lhs, ref rhs) => Ast::Plus(self.share(lhs), self.share(rhs)),
+            Ast::Lollipop(ref lhs, ref rhs) => Ast::Lollipop(self.share(lhs), self.share(rhs)),
+        }
+    }
+
+    /// The one shared allocation for anything equal to `ast`.
+    #[inline]
+    fn share(&mut self, ast: &Arc<Ast<A>>) -> Arc<Ast<A>> {
+        let interned = self.intern(ast);
+        if let Some(existing) = self.seen.get(&interned) {
+            return Arc::clone(existing);
+        }
+        let shared = Arc::new(interned);
+        let _ = self.seen.insert(Arc::clone(&shared));
+        shared
+    }
+}
+
+impl<A: Atom> core::ops::Mul<Self> for Ast<A> {
+    type Output = Self;
+    #[inline(always)]
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::Times(Arc::new(self), Arc::new(rhs))
+    }
+}
+
+impl<A: Atom> core::ops::BitAnd<Self> for Ast<A> {
+    type Output = Self;
+    #[inline(always)]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self::With(Arc::new(self), Arc::new(rhs))
+    }
+}
+
+impl<A: Atom> core::ops::Add<Self> for Ast<A> {
+    type Output = Self;
+    #[inline(always)]
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::Plus(Arc::new(self), Arc::new(rhs))
+    }
+}
+
+impl<A: Atom> core::ops::Sub<Self> for Ast<A> {
+    type Output = Self;
+    #[inline(always)]
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.lollipop(rhs)
+    }
+}
+
+impl<A: Atom> core::ops::Neg for Ast<A> {
+    type Output = Self;
+    #[inline(always)]
+    #[allow(clippy::arithmetic_side_effects)]
+    fn neg(self) -> Self::Output {
+        Self::Dual(Arc::new(self))
+    }
+}
+
+impl<A: Atom> Infer<RhsOnlyWithExchange<Self>> for Ast<A> {
+    #[inline]
+    fn initial(sequent: &RhsOnlyWithExchange<Self>) -> Option<&'static str> {
+        if sequent.rhs.contains(&Self::Top) {
+            Some("\u{22a4}")
+        } else if sequent.rhs.len() == 2 && sequent.dual_pair(Self::is_dual_of).is_some() {
+            Some("axiom")
+        } else {
+            None
+        }
+    }
+    /// Every atom this formula mentions, and its dual.
+    #[inline]
+    #[allow(clippy::arithmetic_side_effects)]
+    fn candidate_atoms(&self) -> Vec<Self> {
+        self.atoms()
+            .into_iter()
+            .flat_map(|atom| [Self::Value(atom.clone()), -Self::Value(atom)])
+            .collect()
+    }
+    #[inline]
+    fn above(&self, context: RhsOnlyWithExchange<Self>) -> Vec<Rule<RhsOnlyWithExchange<Self>>> {
+        match *self {
+            Self::Top => vec![Rule::invertible("\u{22a4}", [])],
+            Self::One if context.is_empty() => vec![Rule::axiom("1")],
+            Self::Bang(ref arg) if context.all(|ast| matches!(*ast, Self::Quest(_))) => {
+                vec![Rule::unary("!", context.with([arg.as_ref().clone()]))]
+            }
+            Self::One | Self::Zero | Self::Value(_) | Self::Bang(_) => vec![],
+            Self::Bottom => vec![Rule::invertible("\u{22a5}", [context])],
+            Self::Quest(ref arg) => vec![
+                Rule::unary("?W", context.clone()),
+                Rule::unary("?D", context.with([arg.as_ref().clone()])),
+                Rule::unary(
+                    "?C",
+                    context.with([Self::Quest(Arc::clone(arg)), Self::Quest(Arc::clone(arg))]),
+                ),
+            ],
+            Self::Dual(ref dual) => {
+                vec![Rule::unary(
+                    "~",
+                    context.with([match **dual {
+                        Self::One => Self::Bottom,
+                        Self::Bottom => Self::One,
+                        Self::Top => Self::Zero,
+                        Self::Zero => Self::Top,
+                        Self::Value(_) => return vec![],
+                        Self::Bang(ref arg) => Self::Quest(Arc::new(Self::Dual(Arc::clone(arg)))),
+                        Self::Quest(ref arg) => Self::Bang(Arc::new(Self::Dual(Arc::clone(arg)))),
+                        Self::Dual(ref arg) => arg.as_ref().clone(),
+                        Self::Times(ref lhs, ref rhs) => Self::Par(
+                            Arc::new(Self::Dual(Arc::clone(lhs))),
+                            Arc::new(Self::Dual(Arc::clone(rhs))),
+                        ),
+                        Self::Par(ref lhs, ref rhs) => Self::Times(
+                            Arc::new(Self::Dual(Arc::clone(lhs))),
+                            Arc::new(Self::Dual(Arc::clone(rhs))),
+                        ),
+                        Self::With(ref lhs, ref rhs) => Self::Plus(
+                            Arc::new(Self::Dual(Arc::clone(lhs))),
+                            Arc::new(Self::Dual(Arc::clone(rhs))),
+                        ),
+                        Self::Plus(ref lhs, ref rhs) => Self::With(
+                            Arc::new(Self::Dual(Arc::clone(lhs))),
+                            Arc::new(Self::Dual(Arc::clone(rhs))),
+                        ),
+                        Self::Lollipop(ref lhs, ref rhs) => {
+                            Self::Times(Arc::clone(lhs), Arc::new(Self::Dual(Arc::clone(rhs))))
+                        }
+                    }]),
+                )]
+            }
+            Self::Times(ref lhs, ref rhs) => {
+                let splits: Vec<_> = if lhs == rhs {
+                    // Mirrored splits would give the same premises, so take each only once.
+                    context.rhs.unordered_partitions().collect()
+                } else {
+                    context.rhs.partitions().collect()
+                };
+                splits
+                    .into_iter()
+                    .map(|(lctx, rctx)| {
+                        Rule::binary(
+                            "\u{2297}",
+                            RhsOnlyWithExchange::new(lctx.with([lhs.as_ref().clone()])),
+                            RhsOnlyWithExchange::new(rctx.with([rhs.as_ref().clone()])),
+                        )
+                    })
+                    .collect()
+            }
+            Self::Par(ref lhs, ref rhs) => vec![Rule::invertible(
+                "\u{214b}",
+                [context.with([lhs.as_ref().clone(), rhs.as_ref().clone()])],
+            )],
+            Self::Lollipop(ref lhs, ref rhs) => vec![Rule::unary(
+                "\u{22b8}",
+                context.with([Self::Dual(Arc::clone(lhs)), rhs.as_ref().clone()]),
+            )],
+            Self::With(ref lhs, ref rhs) => vec![Rule::invertible(
+                "&",
+                [
+                    context.with([lhs.as_ref().clone()]),
+                    context.with([rhs.as_ref().clone()]),
+                ],
+            )],
+            Self::Plus(ref lhs, ref rhs) => vec![
+                Rule::unary("+L", context.with([lhs.as_ref().clone()])),
+                Rule::unary("+R", context.with([rhs.as_ref().clone()])),
+            ],
+        }
+    }
+}
+
+/// Whether a formula's rule is invertible or commits to a choice.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Polarity {
+    /// Decomposing it means choosing (e.g. how to split a `⊗`), so it waits for a focus.
+    Positive,
+    /// Its rule is invertible, so it can be decomposed as soon as it shows up.
+    Negative,
+}
+
+/// Fragment of linear logic, by which connectives a formula may use.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Fragment {
+    /// Multiplicatives only (`⊗`, `⅋`, `⊸`, and their units), where provability is decidable in NP.
+    Mll,
+    /// Multiplicatives and additives (`&`, `⊕`, and their units), where provability is decidable.
+    Mall,
+    /// Multiplicatives and exponentials (`!` and `?`).
+    Mell,
+    /// Anything at all.
+    Full,
+}
+
+/// Derivation of a linear logic formula in a one-sided sequent calculus.
+pub type Derivation<A = usize> = Tree<RhsOnlyWithExchange<Ast<A>>>;
+
+/// Attempt to prove an expression with whichever procedure suits its fragment best:
+/// MLL formulas go to `prove_mll`, and everything else to the general search.
+/// # Errors
+/// If we can't.
+#[inline]
+pub fn prove_auto<A: Atom>(expr: Ast<A>) -> Result<Derivation<A>, Error> {
+    prove_auto_with_stats(expr).0
+}
+
+/// Attempt to prove an expression as `prove_auto` would,
+/// reporting how much work it took whether or not we succeed.
+#[inline]
+pub fn prove_auto_with_stats<A: Atom>(expr: Ast<A>) -> (Result<Derivation<A>, Error>, SearchStats) {
+    match expr.fragment() {
+        Fragment::Mll => prove_mll(expr),
+        Fragment::Mall | Fragment::Mell | Fragment::Full => prove_with_stats(expr),
+    }
+}
+
+/// Attempt to prove an MLL expression by depth-first search, and report how many sequents that expanded.
+///
+/// Refuses outright if its atoms don't balance, and never splits a context into unbalanced halves.
+///
+/// Decides MLL, but may wrongly give up on anything with additives or exponentials.
+/// # Errors
+/// If we can't.
+#[inline]
+pub fn prove_mll<A: Atom>(expr: Ast<A>) -> (Result<Derivation<A>, Error>, SearchStats) {
+    let goal = RhsOnlyWithExchange::from_rhs(expr);
+    let mut explored = 0;
+    let result = if is_balanced(&goal) {
+        prove_balanced(&goal, &mut explored).ok_or(Error::RanOutOfPaths)
+    } else {
+        Err(Error::RanOutOfPaths)
+    };
+    let stats = SearchStats {
+        dequeued: explored,
+        ..SearchStats::default()
+    };
+    (result, stats)
+}
+
+/// Prove a balanced MLL sequent depth-first, trying only inferences whose premises all balance.
+#[inline]
+fn prove_balanced<A: Atom>(
+    sequent: &RhsOnlyWithExchange<Ast<A>>,
+    explored: &mut usize,
+) -> Option<Derivation<A>> {
+    *explored = explored.saturating_add(1);
+    if let Some(name) = sequent.closed() {
+        return Some(Tree {
+            above: vec![],
+            rule: name.into(),
+            principal: None,
+            below: sequent.clone(),
+        });
+    }
+    let mut rules: Vec<_> = sequent
+        .sample()
+        .flat_map(|(ast, context)| {
+            ast.above(context).into_iter().map(move |mut rule| {
+                rule.principal = Some(ast.clone());
+                rule
+            })
+        })
+        .collect();
+    if let Some(i) = rules.iter().position(|rule| rule.invertible) {
+        // Anything that proves this sequent can go through this rule instead.
+        rules = vec![rules.swap_remove(i)];
+    }
+    rules
+        .into_iter()
+        .filter(|rule| rule.above.keys().all(is_balanced))
+        .find_map(|rule| {
+            let mut above = Vec::with_capacity(rule.above.len());
+            for (premise, copies) in rule.above.into_counts() {
+                let tree = prove_balanced(&premise, explored)?;
+                above.extend(core::iter::repeat_n(tree, copies.get()));
+            }
+            Some(Tree {
+                above,
+                rule: rule.name,
+                principal: rule.principal,
+                below: sequent.clone(),
+            })
+        })
+}
+
+#[cfg(feature = "quickcheck")]
+impl<A: Atom + quickcheck::Arbitrary + Send + Sync> quickcheck::Arbitrary for Ast<A> {
+    #[inline]
+    #[allow(
+        clippy::as_conversions,
+        clippy::indexing_slicing,
+        clippy::unwrap_used,
+        trivial_casts
+    )]
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        g.choose(
+            &[
+                (|_| Self::One) as fn(usize) -> Self,
+                |_| Self::Bottom,
+                |_| Self::Top,
+                |_| Self::Zero,
+                |s| Self::Value(A::arbitrary(&mut quickcheck::Gen::new(s))),
+                |s| {
+                    Self::Bang(Arc::arbitrary(&mut quickcheck::Gen::new(
+                        s.saturating_sub(1),
+                    )))
+                },
+                |s| {
+                    Self::Quest(Arc::arbitrary(&mut quickcheck::Gen::new(
+                        s.saturating_sub(1),
+                    )))
+                },
+                |s| {
+                    Self::Dual(Arc::arbitrary(&mut quickcheck::Gen::new(
+                        s.saturating_sub(1),
+                    )))
+                },
+                |s| {
+                    let mut r = quickcheck::Gen::new(s.saturating_sub(1).overflowing_shr(1).0);
+                    Self::Times(Arc::arbitrary(&mut r), Arc::arbitrary(&mut r))
+                },
+                |s| {
+                    let mut r = quickcheck::Gen::new(s.saturating_sub(1).overflowing_shr(1).0);
+                    Self::Par(Arc::arbitrary(&mut r), Arc::arbitrary(&mut r))
+                },
+                |s| {
+                    let mut r = quickcheck::Gen::new(s.saturating_sub(1).overflowing_shr(1).0);
+                    Self::With(Arc::arbitrary(&mut r), Arc::arbitrary(&mut r))
+                },
+                |s| {
+                    let mut r = quickcheck::Gen::new(s.saturating_sub(1).overflowing_shr(1).0);
+                    Self::Plus(Arc::arbitrary(&mut r), Arc::arbitrary(&mut r))
+                },
+                |s| {
+                    let mut r = quickcheck::Gen::new(s.saturating_sub(1).overflowing_shr(1).0);
+                    Self::Lollipop(Arc::arbitrary(&mut r), Arc::arbitrary(&mut r))
+                },
+            ][..g.size().clamp(4, 13)],
+        )
+        .unwrap()(g.size())
+    }
+    #[inline]
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        match *self {
+            Self::One => Box::new(core::iter::empty()),
+            Self::Bottom => Box::new(core::iter::once(Self::One)),
+            Self::Top => Box::new([Self::One, Self::Bottom].into_iter()),
+            Self::Zero => Box::new([Self::One, Self::Bottom, Self::Top].into_iter()),
+            Self::Value(ref atom) => Box::new(
+                [Self::One, Self::Bottom, Self::Top, Self::Zero]
+                    .into_iter()
+                    .chain(atom.shrink().map(Self::Value)),
+            ),
+            Self::Bang(ref arg) => Box::new(
+                [Self::One, Self::Bottom, Self::Top, Self::Zero]
+                    .into_iter()
+                    .chain(arg.as_ref().shrink())
+                    .chain(arg.shrink().map(Self::Bang)),
+            ),
+            Self::Quest(ref arg) => Box::new(
+                Self::Bang(Arc::clone(arg))
+                    .shrink()
+                    .chain(arg.shrink().map(Self::Quest)),
+            ),
+            Self::Dual(ref arg) => Box::new(
+                Self::Quest(Arc::clone(arg))
+                    .shrink()
+                    .chain(arg.shrink().map(Self::Dual)),
+            ),
+            Self::Times(ref lhs, ref rhs) => Box::new(
+                Self::Quest(Arc::clone(lhs))
+                    .shrink()
+                    .chain(Self::Quest(Arc::clone(rhs)).shrink())
+                    .chain(
+                        (Arc::clone(lhs), Arc::clone(rhs))
+                            .shrink()
+                            .map(|(tl, tr)| Self::Times(tl, tr)),
+                    ),
+            ),
+            Self::Par(ref lhs, ref rhs) => Box::new(
+                Self::Times(Arc::clone(lhs), Arc::clone(rhs))
+                    .shrink()
+                    .chain(
+                        (Arc::clone(lhs), Arc::clone(rhs))
+                            .shrink()
+                            .map(|(tl, tr)| Self::Par(tl, tr)),
+                    ),
+            ),
+            Self::With(ref lhs, ref rhs) => Box::new(
+                Self::Par(Arc::clone(lhs), Arc::clone(rhs)).shrink().chain(
+                    (Arc::clone(lhs), Arc::clone(rhs))
+                        .shrink()
+                        .map(|(tl, tr)| Self::With(tl, tr)),
+                ),
+            ),
+            Self::Plus(ref lhs, ref rhs) => Box::new(
+                Self::With(Arc::clone(lhs), Arc::clone(rhs)).shrink().chain(
+                    (Arc::clone(lhs), Arc::clone(rhs))
+                        .shrink()
+                        .map(|(tl, tr)| Self::Plus(tl, tr)),
+                ),
+            ),
+            Self::Lollipop(ref lhs, ref rhs) => Box::new(
+                Self::Plus(Arc::clone(lhs), Arc::clone(rhs)).shrink().chain(
+                    (Arc::clone(lhs), Arc::clone(rhs))
+                        .shrink()
+                        .map(|(tl, tr)| Self::Lollipop(tl, tr)),
+                ),
+            ),
+        }
+    }
+}
+
+/// Arbitrary formulas at most `depth` connectives deep, as a `proptest` strategy.
+///
+/// Shrinks toward units first, then shrinks subformulas in place.
+#[inline]
+#[cfg(feature = "proptest")]
+pub fn ast_strategy(depth: u32) -> impl proptest::strategy::Strategy<Value = IndexedAst> {
+    use proptest::{prelude::any, prop_oneof, strategy::Strategy as _};
+    let leaf = prop_oneof![
+        proptest::strategy::Just(Ast::One),
+        proptest::strategy::Just(Ast::Bottom),
+        proptest::strategy::Just(Ast::Top),
+        proptest::strategy::Just(Ast::Zero),
+        any::<usize>().prop_map(Ast::Value),
+    ];
+    leaf.prop_recursive(depth, 64, 2, |inner| {
+        let pair =
+            || (inner.clone(), inner.clone()).prop_map(|(lhs, rhs)| (Arc::new(lhs), Arc::new(rhs)));
+        prop_oneof![
+            inner.clone().prop_map(|arg| Ast::Bang(Arc::new(arg))),
+            inner.clone().prop_map(|arg| Ast::Quest(Arc::new(arg))),
+            inner.clone().prop_map(|arg| Ast::Dual(Arc::new(arg))),
+            pair().prop_map(|(lhs, rhs)| Ast::Times(lhs, rhs)),
+            pair().prop_map(|(lhs, rhs)| Ast::Par(lhs, rhs)),
+            pair().prop_map(|(lhs, rhs)| Ast::With(lhs, rhs)),
+            pair().prop_map(|(lhs, rhs)| Ast::Plus(lhs, rhs)),
+            pair().prop_map(|(lhs, rhs)| Ast::Lollipop(lhs, rhs)),
+        ]
+    })
+}
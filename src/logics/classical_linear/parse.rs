@@ -0,0 +1,244 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Formulas from strings, e.g. `!P0 * (P1 + 0) -o bot`.
+
+use super::{bang, quest, Ast, IndexedAst, Infix};
+use alloc::{
+    borrow::ToOwned as _,
+    string::{String, ToString as _},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
+
+/// Why a string isn't a formula.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// Something we didn't expect, starting at this byte offset.
+    UnexpectedToken {
+        /// Byte offset of the first character of the token.
+        offset: usize,
+        /// The token itself, as written.
+        found: String,
+    },
+    /// The input stopped in the middle of a formula.
+    UnexpectedEnd {
+        /// Byte offset of the end of the input.
+        offset: usize,
+    },
+}
+
+impl core::fmt::Display for ParseError {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::UnexpectedToken { offset, ref found } => {
+                write!(f, "Unexpected `{found}` at byte {offset}")
+            }
+            Self::UnexpectedEnd { offset } => write!(f, "Unexpected end of input at byte {offset}"),
+        }
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// Smallest piece of a formula.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[allow(variant_size_differences)] // Short-lived, and only ever a handful at a time.
+enum Token {
+    /// `1`
+    One,
+    /// `⊥` or `bot`
+    Bottom,
+    /// `⊤` or `top`
+    Top,
+    /// `0`
+    Zero,
+    /// `P` followed by a number
+    Value(usize),
+    /// `!`
+    Bang,
+    /// `?`
+    Quest,
+    /// `~`
+    Dual,
+    /// Any infix operator, including `*`, `par`, `+`, and `-o` for `⊗`, `⅋`, `⊕`, and `⊸`
+    Infix(Infix),
+    /// `(`
+    Open,
+    /// `)`
+    Close,
+}
+
+/// Split a string into tokens, each with the range of bytes it spans.
+#[inline]
+fn tokenize(s: &str) -> Result<Vec<(core::ops::Range<usize>, Token)>, ParseError> {
+    let mut tokens = vec![];
+    let mut chars = s.char_indices().peekable();
+    while let Some((offset, c)) = chars.next() {
+        let token = match c {
+            _ if c.is_whitespace() => continue,
+            '1' => Token::One,
+            '0' => Token::Zero,
+            '\u{22a5}' => Token::Bottom,
+            '\u{22a4}' => Token::Top,
+            '!' => Token::Bang,
+            '?' => Token::Quest,
+            '~' => Token::Dual,
+            '*' | '\u{2297}' => Token::Infix(Infix::Times),
+            '\u{214b}' => Token::Infix(Infix::Par),
+            '&' => Token::Infix(Infix::With),
+            '+' | '\u{2295}' => Token::Infix(Infix::Plus),
+            '\u{22b8}' => Token::Infix(Infix::Implies),
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '-' if chars.next_if(|&(_, next)| next == 'o').is_some() => {
+                Token::Infix(Infix::Implies)
+            }
+            _ if c.is_ascii_alphabetic() => {
+                let mut end = offset.saturating_add(c.len_utf8());
+                while let Some((i, next)) = chars.next_if(|&(_, next)| next.is_ascii_alphanumeric())
+                {
+                    end = i.saturating_add(next.len_utf8());
+                }
+                let word = s.get(offset..end).unwrap_or_default();
+                match word {
+                    "top" => Token::Top,
+                    "bot" => Token::Bottom,
+                    "par" => Token::Infix(Infix::Par),
+                    _ => match word.strip_prefix('P').map(str::parse) {
+                        Some(Ok(i)) => Token::Value(i),
+                        _ => {
+                            return Err(ParseError::UnexpectedToken {
+                                offset,
+                                found: word.to_owned(),
+                            })
+                        }
+                    },
+                }
+            }
+            _ => {
+                return Err(ParseError::UnexpectedToken {
+                    offset,
+                    found: c.to_string(),
+                })
+            }
+        };
+        let end = chars.peek().map_or(s.len(), |&(i, _)| i);
+        tokens.push((offset..end, token));
+    }
+    Ok(tokens)
+}
+
+/// Recursive-descent parser over a list of tokens.
+struct Parser<'s> {
+    /// Original input, to quote in errors.
+    input: &'s str,
+    /// Tokens, each with the range of bytes it spans.
+    tokens: Vec<(core::ops::Range<usize>, Token)>,
+    /// Index of the next token to read.
+    next: usize,
+}
+
+impl Parser<'_> {
+    /// Look at the next token without consuming it.
+    #[inline]
+    fn peek(&self) -> Option<(core::ops::Range<usize>, Token)> {
+        self.tokens.get(self.next).cloned()
+    }
+
+    /// Consume the next token.
+    #[inline]
+    fn advance(&mut self) -> Result<(core::ops::Range<usize>, Token), ParseError> {
+        let token = self.peek().ok_or(ParseError::UnexpectedEnd {
+            offset: self.input.len(),
+        })?;
+        self.next = self.next.saturating_add(1);
+        Ok(token)
+    }
+
+    /// Error for an unexpected token spanning these bytes.
+    #[inline]
+    fn unexpected(&self, span: core::ops::Range<usize>) -> ParseError {
+        ParseError::UnexpectedToken {
+            offset: span.start,
+            found: self.input.get(span).unwrap_or_default().to_owned(),
+        }
+    }
+
+    /// Parse a chain of infix operators that bind more tightly than `outer`
+    /// (or, for `-o`, which associates to the right, at least as tightly).
+    #[inline]
+    fn infix(&mut self, outer: Option<Infix>) -> Result<IndexedAst, ParseError> {
+        let mut lhs = self.prefix()?;
+        while let Some((_, Token::Infix(op))) = self.peek() {
+            if outer.is_some_and(|loosest| {
+                op < loosest || (op == loosest && !op.is_right_associative())
+            }) {
+                break;
+            }
+            self.next = self.next.saturating_add(1);
+            let rhs = self.infix(Some(op))?;
+            lhs = op.apply(lhs, rhs);
+        }
+        Ok(lhs)
+    }
+
+    /// Parse a unit, an atom, a parenthesized formula, or a prefix operator applied to one of those.
+    #[inline]
+    fn prefix(&mut self) -> Result<IndexedAst, ParseError> {
+        let (span, token) = self.advance()?;
+        Ok(match token {
+            Token::One => Ast::One,
+            Token::Bottom => Ast::Bottom,
+            Token::Top => Ast::Top,
+            Token::Zero => Ast::Zero,
+            Token::Value(i) => Ast::Value(i),
+            Token::Bang => bang(self.prefix()?),
+            Token::Quest => quest(self.prefix()?),
+            Token::Dual => Ast::Dual(Arc::new(self.prefix()?)),
+            Token::Open => {
+                let inside = self.infix(None)?;
+                match self.advance()? {
+                    (_, Token::Close) => inside,
+                    (other, _) => return Err(self.unexpected(other)),
+                }
+            }
+            Token::Infix(_) | Token::Close => return Err(self.unexpected(span)),
+        })
+    }
+}
+
+/// Parse a formula, e.g. `!P0 * (P1 + 0) -o bot`.
+///
+/// Units are `1`, `0`, `top`/`⊤`, and `bot`/`⊥`; atoms `P0`, `P1`, ...;
+/// prefix `!`, `?`, `~`; and infix, from loosest to tightest,
+/// `-o`/`⊸` (to the right), `par`/`⅋`, `*`/`⊗`, `+`/`⊕`, `&` (all to the left).
+/// # Errors
+/// At the first token that doesn't fit, or if the input ends early.
+#[inline]
+pub fn parse_ast(s: &str) -> Result<IndexedAst, ParseError> {
+    let mut parser = Parser {
+        input: s,
+        tokens: tokenize(s)?,
+        next: 0,
+    };
+    let ast = parser.infix(None)?;
+    match parser.peek() {
+        None => Ok(ast),
+        Some((span, _)) => Err(parser.unexpected(span)),
+    }
+}
+
+impl core::str::FromStr for IndexedAst {
+    type Err = ParseError;
+    #[inline(always)]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_ast(s)
+    }
+}
@@ -0,0 +1,2695 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+#![allow(
+    clippy::arithmetic_side_effects,
+    clippy::assertions_on_result_states,
+    clippy::default_numeric_fallback,
+    clippy::indexing_slicing,
+    clippy::iter_on_single_items,
+    clippy::missing_asserts_for_indexing,
+    clippy::non_ascii_literal,
+    clippy::panic,
+    clippy::pattern_type_mismatch,
+    clippy::suspicious_operation_groupings,
+    clippy::unwrap_used,
+    unused_results
+)]
+
+use super::*;
+use crate::{
+    apply, audit, complete, interactive::Session, ll, prelude::*, proofs, prove_cancellable,
+    prove_dag, prove_dfs, prove_observed, prove_or_explain, prove_shortest, prove_shortest_within,
+    prove_traced, prove_with_strategy, render_sequent, replay, step, tree::ALREADY_PROVEN, Ascii,
+    CancellationToken, Latex, ProverState, RenderOptions, ReplayError, SearchEvent, SearchStrategy,
+    TraceEvent, Unicode,
+};
+use core::time::Duration;
+
+#[cfg(feature = "std")]
+use crate::{prove_with_deadline, prove_with_progress, Progress};
+
+#[cfg(feature = "persist")]
+use crate::PersistError;
+
+#[test]
+fn cant_prove_0() {
+    let original = IndexedAst::Zero;
+    prove(original).map(|proof| proof.to_string()).unwrap_err();
+}
+
+#[test]
+fn prove_1() {
+    let original = IndexedAst::One;
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn prove_top() {
+    let original = IndexedAst::Top;
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn prove_zero_par_top() {
+    let original = IndexedAst::Zero.par(IndexedAst::Top);
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn prove_0_implies_0() {
+    let original = IndexedAst::Zero - IndexedAst::Zero;
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn prove_0_plus_1() {
+    let original = IndexedAst::Zero + IndexedAst::One;
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn prove_1_plus_0() {
+    let original = IndexedAst::One + IndexedAst::Zero;
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn prove_1_with_1() {
+    let original = IndexedAst::One & IndexedAst::One;
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn prove_1_with_1_with_1() {
+    let original = IndexedAst::One & IndexedAst::One & IndexedAst::One;
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn prove_1_with_1_with_1_with_1() {
+    let original = IndexedAst::One & IndexedAst::One & IndexedAst::One & IndexedAst::One;
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn prove_1_with_1_with_1_with_1_with_1() {
+    let original =
+        IndexedAst::One & IndexedAst::One & IndexedAst::One & IndexedAst::One & IndexedAst::One;
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn cant_prove_0_with_1() {
+    let original = IndexedAst::Zero & IndexedAst::One;
+    prove(original).map(|proof| proof.to_string()).unwrap_err();
+}
+
+#[test]
+fn cant_prove_1_with_0() {
+    let original = IndexedAst::One & IndexedAst::Zero;
+    prove(original).map(|proof| proof.to_string()).unwrap_err();
+}
+
+#[test]
+fn a_with_b_implies_a() {
+    let original = (IndexedAst::Value(0) & IndexedAst::Value(1)) - IndexedAst::Value(0);
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn a_with_b_implies_b() {
+    let original = (IndexedAst::Value(0) & IndexedAst::Value(1)) - IndexedAst::Value(1);
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn bottom_implies_bottom() {
+    let original = IndexedAst::Bottom - IndexedAst::Bottom;
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn prove_1_times_1() {
+    let original = IndexedAst::One * IndexedAst::One;
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn format_1_times_1() {
+    let original = IndexedAst::One * IndexedAst::One;
+    if let Ok(proof) = prove(original) {
+        assert_eq!(
+            proof.to_string(),
+            "
+--- 1 on (1)   --- 1 on (1)
+⊢ 1            ⊢ 1
+------------------ ⊗ on (1 ⊗ 1)
+     ⊢ 1 ⊗ 1
+",
+        );
+    }
+}
+
+#[test]
+fn cant_prove_1_times_0() {
+    let original = IndexedAst::One * IndexedAst::Zero;
+    prove(original).map(|proof| proof.to_string()).unwrap_err();
+}
+
+#[test]
+fn cant_prove_0_times_1() {
+    let original = IndexedAst::Zero * IndexedAst::One;
+    prove(original).map(|proof| proof.to_string()).unwrap_err();
+}
+
+#[test]
+fn cant_prove_0_times_0() {
+    let original = IndexedAst::Zero * IndexedAst::Zero;
+    prove(original).map(|proof| proof.to_string()).unwrap_err();
+}
+
+#[test]
+fn prove_1_times_1_times_1() {
+    let original = IndexedAst::One * IndexedAst::One * IndexedAst::One;
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn format_1_times_1_times_1() {
+    let original = IndexedAst::One * IndexedAst::One * IndexedAst::One;
+    if let Ok(proof) = prove(original) {
+        assert_eq!(
+            proof.to_string(),
+            "
+               --- 1 on (1)   --- 1 on (1)
+               ⊢ 1            ⊢ 1
+--- 1 on (1)   ------------------ ⊗ on (1 ⊗ 1)
+⊢ 1                 ⊢ 1 ⊗ 1
+--------------------------- ⊗ on (1 ⊗ 1 ⊗ 1)
+        ⊢ 1 ⊗ 1 ⊗ 1
+",
+        );
+    }
+}
+
+#[test]
+fn principal_formulas_are_printed() {
+    let plus = IndexedAst::One + IndexedAst::One;
+    let proof = prove(plus.clone() * IndexedAst::One).unwrap();
+    assert_eq!(proof.principal, Some(plus.clone() * IndexedAst::One));
+    let printed = proof.to_string();
+    assert!(printed.contains(&format!("\u{2297} on ({})", plus.clone() * IndexedAst::One)));
+    assert!(printed.contains(&format!("on ({plus})")), "{printed}");
+    proof.validate().unwrap();
+    let mut wrong = proof;
+    wrong.principal = Some(IndexedAst::One);
+    drop(wrong.validate().unwrap_err());
+}
+
+#[test]
+fn prove_1_times_1_times_1_times_1() {
+    let original = IndexedAst::One * IndexedAst::One * IndexedAst::One * IndexedAst::One;
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn prove_1_times_1_times_1_times_1_times_1() {
+    let original =
+        IndexedAst::One * IndexedAst::One * IndexedAst::One * IndexedAst::One * IndexedAst::One;
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn prove_1_implies_1_implies_1_implies_1_implies_1_times_1() {
+    let original = IndexedAst::One
+        - (IndexedAst::One
+            - (IndexedAst::One - (IndexedAst::One - (IndexedAst::One * IndexedAst::One))));
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn step_through_1_times_1_by_hand() {
+    let goal = RhsOnlyWithExchange::from_rhs(IndexedAst::One * IndexedAst::One);
+    let choices = apply(&goal);
+    assert_eq!(choices.len(), 1);
+    let (focus, rules) = choices.first().unwrap();
+    assert_eq!(*focus, IndexedAst::One * IndexedAst::One);
+    assert_eq!(rules.len(), 1);
+    assert_eq!(step(&goal, 0, 1), None);
+    assert_eq!(step(&goal, 1, 0), None);
+    let times = step(&goal, 0, 0).unwrap();
+    assert_eq!(times.name, "\u{2297}");
+    assert_eq!(Some(&times), rules.first());
+    let one = RhsOnlyWithExchange::from_rhs(IndexedAst::One);
+    assert_eq!(times.above.iter_repeat().collect::<Vec<_>>(), [&one, &one]);
+    let unit = step(&one, 0, 0).unwrap();
+    assert!(unit.above.is_empty());
+    let leaf = Tree {
+        above: vec![],
+        rule: unit.name,
+        principal: unit.principal,
+        below: one,
+    };
+    let tree = Tree {
+        above: vec![leaf.clone(), leaf],
+        rule: times.name,
+        principal: times.principal,
+        below: goal,
+    };
+    tree.validate().unwrap();
+    let searched = prove_dag::<_, RhsOnlyWithExchange<_>>(IndexedAst::One * IndexedAst::One);
+    assert_eq!(tree, searched.unwrap().to_tree());
+}
+
+#[test]
+fn session_proves_1_with_1_by_hand_and_by_search() {
+    let goal = RhsOnlyWithExchange::from_rhs(IndexedAst::One & IndexedAst::One);
+    let mut session = Session::new(goal.clone());
+    assert_eq!(session.goals().collect::<Vec<_>>(), [&goal]);
+    let choices = session.choices();
+    assert_eq!(choices.len(), 1);
+    assert_eq!(choices.first().map(|(_, rules)| rules.len()), Some(1));
+    assert!(!session.choose(1, 0));
+    assert!(!session.choose(0, 1));
+    assert!(session.choose(0, 0));
+    // Both premises are `⊢ 1`, so there's only one goal left.
+    let one = RhsOnlyWithExchange::from_rhs(IndexedAst::One);
+    assert_eq!(session.goals().collect::<Vec<_>>(), [&one]);
+    assert_eq!(session.tree(), None);
+    assert!(session.choose(0, 0));
+    assert!(session.is_done());
+    let by_hand = session.tree().unwrap();
+    by_hand.validate().unwrap();
+    assert_eq!(by_hand.below, goal);
+    assert_eq!(by_hand.rule, "&");
+
+    assert!(session.undo());
+    assert_eq!(session.current(), Some(&one));
+    session.auto().unwrap();
+    assert!(session.is_done());
+    let finished = session.tree().unwrap();
+    finished.validate().unwrap();
+    assert_eq!(finished, by_hand);
+
+    assert!(session.undo());
+    assert!(session.undo());
+    assert_eq!(session.current(), Some(&goal));
+    assert_eq!(session.moves(), 0);
+    assert!(!session.undo());
+}
+
+#[test]
+fn session_auto_changes_nothing_on_failure() {
+    let mut session = Session::new(RhsOnlyWithExchange::from_rhs(
+        IndexedAst::Zero & IndexedAst::One,
+    ));
+    assert!(session.choose(0, 0));
+    let goals: Vec<_> = session.goals().cloned().collect();
+    assert_eq!(session.auto(), Err(Error::RanOutOfPaths));
+    assert_eq!(session.goals().cloned().collect::<Vec<_>>(), goals);
+    assert_eq!(session.moves(), 1);
+}
+
+#[test]
+fn complete_finds_the_missing_dual() {
+    let p = IndexedAst::Value;
+    let (additions, proof) = complete(RhsOnlyWithExchange::from_rhs(p(0)), 1).unwrap();
+    assert_eq!(additions, [-p(0)].into_iter().collect());
+    assert_eq!(
+        proof.below,
+        RhsOnlyWithExchange::new([p(0), -p(0)].into_iter().collect())
+    );
+    proof.validate().unwrap();
+}
+
+#[test]
+fn complete_tries_fewest_additions_first() {
+    let p = IndexedAst::Value;
+    let provable = RhsOnlyWithExchange::from_rhs(p(0).par(-p(0)));
+    let (additions, _) = complete(provable, 2).unwrap();
+    assert!(additions.is_empty());
+    // `P0 ⊗ P1` needs both duals, one on each side of the tensor.
+    let tensor = RhsOnlyWithExchange::from_rhs(p(0) * p(1));
+    assert!(complete(tensor.clone(), 1).is_none());
+    let (both, proof) = complete(tensor, 2).unwrap();
+    assert_eq!(both, [-p(0), -p(1)].into_iter().collect());
+    proof.validate().unwrap();
+    // Nothing to add beside `0` can help, whatever the budget.
+    assert!(complete(RhsOnlyWithExchange::from_rhs(IndexedAst::Zero), 3).is_none());
+}
+
+#[test]
+fn prove_excluded_middle_par() {
+    let original = IndexedAst::Value(0).par(-IndexedAst::Value(0));
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn prove_excluded_middle_as_two_formulas() {
+    let p0 = IndexedAst::Value(0);
+    let goal = RhsOnlyWithExchange::new([p0.clone(), -p0].into_iter().collect());
+    let proof = prove_sequent(goal.clone()).unwrap();
+    assert_eq!(proof.below, goal);
+    assert_eq!(proof.rule, "axiom");
+}
+
+#[test]
+fn cant_prove_excluded_middle_plus() {
+    let original = IndexedAst::Value(0) + -IndexedAst::Value(0);
+    assert_eq!(prove(original), Err(Error::RanOutOfPaths));
+}
+
+#[test]
+fn explain_excluded_middle_plus() {
+    let original = IndexedAst::Value(0) + -IndexedAst::Value(0);
+    let report = prove_or_explain(original.clone()).unwrap_err();
+    let p0: RhsOnlyWithExchange<IndexedAst> = Sequent::from_rhs(IndexedAst::Value(0));
+    let not_p0: RhsOnlyWithExchange<IndexedAst> = Sequent::from_rhs(-IndexedAst::Value(0));
+    let root: RhsOnlyWithExchange<IndexedAst> = Sequent::from_rhs(original);
+    assert_eq!(report.dead_ends, [p0.clone(), not_p0.clone()].into());
+    assert_eq!(report.paths[&p0], [root.clone(), p0.clone()]);
+    assert!(report.explored >= 3);
+    let lines: BTreeSet<String> = report.to_string().lines().map(str::to_owned).collect();
+    assert_eq!(
+        lines,
+        [
+            format!("{root} \u{27f6} {p0}"),
+            format!("{root} \u{27f6} {not_p0}")
+        ]
+        .into()
+    );
+}
+
+#[test]
+fn cant_prove_excluded_middle_with() {
+    let original = IndexedAst::Value(0) & -IndexedAst::Value(0);
+    assert_eq!(prove(original), Err(Error::RanOutOfPaths));
+}
+
+#[test]
+fn promote_with_one_quest() {
+    let original = bang(IndexedAst::One).par(quest(IndexedAst::Bottom));
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn promote_with_empty_context() {
+    let original = bang(IndexedAst::One);
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn promote_with_two_quests() {
+    let original = quest(-IndexedAst::Value(0))
+        .par(quest(-IndexedAst::Value(1)))
+        .par(bang(IndexedAst::Value(0) * IndexedAst::Value(1)));
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn promote_with_three_quests() {
+    let original = quest(-IndexedAst::Value(0))
+        .par(quest(-IndexedAst::Value(1)))
+        .par(quest(-IndexedAst::Value(2)))
+        .par(bang(
+            IndexedAst::Value(0) * IndexedAst::Value(1) * IndexedAst::Value(2),
+        ));
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn axiom_closes_atom_and_dual() {
+    let sequent = RhsOnlyWithExchange::new(
+        [IndexedAst::Value(0), -IndexedAst::Value(0)]
+            .into_iter()
+            .collect(),
+    );
+    assert_eq!(sequent.closed(), Some("axiom"));
+    assert!((-IndexedAst::Value(0))
+        .above(RhsOnlyWithExchange::from_rhs(IndexedAst::Value(0)))
+        .iter()
+        .all(|rule| rule.name != "axiom"));
+}
+
+#[test]
+fn dual_pair_in_either_order() {
+    let p = IndexedAst::Value;
+    let dual = IndexedAst::is_dual_of;
+    // An atom comes before its dual, but a tensor comes after its own.
+    let atom = RhsOnlyWithExchange::new([-p(0), p(1), p(0)].into_iter().collect());
+    assert_eq!(atom.dual_pair(dual), Some((&p(0), &-p(0))));
+    let tensor = RhsOnlyWithExchange::new([p(0) * p(1), -(p(0) * p(1))].into_iter().collect());
+    assert_eq!(
+        tensor.dual_pair(dual),
+        Some((&(p(0) * p(1)), &-(p(0) * p(1))))
+    );
+    assert_eq!(tensor.closed(), Some("axiom"));
+    // Copies of one formula are never its dual, however many there are.
+    let copies = RhsOnlyWithExchange::new([p(0), p(0), p(0)].into_iter().collect());
+    assert_eq!(copies.dual_pair(dual), None);
+    let repeated = RhsOnlyWithExchange::new([p(0), p(0), -p(0)].into_iter().collect());
+    assert_eq!(repeated.dual_pair(dual), Some((&p(0), &-p(0))));
+    assert_eq!(repeated.closed(), None);
+}
+
+#[test]
+fn top_closes_any_context() {
+    let sequent = RhsOnlyWithExchange::new(
+        [IndexedAst::Value(0), IndexedAst::Top, IndexedAst::Zero]
+            .into_iter()
+            .collect(),
+    );
+    assert_eq!(sequent.closed(), Some("\u{22a4}"));
+}
+
+#[test]
+fn no_axiom_in_larger_context() {
+    let sequent = RhsOnlyWithExchange::new(
+        [
+            IndexedAst::Value(0),
+            IndexedAst::Value(1),
+            -IndexedAst::Value(0),
+        ]
+        .into_iter()
+        .collect(),
+    );
+    assert_eq!(sequent.closed(), None);
+}
+
+#[test]
+fn initial_sequents_skip_sampling() {
+    let p = IndexedAst::Value;
+    // Sampling every item would offer the same axiom once per item: 3, 5, 3, and 10 rules.
+    for (original, generated) in [
+        (p(0).par(-p(0)), 2),
+        (p(0).par((-p(0)).par(IndexedAst::Bottom)), 4),
+        (IndexedAst::Top.par(p(0) * p(1)), 2),
+        ((p(0) * p(1)).par(-p(0)).par(-p(1)), 8),
+    ] {
+        let (result, stats) = prove_with_stats(original.clone());
+        result.unwrap().validate().unwrap();
+        assert_eq!(stats.rules_generated, generated, "{original}");
+    }
+}
+
+#[test]
+fn prove_atom_par_dual_par_bottom() {
+    let original = IndexedAst::Value(0).par((-IndexedAst::Value(0)).par(IndexedAst::Bottom));
+    prove(original).map(|proof| proof.to_string()).unwrap();
+}
+
+#[test]
+fn every_rule_is_named() {
+    for ast in [
+        IndexedAst::Top,
+        IndexedAst::One,
+        IndexedAst::Bottom,
+        bang(IndexedAst::One),
+        quest(IndexedAst::One),
+        -(IndexedAst::One * IndexedAst::One),
+        IndexedAst::One * IndexedAst::One,
+        IndexedAst::One.par(IndexedAst::One),
+        IndexedAst::One & IndexedAst::One,
+        IndexedAst::One + IndexedAst::One,
+    ] {
+        for rule in ast.above(RhsOnlyWithExchange::new(Multiset::new())) {
+            assert!(!rule.name.is_empty(), "unnamed rule for {ast}");
+        }
+    }
+}
+
+#[test]
+fn weakening_is_labeled() {
+    let original = quest(IndexedAst::Zero).par(IndexedAst::One);
+    assert!(prove(original).unwrap().to_string().contains("?W"));
+}
+
+#[test]
+#[should_panic = "Rules must have non-empty names"]
+#[cfg(debug_assertions)]
+fn unnamed_rule_rejected() {
+    drop(Rule::<RhsOnlyWithExchange<Ast>>::new("", []));
+}
+
+#[test]
+fn method_returns_the_tree() {
+    let proof = (IndexedAst::One & IndexedAst::One).prove().unwrap();
+    assert_eq!(proof.rule, "&");
+    assert_eq!(
+        proof.below,
+        RhsOnlyWithExchange::from_rhs(IndexedAst::One & IndexedAst::One)
+    );
+}
+
+#[test]
+fn method_and_function_agree() {
+    for original in [
+        IndexedAst::One * IndexedAst::One,
+        IndexedAst::Value(0).par(-IndexedAst::Value(0)),
+        bang(IndexedAst::One).par(quest(IndexedAst::Bottom)),
+    ] {
+        assert_eq!(original.clone().prove(), prove(original));
+    }
+    assert_eq!(IndexedAst::Zero.prove(), prove(IndexedAst::Zero));
+}
+
+#[test]
+fn limit_stops_search_early() {
+    let original = IndexedAst::One
+        - (IndexedAst::One
+            - (IndexedAst::One - (IndexedAst::One - (IndexedAst::One * IndexedAst::One))));
+    assert_eq!(
+        prove_with_limit(original.clone(), 3),
+        Err(Error::LimitExceeded { explored: 3 }),
+    );
+    prove_with_limit(original, usize::MAX).unwrap();
+}
+
+#[test]
+fn limit_zero_explores_nothing() {
+    assert_eq!(
+        prove_with_limit(IndexedAst::One, 0),
+        Err(Error::LimitExceeded { explored: 0 }),
+    );
+}
+
+#[test]
+fn limit_doesnt_mask_exhaustion() {
+    assert_eq!(
+        prove_with_limit(IndexedAst::Zero, 1_000),
+        Err(Error::RanOutOfPaths)
+    );
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn generous_deadline_succeeds() {
+    let original = IndexedAst::One
+        - (IndexedAst::One
+            - (IndexedAst::One - (IndexedAst::One - (IndexedAst::One * IndexedAst::One))));
+    prove_with_deadline(original, Duration::from_hours(1)).unwrap();
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn zero_deadline_times_out() {
+    let original = IndexedAst::One
+        - (IndexedAst::One
+            - (IndexedAst::One - (IndexedAst::One - (IndexedAst::One * IndexedAst::One))));
+    assert_eq!(
+        prove_with_deadline(original, Duration::ZERO),
+        Err(Error::TimedOut),
+    );
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn progress_reports_every_dequeue_without_an_interval() {
+    let original = IndexedAst::One
+        - (IndexedAst::One
+            - (IndexedAst::One - (IndexedAst::One - (IndexedAst::One * IndexedAst::One))));
+    let mut reports: Vec<Progress> = vec![];
+    let tree = prove_with_progress(original.clone(), Duration::ZERO, |progress| {
+        reports.push(*progress);
+    });
+    let (result, stats) = prove_with_stats(original);
+    assert_eq!(tree, result);
+    assert!(reports.len() >= stats.dequeued);
+    assert_eq!(
+        reports.last().map(|progress| progress.dequeued),
+        Some(stats.dequeued)
+    );
+    assert!(reports
+        .windows(2)
+        .all(|pair| pair[0].dequeued < pair[1].dequeued && pair[0].elapsed <= pair[1].elapsed));
+    assert!(reports.iter().all(|progress| progress.cache_size > 0));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn progress_reports_at_most_once_per_interval() {
+    let mut reports: usize = 0;
+    prove_with_progress(
+        IndexedAst::One * IndexedAst::One,
+        Duration::from_hours(1),
+        |_| {
+            reports += 1;
+        },
+    )
+    .unwrap();
+    assert_eq!(reports, 1);
+}
+
+#[test]
+fn cancel_from_another_thread() {
+    let token = CancellationToken::new();
+    let remote = token.clone();
+    let canceller = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        remote.cancel();
+    });
+    // Unprovable, but contraction generates far more sequents than we could ever try,
+    // even with a few copies of each at most.
+    let original = (1..8).fold(quest(IndexedAst::Value(0)), |ast, i| {
+        ast.par(quest(IndexedAst::Value(i)))
+    });
+    assert_eq!(prove_cancellable(original, &token), Err(Error::Cancelled),);
+    assert!(token.is_cancelled());
+    canceller.join().unwrap();
+}
+
+#[test]
+fn cancelled_before_starting() {
+    let token = CancellationToken::new();
+    token.cancel();
+    assert_eq!(
+        prove_cancellable(IndexedAst::One, &token),
+        Err(Error::Cancelled)
+    );
+}
+
+#[test]
+fn stats_for_1_times_1() {
+    let (result, stats) = prove_with_stats(IndexedAst::One * IndexedAst::One);
+    result.unwrap();
+    assert!(stats.dequeued > 0);
+    assert!(stats.cache_hits > 0);
+    assert!(stats.enqueued >= stats.dequeued);
+    assert!(stats.inferences_discharged > 0);
+    assert!(stats.inferences_paused >= stats.inferences_discharged);
+    assert!(stats.peak_queue_len > 0);
+}
+
+#[test]
+fn stats_on_failure() {
+    let (result, stats) = prove_with_stats(IndexedAst::Zero);
+    assert_eq!(result, Err(Error::RanOutOfPaths));
+    assert_eq!(stats.dequeued, 1);
+    assert_eq!(stats.inferences_discharged, 0);
+}
+
+#[test]
+fn observer_sees_every_step() {
+    let mut events = vec![];
+    prove_observed(IndexedAst::One * IndexedAst::One, &mut events).unwrap();
+    let goal = RhsOnlyWithExchange::from_rhs(IndexedAst::One * IndexedAst::One);
+    assert_eq!(events.first(), Some(&SearchEvent::Dequeued(goal.clone())));
+    assert!(matches!(
+        events.last(),
+        Some(&SearchEvent::Proved { ref sequent, .. }) if *sequent == goal
+    ));
+    assert!(events
+        .iter()
+        .any(|event| matches!(*event, SearchEvent::Paused { .. })));
+}
+
+#[test]
+fn custom_observer_counts_dequeues() {
+    /// Count sequents taken out of the queue.
+    struct Counter(usize);
+    impl SearchObserver<RhsOnlyWithExchange<Ast>> for Counter {
+        fn on_dequeue(&mut self, _: &RhsOnlyWithExchange<Ast>) {
+            self.0 += 1;
+        }
+    }
+    let mut counter = Counter(0);
+    prove_observed(IndexedAst::One & IndexedAst::One, &mut counter).unwrap();
+    assert_eq!(
+        counter.0,
+        prove_with_stats(IndexedAst::One & IndexedAst::One)
+            .1
+            .dequeued,
+    );
+}
+
+#[test]
+fn errors_display_their_payload() {
+    assert_eq!(
+        Error::LimitExceeded { explored: 42 }.to_string(),
+        "Gave up after exploring 42 sequents without finding a proof",
+    );
+    for error in [Error::RanOutOfPaths, Error::TimedOut, Error::Cancelled] {
+        assert!(!error.to_string().is_empty());
+    }
+}
+
+#[test]
+fn errors_work_with_question_mark() {
+    fn attempt() -> Result<String, Box<dyn core::error::Error>> {
+        Ok(prove(IndexedAst::Zero)?.to_string())
+    }
+    fn attempt_crate_result() -> crate::Result<String> {
+        Ok(prove(IndexedAst::One)?.to_string())
+    }
+    assert_eq!(
+        attempt().unwrap_err().to_string(),
+        Error::RanOutOfPaths.to_string(),
+    );
+    attempt_crate_result().unwrap();
+}
+
+#[test]
+fn shortest_proof_of_1_plus_1() {
+    let (tree, depth) = prove_shortest(IndexedAst::One + IndexedAst::One).unwrap();
+    assert_eq!(depth, 2);
+    assert_eq!(tree.depth(), 2);
+    assert_eq!(tree.above.len(), 1);
+    assert!(tree.above.iter().all(|leaf| leaf.above.is_empty()));
+}
+
+#[test]
+fn shortest_proof_of_deeper_formula() {
+    let original = IndexedAst::One - (IndexedAst::One - (IndexedAst::One * IndexedAst::One));
+    let (tree, depth) = prove_shortest(original.clone()).unwrap();
+    assert_eq!(depth, tree.depth());
+    assert!(depth <= prove(original).unwrap().depth());
+}
+
+#[test]
+fn shortest_gives_up_past_ceiling() {
+    assert_eq!(
+        prove_shortest_within(IndexedAst::One * IndexedAst::One, 1),
+        Err(Error::DepthExceeded { max_depth: 1 }),
+    );
+}
+
+#[test]
+fn shortest_agrees_with_the_shallowest_dfs() {
+    for source in [
+        "1 * 1",
+        "1 -o 1 -o 1 * 1",
+        "(P0 -o P0) & (P0 -o P0)",
+        "?1 * 1",
+        "~(bot par (1 -o bot))",
+        "?(P0 + ~P0)",
+    ] {
+        let ast = parse_ast(source).unwrap();
+        let (tree, depth) = prove_shortest(ast.clone()).unwrap();
+        assert_eq!(depth, tree.depth(), "{ast}");
+        assert!(tree.iter().all(|node| node.rule != ALREADY_PROVEN), "{ast}");
+        let shallowest = (1..=depth)
+            .find(|&max_depth| prove_dfs(ast.clone(), max_depth).is_ok())
+            .unwrap();
+        assert_eq!(depth, shallowest, "{ast}");
+    }
+}
+
+#[test]
+fn shortest_deepens_past_the_copy_cap() {
+    for (source, depth) in [
+        ("?(P0 + ~P0)", 6),
+        ("((P0 + 0 + 0) * (P0 + 0 + 0)) par ?~P0", 7),
+    ] {
+        let ast = parse_ast(source).unwrap();
+        assert!(prove(ast.clone()).is_ok(), "{ast}");
+        let (tree, shortest) = prove_shortest(ast.clone()).unwrap();
+        assert!(shortest <= depth, "{ast}: {shortest} > {depth}");
+        tree.validate().unwrap();
+    }
+}
+
+#[test]
+fn shortest_reports_exhaustion() {
+    assert_eq!(prove_shortest(IndexedAst::Zero), Err(Error::RanOutOfPaths));
+}
+
+#[test]
+fn dfs_agrees_with_prove_on_the_corpus() {
+    let p = IndexedAst::Value;
+    for ast in [
+        IndexedAst::Zero,
+        IndexedAst::One,
+        IndexedAst::Top,
+        IndexedAst::Zero.par(IndexedAst::Top),
+        IndexedAst::Zero - IndexedAst::Zero,
+        IndexedAst::Zero + IndexedAst::One,
+        IndexedAst::One & IndexedAst::One & IndexedAst::One,
+        IndexedAst::Zero & IndexedAst::One,
+        (p(0) & p(1)) - p(1),
+        IndexedAst::Bottom - IndexedAst::Bottom,
+        IndexedAst::One * IndexedAst::One * IndexedAst::One,
+        IndexedAst::One * IndexedAst::Zero,
+        IndexedAst::One - IndexedAst::One - IndexedAst::One - IndexedAst::One * IndexedAst::One,
+        p(0).par(-p(0)),
+        p(0) + -p(0),
+        p(0) & -p(0),
+        bang(IndexedAst::One).par(quest(IndexedAst::Bottom)),
+        quest(-p(0)).par(quest(-p(1))).par(bang(p(0) * p(1))),
+    ] {
+        let dfs = prove_dfs::<_, RhsOnlyWithExchange<_>>(ast.clone(), 12);
+        assert_eq!(dfs.is_ok(), prove(ast.clone()).is_ok(), "{ast}");
+        if let Ok(tree) = dfs {
+            tree.validate().unwrap();
+        }
+    }
+}
+
+#[test]
+fn dfs_respects_depth() {
+    let original = IndexedAst::One * IndexedAst::One;
+    assert_eq!(
+        prove_dfs::<_, RhsOnlyWithExchange<_>>(original.clone(), 1),
+        Err(Error::DepthExceeded { max_depth: 1 }),
+    );
+    assert_eq!(
+        prove_dfs::<_, RhsOnlyWithExchange<_>>(original, 2).map(|tree| tree.depth()),
+        Ok(2),
+    );
+}
+
+#[test]
+fn dfs_reports_exhaustion() {
+    assert_eq!(
+        prove_dfs::<_, RhsOnlyWithExchange<_>>(IndexedAst::Value(0) + -IndexedAst::Value(0), 32),
+        Err(Error::RanOutOfPaths),
+    );
+}
+
+#[test]
+fn every_proof_of_1_plus_1_plus_1() {
+    let trees: Vec<_> = proofs(IndexedAst::One + IndexedAst::One + IndexedAst::One).collect();
+    assert!(trees.len() > 1);
+    let unique: BTreeSet<_> = trees.iter().collect();
+    assert_eq!(unique.len(), trees.len());
+}
+
+#[test]
+fn every_proof_of_a_sum_times_a_sum() {
+    let sum = IndexedAst::One + IndexedAst::One;
+    let other = IndexedAst::One + IndexedAst::Top;
+    // Copies of the same premise are interchangeable, so `+L` on one and `+R` on the other counts once.
+    assert_eq!(proofs(sum.clone() * sum.clone()).count(), 3);
+    let trees: Vec<_> = proofs(sum * other).collect();
+    assert_eq!(trees.len(), 4);
+    let unique: BTreeSet<_> = trees.iter().collect();
+    assert_eq!(unique.len(), trees.len());
+    for tree in &trees {
+        tree.validate().unwrap();
+    }
+}
+
+#[test]
+fn first_few_of_many_proofs() {
+    let sum = IndexedAst::One + IndexedAst::One;
+    // Two ways to prove each factor, so 256 proofs in all.
+    let product = (1..8).fold(sum.clone(), |product, _| product * sum.clone());
+    let trees: Vec<_> = proofs(product).take(3).collect();
+    assert_eq!(trees.len(), 3);
+    let unique: BTreeSet<_> = trees.iter().collect();
+    assert_eq!(unique.len(), trees.len());
+    for tree in &trees {
+        tree.validate().unwrap();
+    }
+}
+
+#[test]
+fn no_proofs_of_0() {
+    assert_eq!(
+        proofs::<Ast, RhsOnlyWithExchange<Ast>>(IndexedAst::Zero).next(),
+        None
+    );
+}
+
+#[test]
+fn only_one_proof_of_1_times_1() {
+    let trees: Vec<_> = proofs(IndexedAst::One * IndexedAst::One).collect();
+    assert_eq!(trees.len(), 1);
+    assert!(trees
+        .iter()
+        .all(|tree| tree.rule == prove(IndexedAst::One * IndexedAst::One).unwrap().rule));
+}
+
+#[test]
+fn prover_state_reuses_shared_subgoals() {
+    let shared = (IndexedAst::One * IndexedAst::One)
+        * (IndexedAst::One * (IndexedAst::One & IndexedAst::One));
+    let mut state = ProverState::<RhsOnlyWithExchange<Ast>>::new();
+    state.prove(shared.clone()).unwrap();
+    assert!(state.cached_sequents() > 0);
+    let before = state.stats().dequeued;
+    state.prove(shared.clone() & IndexedAst::One).unwrap();
+    let reused = state.stats().dequeued - before;
+    let (fresh, stats) = prove_with_stats(shared & IndexedAst::One);
+    fresh.unwrap();
+    assert!(reused < stats.dequeued);
+}
+
+#[test]
+fn prover_state_proves_the_same_goal_twice() {
+    let mut state = ProverState::<RhsOnlyWithExchange<Ast>>::new();
+    let first = state.prove(IndexedAst::One * IndexedAst::One).unwrap();
+    let before = state.stats().dequeued;
+    assert_eq!(
+        state.prove(IndexedAst::One * IndexedAst::One).unwrap(),
+        first
+    );
+    assert_eq!(state.stats().dequeued, before);
+}
+
+#[test]
+fn prover_state_survives_failures() {
+    let mut state = ProverState::<RhsOnlyWithExchange<Ast>>::new();
+    assert_eq!(state.prove(IndexedAst::Zero), Err(Error::RanOutOfPaths));
+    state.prove(IndexedAst::One & IndexedAst::One).unwrap();
+    assert_eq!(
+        state.prove(IndexedAst::One * IndexedAst::Zero),
+        Err(Error::RanOutOfPaths)
+    );
+    state.prove(IndexedAst::One + IndexedAst::Zero).unwrap();
+}
+
+#[test]
+#[cfg(feature = "persist")]
+fn prover_state_round_trips_through_a_file() {
+    let goal = (IndexedAst::One * IndexedAst::One) & (IndexedAst::Top + IndexedAst::Zero);
+    let mut state = ProverState::<RhsOnlyWithExchange<Ast>>::new();
+    let first = state.prove(goal.clone()).unwrap();
+    let mut file = vec![];
+    state.save(&mut file).unwrap();
+    let mut loaded = ProverState::<RhsOnlyWithExchange<Ast>>::load(file.as_slice()).unwrap();
+    assert_eq!(loaded.cached_sequents(), state.cached_sequents());
+    let second = loaded.prove(goal).unwrap();
+    assert_eq!(loaded.stats().dequeued, 0);
+    assert_eq!(second, first);
+    second.validate().unwrap();
+}
+
+#[test]
+#[cfg(feature = "persist")]
+fn prover_state_rejects_corrupted_caches() {
+    type State = ProverState<RhsOnlyWithExchange<Ast>>;
+    let one = RhsOnlyWithExchange::from_rhs(IndexedAst::One);
+    let save = |entries: &[(RhsOnlyWithExchange<Ast>, Rule<RhsOnlyWithExchange<Ast>>)]| {
+        serde_json::to_vec(entries).unwrap()
+    };
+    assert!(matches!(
+        State::load(&b"not a cache"[..]),
+        Err(PersistError::Format(_))
+    ));
+    let mut state = State::new();
+    state.prove(IndexedAst::One * IndexedAst::One).unwrap();
+    let mut file = vec![];
+    state.save(&mut file).unwrap();
+    file.truncate(file.len() >> 1_u8);
+    assert!(matches!(
+        State::load(file.as_slice()),
+        Err(PersistError::Format(_))
+    ));
+    let forged = save(&[(
+        RhsOnlyWithExchange::from_rhs(IndexedAst::Zero),
+        Rule::new("1", []),
+    )]);
+    assert!(matches!(
+        State::load(forged.as_slice()),
+        Err(PersistError::Invalid(_))
+    ));
+    let missing_premise = save(&[(
+        RhsOnlyWithExchange::from_rhs(IndexedAst::One * IndexedAst::One),
+        Rule::new("\u{2297}", [one.clone(), one]),
+    )]);
+    assert!(matches!(
+        State::load(missing_premise.as_slice()),
+        Err(PersistError::Unfounded(_))
+    ));
+}
+
+#[test]
+fn dag_shares_what_the_tree_repeats() {
+    let with = IndexedAst::One & IndexedAst::One;
+    let goal = with.clone() * with.clone();
+    let dag = prove_dag::<_, RhsOnlyWithExchange<_>>(goal.clone()).unwrap();
+    let tree = prove(goal).unwrap();
+    assert!(dag.len() < tree.len());
+    assert!(dag.len() < dag.tree_size());
+    assert_eq!(
+        dag.shared_lemmas(),
+        [
+            (&RhsOnlyWithExchange::from_rhs(IndexedAst::One), 2),
+            (&RhsOnlyWithExchange::from_rhs(with), 2),
+        ],
+    );
+    let expanded = dag.to_tree();
+    expanded.validate().unwrap();
+    assert!(expanded.iter().all(|node| node.rule != "(already proven)"));
+    assert_eq!(expanded.below, tree.below);
+    let root = dag.get(dag.root()).unwrap();
+    assert_eq!(root.below, tree.below);
+    assert!(root.above.iter().all(|&id| id < dag.root()));
+}
+
+#[test]
+fn repeated_premises_keep_their_arity() {
+    let one = RhsOnlyWithExchange::from_rhs(IndexedAst::One);
+    let rule = Rule::binary("&", one.clone(), one.clone());
+    assert_eq!(rule.arity(), 2);
+    assert!(!rule.is_axiom());
+    assert_eq!(rule.above.count(&one), 2);
+    assert_eq!(rule, Rule::new("&", [one.clone(), one.clone()]));
+    assert_eq!(Rule::unary("+L", one.clone()).arity(), 1);
+    assert!(Rule::<RhsOnlyWithExchange<IndexedAst>>::axiom("1").is_axiom());
+    let with = IndexedAst::One & IndexedAst::One;
+    assert!(with
+        .above(RhsOnlyWithExchange::new(Multiset::new()))
+        .iter()
+        .all(|with_rule| with_rule.arity() == 2));
+    // `1` gets proven once on its own before `&` needs it twice, and each copy still gets its own proof.
+    let proof = prove(IndexedAst::One * with.clone()).unwrap();
+    proof.validate().unwrap();
+    let withs: Vec<_> = proof.iter().filter(|node| node.rule == "&").collect();
+    assert_eq!(withs.len(), 1);
+    for node in withs {
+        assert_eq!(node.below, RhsOnlyWithExchange::from_rhs(with.clone()));
+        assert_eq!(node.above.len(), 2);
+        assert!(node
+            .above
+            .iter()
+            .all(|premise| premise.below == one && premise.rule == "1"));
+    }
+}
+
+#[test]
+fn identical_premises_get_identical_proofs() {
+    let imp = IndexedAst::Value(0).lollipop(IndexedAst::Value(0));
+    let premise = RhsOnlyWithExchange::from_rhs(imp.clone());
+    let goal = imp.clone() & imp;
+    let mut proof = prove(goal.clone()).unwrap();
+    proof.validate().unwrap();
+    assert_eq!(proof.rule, "&");
+    let [ref left, ref right] = *proof.above else {
+        panic!("{} premises", proof.above.len());
+    };
+    assert_eq!(left, right);
+    assert_eq!(left.below, premise);
+    assert_eq!(left.rule, "\u{22b8}");
+    assert!(left.leaves().all(|leaf| leaf.rule == "axiom"));
+    let before = proof.clone();
+    proof.expand_shared();
+    assert_eq!(proof, before);
+    assert_eq!(
+        proof,
+        prove_dag::<_, RhsOnlyWithExchange<_>>(goal)
+            .unwrap()
+            .to_tree()
+    );
+}
+
+#[test]
+fn times_splits_each_context_once() {
+    let context = RhsOnlyWithExchange::from_rhs(IndexedAst::Value(0)).with([
+        IndexedAst::Value(1),
+        IndexedAst::Value(2),
+        IndexedAst::Value(3),
+    ]);
+    let different = (IndexedAst::One * IndexedAst::Bottom).above(context.clone());
+    assert_eq!(different.len(), 16);
+    assert!(different.iter().all(|rule| rule.name == "\u{2297}"));
+    assert_eq!((IndexedAst::One * IndexedAst::One).above(context).len(), 8);
+}
+
+#[test]
+fn proofs_validate() {
+    for original in [
+        IndexedAst::One * IndexedAst::One,
+        IndexedAst::One * IndexedAst::One * IndexedAst::One,
+        IndexedAst::One & IndexedAst::One,
+        IndexedAst::Value(0).par(-IndexedAst::Value(0)),
+        bang(IndexedAst::One).par(quest(IndexedAst::Bottom)),
+        quest(IndexedAst::Zero).par(IndexedAst::One),
+    ] {
+        prove(original).unwrap().validate().unwrap();
+    }
+}
+
+#[test]
+fn expanding_shared_subproofs_leaves_no_references() {
+    /// Whether any node in this tree is just a reference to another proof.
+    fn any_references(tree: &Tree<RhsOnlyWithExchange<IndexedAst>>) -> bool {
+        tree.rule == "(already proven)" || tree.above.iter().any(any_references)
+    }
+    let both = IndexedAst::One & IndexedAst::One;
+    let mut proof = prove(both.clone() * (both * IndexedAst::One)).unwrap();
+    assert!(any_references(&proof));
+    proof.expand_shared();
+    assert!(!any_references(&proof));
+    proof.validate().unwrap();
+}
+
+#[test]
+fn expanding_leaves_dangling_references() {
+    let mut proof = Tree {
+        above: [Tree {
+            above: vec![],
+            rule: "(already proven)".into(),
+            principal: None,
+            below: RhsOnlyWithExchange::from_rhs(IndexedAst::One),
+        }]
+        .into_iter()
+        .collect(),
+        rule: "\u{2295}".into(),
+        principal: None,
+        below: RhsOnlyWithExchange::from_rhs(IndexedAst::One + IndexedAst::Zero),
+    };
+    let before = proof.clone();
+    proof.expand_shared();
+    assert_eq!(proof, before);
+}
+
+#[test]
+fn rule_histogram_counts_every_node() {
+    let mut proof = prove(IndexedAst::One & (IndexedAst::One * IndexedAst::One)).unwrap();
+    proof.expand_shared();
+    let histogram = proof.rule_histogram();
+    assert_eq!(histogram.get("&"), Some(&1));
+    assert_eq!(histogram.get("\u{2297}"), Some(&1));
+    assert_eq!(histogram.get("1"), Some(&3));
+    assert_eq!(histogram.len(), 3);
+    assert_eq!(proof.len(), 5);
+    assert_eq!(proof.depth(), 3);
+}
+
+/// A sequent already printed to a string, with no rules of its own.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct Printed(String);
+
+impl core::fmt::Display for Printed {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Infer<RhsOnlyOrdered<Self>> for Printed {
+    #[inline]
+    fn above(&self, _: RhsOnlyOrdered<Self>) -> Vec<Rule<RhsOnlyOrdered<Self>>> {
+        vec![]
+    }
+}
+
+#[test]
+fn map_sequents_to_strings() {
+    let mut proof = prove(IndexedAst::One & (IndexedAst::One * IndexedAst::One)).unwrap();
+    proof.expand_shared();
+    let mut before: Vec<_> = proof
+        .iter()
+        .map(|tree| {
+            (
+                tree.rule.to_string(),
+                tree.below.to_string(),
+                tree.above.len(),
+            )
+        })
+        .collect();
+    let (len, depth) = (proof.len(), proof.depth());
+    let mapped = proof.map(|sequent| RhsOnlyOrdered {
+        rhs: vec![Printed(sequent.to_string())],
+    });
+    let mut after: Vec<_> = mapped
+        .iter()
+        .map(|tree| {
+            (
+                tree.rule.to_string(),
+                tree.below.rhs[0].0.clone(),
+                tree.above.len(),
+            )
+        })
+        .collect();
+    before.sort();
+    after.sort();
+    assert_eq!(before, after);
+    assert_eq!((mapped.len(), mapped.depth()), (len, depth));
+}
+
+#[test]
+fn try_map_stops_at_the_first_error() {
+    let proof = prove(IndexedAst::One & (IndexedAst::One * IndexedAst::One)).unwrap();
+    let mut seen = 0_usize;
+    let result = proof.try_map(|sequent| {
+        seen += 1;
+        if sequent.to_string() == "\u{22a2} 1" {
+            Err(sequent)
+        } else {
+            Ok(RhsOnlyOrdered {
+                rhs: vec![Printed(sequent.to_string())],
+            })
+        }
+    });
+    assert_eq!(result.unwrap_err().to_string(), "\u{22a2} 1");
+    assert!(seen < 4, "kept mapping after the first error");
+}
+
+#[test]
+fn map_rules_renames_every_line() {
+    let proof = prove(IndexedAst::One * IndexedAst::One).unwrap();
+    let renamed = proof.clone().map_rules(|name| match name.as_ref() {
+        "\u{2297}" => "\\otimes R".into(),
+        "1" => "\\mathbf{1}".into(),
+        _ => name,
+    });
+    let rules: Vec<_> = renamed.rule_histogram().into_keys().collect();
+    assert_eq!(rules, ["\\mathbf{1}", "\\otimes R"]);
+    assert_eq!(renamed.map_rules(|name| name).len(), proof.len());
+}
+
+#[test]
+fn diff_with_itself_is_none() {
+    let proof = prove(IndexedAst::One & (IndexedAst::One * IndexedAst::One)).unwrap();
+    assert_eq!(proof.diff(&proof), None);
+    assert_eq!(proof.diff(&proof.clone()), None);
+}
+
+#[test]
+fn diff_pinpoints_a_renamed_rule() {
+    let proof = prove(IndexedAst::One & (IndexedAst::One * IndexedAst::One)).unwrap();
+    let renamed = proof.clone().map_rules(|name| {
+        if name == "\u{2297}" {
+            "times".into()
+        } else {
+            name
+        }
+    });
+    let diff = proof.diff(&renamed).unwrap();
+    let index = proof
+        .above
+        .iter()
+        .position(|premise| premise.rule == "\u{2297}")
+        .unwrap();
+    assert_eq!(diff.path, [index]);
+    assert_eq!(diff.left, diff.right);
+    assert_eq!(diff.left.to_string(), "\u{22a2} 1 \u{2297} 1");
+    assert_eq!(
+        (diff.left_rule.as_ref(), diff.right_rule.as_ref()),
+        ("\u{2297}", "times")
+    );
+    assert_eq!(
+        diff.to_string(),
+        format!(
+            "Proofs diverge at premise path [{index}]:\n    left:  \u{22a2} 1 \u{2297} 1 by \u{2297}\n    right: \u{22a2} 1 \u{2297} 1 by times",
+        ),
+    );
+}
+
+#[test]
+fn diff_pinpoints_a_missing_premise() {
+    let proof = prove(IndexedAst::One & (IndexedAst::One * IndexedAst::One)).unwrap();
+    let mut broken = proof.clone();
+    broken.above = core::mem::take(&mut broken.above)
+        .into_iter()
+        .map(|mut premise| {
+            if premise.rule == "\u{2297}" {
+                let mut above = core::mem::take(&mut premise.above);
+                drop(above.pop());
+                premise.above = above;
+            }
+            premise
+        })
+        .collect();
+    let diff = broken.diff(&proof).unwrap();
+    let index = broken
+        .above
+        .iter()
+        .position(|premise| premise.rule == "\u{2297}")
+        .unwrap();
+    assert_eq!(diff.path, [index]);
+    assert_eq!(diff.left.to_string(), "\u{22a2} 1 \u{2297} 1");
+    assert_eq!(diff.left_rule, diff.right_rule);
+}
+
+#[test]
+fn traversal_is_pre_order() {
+    let proof = prove(IndexedAst::One & (IndexedAst::One * IndexedAst::One)).unwrap();
+    let rules: Vec<_> = proof.iter().map(|tree| tree.rule.as_ref()).collect();
+    assert_eq!(rules.first(), Some(&"&"));
+    assert_eq!(rules.len(), proof.len());
+    assert!(proof.leaves().all(|leaf| leaf.above.is_empty()));
+    assert_eq!(proof.leaves().count(), 3);
+}
+
+#[test]
+fn tampered_rule_name_is_rejected() {
+    let mut proof = prove(IndexedAst::One * IndexedAst::One).unwrap();
+    proof.rule = "&".into();
+    let error = proof.validate().unwrap_err();
+    assert_eq!(error.rule, "&");
+    assert_eq!(
+        error.sequent,
+        RhsOnlyWithExchange::from_rhs(IndexedAst::One * IndexedAst::One)
+    );
+    assert!(error.available.iter().any(|rule| rule.name == "\u{2297}"));
+}
+
+#[test]
+fn tampered_premises_are_rejected() {
+    let mut proof = prove(IndexedAst::One * IndexedAst::One).unwrap();
+    proof.above = core::mem::take(&mut proof.above)
+        .into_iter()
+        .map(|mut tree| {
+            tree.below = RhsOnlyWithExchange::from_rhs(IndexedAst::Top);
+            tree
+        })
+        .collect();
+    assert!(proof.validate().is_err());
+}
+
+#[test]
+fn dangling_reference_is_rejected() {
+    let proof = Tree {
+        above: [Tree {
+            above: vec![],
+            rule: "(already proven)".into(),
+            principal: None,
+            below: RhsOnlyWithExchange::from_rhs(IndexedAst::One),
+        }]
+        .into_iter()
+        .collect(),
+        rule: "\u{2295}".into(),
+        principal: None,
+        below: RhsOnlyWithExchange::from_rhs(IndexedAst::One + IndexedAst::Zero),
+    };
+    assert!(proof.validate().is_err());
+}
+
+#[test]
+fn circular_reference_is_rejected() {
+    let goal = RhsOnlyWithExchange::from_rhs(quest(IndexedAst::Value(0)));
+    let proof = Tree {
+        above: vec![Tree {
+            above: vec![Tree {
+                above: vec![],
+                rule: "(already proven)".into(),
+                principal: None,
+                below: goal.clone(),
+            }],
+            rule: "?W".into(),
+            principal: None,
+            below: goal.clone().with([quest(IndexedAst::Value(0))]),
+        }],
+        rule: "?C".into(),
+        principal: None,
+        below: goal,
+    };
+    assert_eq!(proof.validate().unwrap_err().rule, "(already proven)");
+    assert!(prove_with_limit(quest(IndexedAst::Value(0)), 1_000).is_err());
+}
+
+#[test]
+fn latex_1_times_1() {
+    let proof = prove(IndexedAst::One * IndexedAst::One).unwrap();
+    assert_eq!(
+        proof.to_latex(),
+        r"\begin{prooftree}
+\AxiomC{}
+\RightLabel{$1$}
+\UnaryInfC{$\vdash 1$}
+\AxiomC{}
+\RightLabel{$1$}
+\UnaryInfC{$\vdash 1$}
+\RightLabel{$\otimes$}
+\BinaryInfC{$\vdash 1 \otimes 1$}
+\end{prooftree}
+",
+    );
+}
+
+#[test]
+fn latex_environments_are_balanced() {
+    let proof =
+        prove(IndexedAst::One * IndexedAst::One * (IndexedAst::Top + IndexedAst::Zero)).unwrap();
+    let latex = proof.to_latex();
+    assert_eq!(
+        latex.matches("\\begin{").count(),
+        latex.matches("\\end{").count()
+    );
+    assert_eq!(latex.matches('{').count(), latex.matches('}').count());
+}
+
+#[test]
+fn latex_stacks_extra_premises() {
+    let leaf = |ast: Ast| Tree {
+        above: vec![],
+        rule: "axiom".into(),
+        principal: None,
+        below: RhsOnlyWithExchange::from_rhs(ast),
+    };
+    let proof = Tree {
+        above: [
+            IndexedAst::One,
+            IndexedAst::Bottom,
+            IndexedAst::Top,
+            IndexedAst::Zero,
+        ]
+        .into_iter()
+        .map(leaf)
+        .collect(),
+        rule: "many".into(),
+        principal: None,
+        below: RhsOnlyWithExchange::from_rhs(IndexedAst::One),
+    };
+    let latex = proof.to_latex();
+    assert_eq!(latex.matches("\\noLine").count(), 1);
+    assert_eq!(latex.matches("\\TrinaryInfC").count(), 1);
+    assert_eq!(latex.matches("\\AxiomC").count(), 4);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn serde_round_trip() {
+    let proof = prove(IndexedAst::One & IndexedAst::One).unwrap();
+    let json = serde_json::to_string(&proof).unwrap();
+    let back: Tree<RhsOnlyWithExchange<Ast>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, proof);
+    assert_eq!(back.to_string(), proof.to_string());
+    back.validate().unwrap();
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn serde_round_trip_rule() {
+    let rule = Rule::new("&", [RhsOnlyWithExchange::from_rhs(IndexedAst::One)]);
+    let json = serde_json::to_string(&rule).unwrap();
+    let back: Rule<RhsOnlyWithExchange<Ast>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.name, rule.name);
+    assert_eq!(back.above, rule.above);
+}
+
+#[test]
+fn json_1_times_1() {
+    let proof = prove(IndexedAst::One * IndexedAst::One).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&proof.to_json()).unwrap();
+    assert_eq!(json["sequent"], "\u{22a2} 1 \u{2297} 1");
+    assert_eq!(json["rule"], "\u{2297}");
+    let premises = json["premises"].as_array().unwrap();
+    assert_eq!(premises.len(), 2);
+    assert!(premises
+        .iter()
+        .all(|premise| premise["sequent"] == "\u{22a2} 1"
+            && premise["premises"] == serde_json::json!([])));
+}
+
+#[test]
+fn json_escapes_strings() {
+    let proof = Tree {
+        above: vec![],
+        rule: "\"quoted\"\n\\".into(),
+        principal: None,
+        below: RhsOnlyWithExchange::from_rhs(IndexedAst::One),
+    };
+    let json: serde_json::Value = serde_json::from_str(&proof.to_json()).unwrap();
+    assert_eq!(json["rule"], "\"quoted\"\n\\");
+}
+
+#[test]
+#[allow(clippy::mem_forget)]
+fn json_of_deep_tree() {
+    let mut proof = Tree {
+        above: vec![],
+        rule: "1".into(),
+        principal: None,
+        below: RhsOnlyWithExchange::from_rhs(IndexedAst::One),
+    };
+    for _ in 0..10_000 {
+        proof = Tree {
+            above: [proof].into_iter().collect(),
+            rule: "\u{22a5}".into(),
+            principal: None,
+            below: RhsOnlyWithExchange::from_rhs(IndexedAst::One),
+        };
+    }
+    let json = proof.to_json();
+    assert_eq!(json.matches("\"premises\"").count(), 10_001);
+    assert!(json.ends_with(&"]}".repeat(10_001)));
+    let html = proof.to_html();
+    assert_eq!(html.matches("<details open>").count(), 10_000);
+    assert_eq!(html.matches("</details>").count(), 10_000);
+    // Dropping is recursive and would overflow the stack on its own.
+    core::mem::forget(proof);
+}
+
+#[test]
+fn html_is_well_formed_and_escaped() {
+    let original = (IndexedAst::One & IndexedAst::Top) * IndexedAst::One;
+    let proof = prove(original).unwrap();
+    let html = proof.to_html();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.ends_with("</html>\n"));
+    for tag in [
+        "html", "head", "title", "style", "body", "div", "details", "summary", "span",
+    ] {
+        assert_eq!(
+            html.matches(&format!("<{tag}>")).count() + html.matches(&format!("<{tag} ")).count(),
+            html.matches(&format!("</{tag}>")).count(),
+            "{tag}",
+        );
+    }
+    // One `<div class="inference">` per inference line, and a `<details>` for each with premises.
+    assert_eq!(html.matches("class=\"inference\"").count(), proof.len());
+    assert_eq!(
+        html.matches("<details open>").count(),
+        proof.iter().filter(|tree| !tree.above.is_empty()).count(),
+    );
+    assert!(html.contains("<title>⊢ 1 &amp; ⊤ ⊗ 1</title>"));
+    assert!(html.contains("<div class=\"conclusion\">⊢ 1 &amp; ⊤<span"));
+    assert!(!html.contains("1 & ⊤"));
+}
+
+#[test]
+fn ascii_1_times_1() {
+    let proof = prove(IndexedAst::One * IndexedAst::One).unwrap();
+    assert_eq!(
+        proof.to_ascii_string(),
+        "
+---- 1 on (1)   ---- 1 on (1)
+|- 1            |- 1
+-------------------- * on (1 * 1)
+      |- 1 * 1
+",
+    );
+}
+
+#[test]
+fn render_two_formulas_three_ways() {
+    let sequent =
+        RhsOnlyWithExchange::from_rhs(IndexedAst::One * IndexedAst::One).with([IndexedAst::Top]);
+    assert_eq!(sequent.render(&Unicode), "\u{22a2} \u{22a4}, 1 \u{2297} 1");
+    assert_eq!(sequent.render(&Unicode), sequent.to_string());
+    assert_eq!(sequent.render(&Ascii), "|- top, 1 * 1");
+    assert_eq!(sequent.render(&Ascii), format!("{sequent:#}"));
+    assert_eq!(
+        sequent.render(&Latex::default()),
+        r"\vdash \top, 1 \otimes 1"
+    );
+    assert_eq!(render_sequent(&sequent, &Ascii), sequent.render(&Ascii));
+}
+
+#[test]
+fn ascii_has_no_unicode() {
+    let proof =
+        prove((IndexedAst::Top & IndexedAst::Top).par(IndexedAst::One + IndexedAst::Bottom))
+            .unwrap();
+    assert!(!proof.to_string().is_ascii());
+    assert!(proof.to_ascii_string().is_ascii());
+    assert!(format!("{:#}", proof.below).is_ascii());
+}
+
+#[test]
+fn parse_units_and_atoms() {
+    assert_eq!("1".parse(), Ok(IndexedAst::One));
+    assert_eq!("0".parse(), Ok(IndexedAst::Zero));
+    assert_eq!("top".parse(), Ok(IndexedAst::Top));
+    assert_eq!("\u{22a4}".parse(), Ok(IndexedAst::Top));
+    assert_eq!("bot".parse(), Ok(IndexedAst::Bottom));
+    assert_eq!("\u{22a5}".parse(), Ok(IndexedAst::Bottom));
+    assert_eq!(" P42 ".parse(), Ok(IndexedAst::Value(42)));
+}
+
+#[test]
+fn parse_precedence() {
+    let p = IndexedAst::Value;
+    assert_eq!(parse_ast("P0 * P1 par P2"), Ok((p(0) * p(1)).par(p(2))),);
+    assert_eq!(parse_ast("P0 & P1 + P2"), Ok((p(0) & p(1)) + p(2)));
+    assert_eq!(parse_ast("P0 + P1 * P2"), Ok((p(0) + p(1)) * p(2)));
+    assert_eq!(parse_ast("P0 * P1 * P2"), Ok((p(0) * p(1)) * p(2)));
+    assert_eq!(parse_ast("P0 -o P1 -o P2"), Ok(p(0) - (p(1) - p(2))));
+    assert_eq!(parse_ast("P0 \u{22b8} P1"), Ok(p(0) - p(1)));
+    assert_eq!(parse_ast("!P0 * ~?P1"), Ok(bang(p(0)) * -quest(p(1))));
+    assert_eq!(parse_ast("P0 * (P1 par P2)"), Ok(p(0) * p(1).par(p(2))));
+}
+
+#[test]
+fn parse_display_round_trip() {
+    let ast = bang(IndexedAst::Value(3))
+        * (IndexedAst::Top & -IndexedAst::Zero).par(quest(IndexedAst::Bottom) + IndexedAst::One);
+    assert_eq!(parse_ast(&ast.to_string()), Ok(ast));
+}
+
+#[test]
+fn parse_errors_have_offsets() {
+    assert_eq!(
+        parse_ast("P0 * "),
+        Err(ParseError::UnexpectedEnd { offset: 5 }),
+    );
+    assert_eq!(
+        parse_ast("P0 ) P1"),
+        Err(ParseError::UnexpectedToken {
+            offset: 3,
+            found: ")".to_owned(),
+        }),
+    );
+    assert_eq!(
+        parse_ast("(P0 P1)"),
+        Err(ParseError::UnexpectedToken {
+            offset: 4,
+            found: "P1".to_owned(),
+        }),
+    );
+    assert_eq!(
+        parse_ast("\u{22a4} # P1"),
+        Err(ParseError::UnexpectedToken {
+            offset: 4,
+            found: "#".to_owned(),
+        }),
+    );
+    assert_eq!(
+        parse_ast("Q1"),
+        Err(ParseError::UnexpectedToken {
+            offset: 0,
+            found: "Q1".to_owned(),
+        }),
+    );
+}
+
+#[cfg(feature = "quickcheck")]
+quickcheck::quickcheck! {
+    fn parse_inverts_display(ast: Ast) -> bool {
+        parse_ast(&ast.to_string()) == Ok(ast)
+    }
+}
+
+/// Unary connectives shrink the size by one and binary connectives split it in half,
+/// so no arbitrary formula has more than `2 * size + 1` nodes or `size + 1` levels.
+#[test]
+#[cfg(feature = "quickcheck")]
+fn arbitrary_asts_are_size_bounded() {
+    for size in [0, 1, 4, 5, 13, 100, 1_000] {
+        let mut g = quickcheck::Gen::new(size);
+        for _ in 0..100 {
+            let ast: Ast = quickcheck::Arbitrary::arbitrary(&mut g);
+            assert!(ast.size() <= 2 * size + 1, "{ast} is too big for {size}");
+            assert!(ast.depth() <= size + 1, "{ast} is too deep for {size}");
+        }
+    }
+}
+
+#[test]
+fn display_minimal_parentheses() {
+    let p = IndexedAst::Value;
+    assert_eq!(
+        (p(0) * p(1)).par(p(2)).to_string(),
+        "P0 \u{2297} P1 \u{214b} P2"
+    );
+    assert_eq!(
+        p(0).par(p(1) * p(2)).to_string(),
+        "P0 \u{214b} P1 \u{2297} P2"
+    );
+    assert_eq!(
+        (p(0).par(p(1)) * p(2)).to_string(),
+        "(P0 \u{214b} P1) \u{2297} P2"
+    );
+    assert_eq!(
+        ((p(0) * p(1)) * p(2)).to_string(),
+        "P0 \u{2297} P1 \u{2297} P2"
+    );
+    assert_eq!(
+        (p(0) * (p(1) * p(2))).to_string(),
+        "P0 \u{2297} (P1 \u{2297} P2)"
+    );
+    assert_eq!(((p(0) & p(1)) + p(2)).to_string(), "P0 & P1 \u{2295} P2");
+    assert_eq!((p(0) & (p(1) + p(2))).to_string(), "P0 & (P1 \u{2295} P2)");
+    assert_eq!(bang(-IndexedAst::One).to_string(), "!~1");
+    assert_eq!(quest(p(0) * p(1)).to_string(), "?(P0 \u{2297} P1)");
+    assert_eq!((-(p(0) & IndexedAst::Top)).to_string(), "~(P0 & \u{22a4})");
+}
+
+#[test]
+fn display_round_trips_through_parse() {
+    let p = IndexedAst::Value;
+    for ast in [
+        (p(0) * p(1)).par(p(2)),
+        p(0).par(p(1) * p(2)),
+        p(0) * (p(1) * (p(2) * p(3))),
+        ((p(0) + p(1)) & p(2)).par(-(p(3) - p(4))),
+        quest(bang(p(0)) & IndexedAst::Bottom) + IndexedAst::Zero,
+    ] {
+        assert_eq!(parse_ast(&ast.to_string()), Ok(ast));
+    }
+}
+
+#[test]
+fn nnf_pushes_duals_to_values() {
+    let p = IndexedAst::Value;
+    let ast = -(bang(p(0)) * (IndexedAst::One & -p(1)));
+    assert!(!ast.is_nnf());
+    let nnf = ast.nnf();
+    assert!(nnf.is_nnf());
+    assert_eq!(nnf, quest(-p(0)).par(IndexedAst::Bottom + p(1)));
+    assert_eq!((-(-(-p(2)))).nnf(), -p(2));
+    assert_eq!((-IndexedAst::Top).nnf(), IndexedAst::Zero);
+}
+
+#[test]
+fn nnf_proofs_are_shorter() {
+    let ast = -(IndexedAst::Zero * IndexedAst::Zero) + IndexedAst::Zero;
+    let plain = ast.clone().prove().unwrap();
+    let nnf = ast.prove_nnf().unwrap();
+    assert!(nnf.depth() < plain.depth());
+}
+
+#[cfg(feature = "quickcheck")]
+quickcheck::quickcheck! {
+    /// `prove` agrees on an expression and its negation normal form
+    /// (skipping large expressions and any that either search gives up on).
+    fn nnf_preserves_provability(ast: Ast) -> quickcheck::TestResult {
+        const BUDGET: usize = 200;
+        if ast.to_string().len() > 24 {
+            return quickcheck::TestResult::discard();
+        }
+        match (
+            prove_with_limit(ast.clone(), BUDGET),
+            prove_with_limit(ast.nnf(), BUDGET),
+        ) {
+            (Err(Error::LimitExceeded { .. }), _) | (_, Err(Error::LimitExceeded { .. })) => {
+                quickcheck::TestResult::discard()
+            }
+            (plain, nnf) => quickcheck::TestResult::from_bool(plain.is_ok() == nnf.is_ok()),
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+quickcheck::quickcheck! {
+    /// `prove_dfs` and `prove` agree on small expressions
+    /// (skipping any that either search gives up on).
+    fn dfs_agrees_with_prove_on_small_asts(ast: Ast) -> quickcheck::TestResult {
+        const BUDGET: usize = 200;
+        const MAX_DEPTH: usize = 12;
+        if ast.to_string().len() > 24 {
+            return quickcheck::TestResult::discard();
+        }
+        match (
+            prove_with_limit(ast.clone(), BUDGET),
+            prove_dfs::<_, RhsOnlyWithExchange<_>>(ast, MAX_DEPTH),
+        ) {
+            (Err(Error::LimitExceeded { .. }), _) | (_, Err(Error::DepthExceeded { .. })) => {
+                quickcheck::TestResult::discard()
+            }
+            (bfs, dfs) => quickcheck::TestResult::from_bool(bfs.is_ok() == dfs.is_ok()),
+        }
+    }
+}
+
+#[cfg(feature = "proptest")]
+proptest::proptest! {
+    #[test]
+    fn nnf_is_idempotent(ast in ast_strategy(6)) {
+        let nnf = ast.nnf();
+        proptest::prop_assert!(nnf.is_nnf());
+        proptest::prop_assert_eq!(nnf.clone().nnf(), nnf);
+    }
+
+    #[test]
+    fn strategy_respects_depth(ast in ast_strategy(6)) {
+        proptest::prop_assert!(ast.depth() <= 7, "{} is too deep", ast);
+    }
+}
+
+#[test]
+fn metrics_of_small_formulas() {
+    let p = IndexedAst::Value;
+    assert_eq!(IndexedAst::One.size(), 1);
+    assert_eq!(IndexedAst::One.depth(), 1);
+    assert!(IndexedAst::One.atoms().is_empty());
+    let ast = (p(0) * -p(1)).par(bang(p(0)) & IndexedAst::Top);
+    assert_eq!(ast.size(), 9);
+    assert_eq!(ast.depth(), 4);
+    assert_eq!(ast.atoms(), [0, 1].into_iter().collect());
+    assert_eq!(ast.atom_occurrences(), [0, 0, 1].into_iter().collect());
+}
+
+#[test]
+#[allow(clippy::mem_forget)]
+fn metrics_of_deep_formulas() {
+    let mut ast = IndexedAst::Value(0);
+    for i in 1..100_000 {
+        ast = IndexedAst::Value(i).par(ast);
+    }
+    assert_eq!(ast.size(), 199_999);
+    assert_eq!(ast.depth(), 100_000);
+    assert_eq!(ast.atoms().len(), 100_000);
+    assert_eq!(ast.atom_occurrences().len(), 100_000);
+    // Dropping is recursive and would overflow the stack on its own.
+    core::mem::forget(ast);
+}
+
+#[test]
+fn substitute_introduces_connectives() {
+    let p = IndexedAst::Value;
+    let schema = (p(0) * -p(1)).par(p(2));
+    let map: BTreeMap<_, _> = [(0, IndexedAst::One & p(3)), (1, bang(p(0)))]
+        .into_iter()
+        .collect();
+    assert_eq!(
+        schema.substitute(&map),
+        ((IndexedAst::One & p(3)) * -bang(p(0))).par(p(2)),
+    );
+    assert_eq!(schema.substitute(&BTreeMap::new()), schema);
+}
+
+#[test]
+fn substitute_preserves_tautologies() {
+    let identity = IndexedAst::Value(0) - IndexedAst::Value(0);
+    identity.clone().prove().unwrap();
+    let map: BTreeMap<_, _> = [(0, IndexedAst::Value(1) * IndexedAst::Value(2))]
+        .into_iter()
+        .collect();
+    let instance = identity.substitute(&map);
+    assert_eq!(
+        instance,
+        (IndexedAst::Value(1) * IndexedAst::Value(2))
+            - (IndexedAst::Value(1) * IndexedAst::Value(2)),
+    );
+    instance.prove().unwrap();
+}
+
+#[test]
+fn rename_atoms_renumbers() {
+    let p = IndexedAst::Value;
+    let ast = (p(0) * -p(1)) & quest(p(0));
+    assert_eq!(
+        ast.rename_atoms(|i| i + 10),
+        (p(10) * -p(11)) & quest(p(10)),
+    );
+}
+
+#[test]
+fn named_atoms() {
+    let rain: Ast<&'static str> = Ast::Value("rain");
+    let proof = (rain.clone() - rain).prove().unwrap();
+    assert_eq!(proof.below.to_string(), "\u{22a2} rain \u{22b8} rain");
+}
+
+#[test]
+fn rename_atoms_changes_type() {
+    let ast = IndexedAst::Value(0) * -IndexedAst::Value(1);
+    let names = ["rain", "wet"];
+    assert_eq!(
+        ast.rename_atoms(|i| names[i]),
+        Ast::Value("rain") * -Ast::Value("wet"),
+    );
+}
+
+#[test]
+fn lollipop_shows_in_proofs() {
+    let a = IndexedAst::Value(0);
+    let proof = a.clone().lollipop(a).prove().unwrap();
+    assert_eq!(proof.rule, "\u{22b8}");
+    assert!(proof.to_string().contains("\u{22a2} P0 \u{22b8} P0"));
+}
+
+#[test]
+fn lollipop_display_nesting() {
+    let p = IndexedAst::Value;
+    assert_eq!(
+        p(0).lollipop(p(1).lollipop(p(2))).to_string(),
+        "P0 \u{22b8} P1 \u{22b8} P2",
+    );
+    assert_eq!(
+        p(0).lollipop(p(1)).lollipop(p(2)).to_string(),
+        "(P0 \u{22b8} P1) \u{22b8} P2",
+    );
+    assert_eq!(
+        (p(0) * p(1)).lollipop(p(2).par(p(3))).to_string(),
+        "P0 \u{2297} P1 \u{22b8} P2 \u{214b} P3",
+    );
+    assert_eq!(
+        (p(0).lollipop(p(1)) & p(2)).to_string(),
+        "(P0 \u{22b8} P1) & P2",
+    );
+    assert_eq!((-p(0).lollipop(p(1))).to_string(), "~(P0 \u{22b8} P1)",);
+    for ast in [
+        p(0).lollipop(p(1).lollipop(p(2))),
+        p(0).lollipop(p(1)).lollipop(p(2)),
+        (p(0) * p(1)).lollipop(p(2).par(p(3))),
+    ] {
+        assert_eq!(parse_ast(&ast.to_string()), Ok(ast));
+    }
+}
+
+#[test]
+fn lollipop_means_dual_par() {
+    let p = IndexedAst::Value;
+    let sugar = p(0).lollipop(p(1));
+    assert_eq!(sugar.clone().nnf(), (-p(0)).par(p(1)));
+    assert_eq!((-sugar).nnf(), p(0) * -p(1));
+    assert!((p(0) * p(1)).lollipop(p(1) * p(0)).prove().is_ok());
+}
+
+#[test]
+fn ll_units_and_atoms() {
+    let one: IndexedAst = ll!(1);
+    assert_eq!(one, IndexedAst::One);
+    let zero: IndexedAst = ll!(0);
+    assert_eq!(zero, IndexedAst::Zero);
+    let top: IndexedAst = ll!(top);
+    assert_eq!(top, IndexedAst::Top);
+    let bottom: IndexedAst = ll!(bot);
+    assert_eq!(bottom, IndexedAst::Bottom);
+    assert_eq!(ll!(P 7), IndexedAst::Value(7));
+    let ast = IndexedAst::Value(3) * IndexedAst::Top;
+    assert_eq!(ll!({ ast.clone() } & P 1), ast & IndexedAst::Value(1));
+}
+
+#[test]
+fn ll_connectives() {
+    let p = IndexedAst::Value;
+    assert_eq!(ll!(!P 0), bang(p(0)));
+    assert_eq!(ll!(?P 0), quest(p(0)));
+    assert_eq!(ll!(~P 0), -p(0));
+    assert_eq!(ll!(P 0 * P 1), p(0) * p(1));
+    assert_eq!(ll!(P 0 par P 1), p(0).par(p(1)));
+    assert_eq!(ll!(P 0 & P 1), p(0) & p(1));
+    assert_eq!(ll!(P 0 + P 1), p(0) + p(1));
+    assert_eq!(ll!(P 0 -o P 1), p(0).lollipop(p(1)));
+}
+
+#[test]
+fn ll_precedence() {
+    let p = IndexedAst::Value;
+    assert_eq!(
+        ll!(!(P 0) * (P 1 + 0) -o bot),
+        (bang(p(0)) * (p(1) + IndexedAst::Zero)).lollipop(IndexedAst::Bottom),
+    );
+    assert_eq!(ll!(P 0 * P 1 par P 2), (p(0) * p(1)).par(p(2)));
+    assert_eq!(ll!(P 0 + P 1 * P 2), (p(0) + p(1)) * p(2));
+    assert_eq!(ll!(P 0 & P 1 + P 2), (p(0) & p(1)) + p(2));
+    assert_eq!(ll!(P 0 * P 1 * P 2), (p(0) * p(1)) * p(2));
+    assert_eq!(ll!(P 0 -o P 1 -o P 2), p(0).lollipop(p(1).lollipop(p(2))));
+    assert_eq!(ll!(~!P 0 * P 1), -bang(p(0)) * p(1));
+    assert_eq!(
+        ll!(P 0 * P 1 par P 2 -o P 3),
+        parse_ast("P0 * P1 par P2 -o P3").unwrap(),
+    );
+}
+
+/// Count how much each thread allocates, so tests can check what sharing saves.
+struct CountingAllocator;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+thread_local! {
+    /// Bytes this thread has allocated so far.
+    static ALLOCATED: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+}
+
+// SAFETY: Hands every request straight to the system allocator, only counting on the way.
+#[allow(unsafe_code)]
+unsafe impl core::alloc::GlobalAlloc for CountingAllocator {
+    #[inline]
+    unsafe fn alloc(&self, layout: core::alloc::Layout) -> *mut u8 {
+        // Fails only while this thread is being torn down, when nobody's counting.
+        ALLOCATED
+            .try_with(|bytes| bytes.set(bytes.get().saturating_add(layout.size())))
+            .unwrap_or(());
+        // SAFETY: Same contract as ours.
+        unsafe { std::alloc::System.alloc(layout) }
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: core::alloc::Layout) {
+        // SAFETY: Same contract as ours, and `ptr` came from `System.alloc`.
+        unsafe { std::alloc::System.dealloc(ptr, layout) }
+    }
+}
+
+/// Run `f` and count the bytes it allocated on this thread.
+fn allocated_while<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATED.with(core::cell::Cell::get);
+    let result = f();
+    let after = ALLOCATED.with(core::cell::Cell::get);
+    (result, after.saturating_sub(before))
+}
+
+/// Balanced tree of `⊗`s built from scratch, so no two subexpressions share anything.
+fn unshared_tower(height: usize) -> IndexedAst {
+    height.checked_sub(1).map_or(IndexedAst::Value(0), |below| {
+        unshared_tower(below) * unshared_tower(below)
+    })
+}
+
+#[test]
+fn cloning_allocates_nothing() {
+    let ast = unshared_tower(10);
+    let (clone, bytes) = allocated_while(|| ast.clone());
+    assert_eq!(bytes, 0);
+    assert_eq!(clone, ast);
+}
+
+#[test]
+fn interning_shares_equal_subexpressions() {
+    let mut interner = Interner::new();
+    let interned = interner.intern(&unshared_tower(3));
+    assert_eq!(interned, unshared_tower(3));
+    assert_eq!(interner.len(), 3);
+    let IndexedAst::Times(ref lhs, ref rhs) = interned else {
+        panic!("expected a tensor, got {interned}");
+    };
+    assert!(Arc::ptr_eq(lhs, rhs));
+    let IndexedAst::Times(ref again, _) = interner.intern(&unshared_tower(3)) else {
+        panic!("expected a tensor");
+    };
+    assert!(Arc::ptr_eq(lhs, again));
+    assert_eq!(interner.len(), 3);
+}
+
+#[test]
+fn interning_cuts_allocations() {
+    let (unshared, unshared_bytes) = allocated_while(|| unshared_tower(12));
+    let (interned, interned_bytes) = allocated_while(|| Interner::new().intern(&unshared));
+    assert_eq!(interned, unshared);
+    assert!(
+        interned_bytes.saturating_mul(10) < unshared_bytes,
+        "interning took {interned_bytes} bytes, but building from scratch took {unshared_bytes}",
+    );
+}
+
+#[test]
+fn sampling_lazily_skips_unused_contexts() {
+    let sequent = RhsOnlyWithExchange::new((0..10).map(IndexedAst::Value).collect());
+    let (first, first_bytes) = allocated_while(|| sequent.sample().next());
+    let (all, all_bytes) = allocated_while(|| sequent.sample().collect::<Vec<_>>());
+    assert_eq!(all.len(), 10);
+    assert_eq!(first.as_ref(), all.first());
+    assert!(
+        first_bytes.saturating_mul(5) < all_bytes,
+        "the first focus took {first_bytes} bytes, but every focus took {all_bytes}",
+    );
+}
+
+#[test]
+fn prove_six_way_times() {
+    let p = IndexedAst::Value;
+    let tensors = (1..6).fold(p(0), |ast, i| ast * p(i));
+    let duals = (1..6).fold(-p(0), |ast, i| ast.par(-p(i)));
+    let (result, stats) = prove_with_stats(duals.par(tensors));
+    result.unwrap();
+    assert!(stats.dequeued > 0);
+    // Each premise is checked once when paused and at most once more when proven.
+    assert!(stats.premise_checks <= 4 * stats.inferences_paused);
+}
+
+#[test]
+fn premise_checks_stay_linear() {
+    let original =
+        IndexedAst::One & IndexedAst::One & IndexedAst::One & IndexedAst::One & IndexedAst::One;
+    let (result, stats) = prove_with_stats(original);
+    result.unwrap();
+    assert!(stats.premise_checks > 0);
+    assert!(stats.premise_checks <= 4 * stats.inferences_paused);
+}
+
+#[test]
+fn prune_inferences_already_proven() {
+    let p = IndexedAst::Value;
+    // Many ways to split the tensors, but only one needs to work.
+    let tensors = (1..4).fold(p(0), |ast, i| ast * p(i));
+    let duals = (1..4).fold(-p(0), |ast, i| ast.par(-p(i)));
+    let contraction = quest(p(0) * p(0)).par(quest(-p(0)));
+    let (result, stats) = prove_with_stats(duals.par(tensors) & contraction);
+    result.unwrap();
+    assert!(stats.inferences_pruned > 0);
+    assert!(stats.peak_paused < stats.inferences_paused);
+}
+
+#[test]
+fn every_strategy_proves_1_times_1() {
+    for strategy in [
+        SearchStrategy::SmallestFirst,
+        SearchStrategy::Fifo,
+        SearchStrategy::Lifo,
+        SearchStrategy::custom(|sequent: &RhsOnlyWithExchange<IndexedAst>| {
+            u64::try_from(sequent.len()).unwrap_or(u64::MAX)
+        }),
+    ] {
+        prove_with_strategy(IndexedAst::One * IndexedAst::One, strategy).unwrap();
+    }
+}
+
+#[test]
+fn custom_strategy_is_consulted() {
+    let calls = Arc::new(core::sync::atomic::AtomicUsize::new(0));
+    let counter = Arc::clone(&calls);
+    let strategy = SearchStrategy::custom(move |_: &RhsOnlyWithExchange<IndexedAst>| {
+        let _ = counter.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+        0
+    });
+    prove_with_strategy(IndexedAst::One * IndexedAst::One, strategy).unwrap();
+    assert!(calls.load(core::sync::atomic::Ordering::Relaxed) > 0);
+}
+
+#[test]
+fn proofs_are_deterministic() {
+    let p = IndexedAst::Value;
+    let tensors = (1..4).fold(p(0), |ast, i| ast * p(i));
+    let duals = (1..4).fold(-p(0), |ast, i| ast.par(-p(i)));
+    // Many derivations of each: which `⊕` to pick, and how to split and contract.
+    for original in [
+        IndexedAst::One + IndexedAst::One + IndexedAst::One,
+        duals.par(tensors) & quest(p(0) * p(0)).par(quest(-p(0))),
+    ] {
+        let first = prove(original.clone()).unwrap().to_string();
+        for _ in 0..20 {
+            assert_eq!(prove(original.clone()).unwrap().to_string(), first);
+        }
+    }
+}
+
+/// FNV-1a: deterministic, unlike the standard library's randomly seeded default.
+#[derive(Clone, Copy, Debug)]
+struct Fnv(u64);
+
+impl Default for Fnv {
+    #[inline]
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl core::hash::Hasher for Fnv {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[test]
+fn custom_hasher_finds_the_same_proofs() {
+    let p = IndexedAst::Value;
+    let tensors = (1..4).fold(p(0), |ast, i| ast * p(i));
+    let duals = (1..4).fold(-p(0), |ast, i| ast.par(-p(i)));
+    for original in [
+        IndexedAst::One + IndexedAst::One + IndexedAst::One,
+        duals.par(tensors),
+        quest(p(0) * p(0)).par(quest(-p(0))),
+    ] {
+        let config = SearchConfig::<_, core::hash::BuildHasherDefault<Fnv>>::default();
+        assert_eq!(prove_with_config(original.clone(), config), prove(original));
+    }
+    assert_eq!(
+        prove_with_config(IndexedAst::Zero, SearchConfig::new()),
+        Err(Error::RanOutOfPaths),
+    );
+}
+
+#[test]
+fn contraction_gives_up_past_the_copy_cap() {
+    let (result, stats) = prove_with_stats(quest(IndexedAst::Value(0)));
+    assert_eq!(
+        result,
+        Err(Error::Incomplete {
+            max_width: None,
+            max_copies: Some(4),
+        }),
+    );
+    assert!(stats.over_copy_cap > 0);
+    assert!(stats.dequeued < 32, "explored {} sequents", stats.dequeued);
+}
+
+#[test]
+fn copy_cap_is_configurable() {
+    let p = IndexedAst::Value;
+    // Needs two copies of `?~P0`, one for each side of the tensor.
+    let original = quest(-p(0)).par(p(0) * p(0));
+    prove(original.clone()).unwrap();
+    let config = SearchConfig {
+        max_copies: Some(1),
+        ..SearchConfig::new()
+    };
+    assert_eq!(
+        prove_with_config(original, config),
+        Err(Error::Incomplete {
+            max_width: None,
+            max_copies: Some(1),
+        }),
+    );
+}
+
+#[test]
+fn width_cap_is_configurable() {
+    let p = IndexedAst::Value;
+    // Splitting the tensor needs `P0, P1, P2` all beside it: four items at once.
+    let original = p(0).par(p(1)).par(p(2)).par(-p(0) * -p(1) * -p(2));
+    let narrow = SearchConfig {
+        max_width: Some(3),
+        ..SearchConfig::new()
+    };
+    let wide = SearchConfig {
+        max_width: Some(4),
+        ..SearchConfig::new()
+    };
+    assert_eq!(
+        prove_with_config(original.clone(), narrow),
+        Err(Error::Incomplete {
+            max_width: Some(3),
+            max_copies: None,
+        }),
+    );
+    let proof = prove_with_config(original.clone(), wide).unwrap();
+    assert_eq!(proof, prove(original).unwrap());
+    assert!(proof.iter().all(|node| node.below.width() <= 4));
+}
+
+#[test]
+fn width_cap_leaves_narrow_failures_alone() {
+    let config = SearchConfig {
+        max_width: Some(1),
+        ..SearchConfig::new()
+    };
+    assert_eq!(
+        prove_with_config(IndexedAst::Zero, config),
+        Err(Error::RanOutOfPaths),
+    );
+}
+
+#[test]
+fn invertible_rules_shrink_refutations() {
+    let p = IndexedAst::Value;
+    // `((a ⅋ b) ⅋ c) ⅋ d` against a tensor that never mentions `a`.
+    let pars = (1..4).fold(p(0), |ast, i| ast.par(p(i)));
+    let tensors = (1..4).fold(-p(4), |ast, i| ast * -p(i));
+    let (result, stats) = prove_with_stats(pars.par(tensors));
+    assert!(result.is_err());
+    // Enumerating every rule at every sequent explores 143 sequents here.
+    assert!(stats.dequeued < 143, "explored {} sequents", stats.dequeued);
+}
+
+#[test]
+fn polarity_of_connectives() {
+    let p = IndexedAst::Value;
+    assert_eq!(p(0).polarity(), Polarity::Positive);
+    assert_eq!((-p(0)).polarity(), Polarity::Negative);
+    assert_eq!((p(0) * p(1)).polarity(), Polarity::Positive);
+    assert_eq!(p(0).par(p(1)).polarity(), Polarity::Negative);
+    assert_eq!((-(p(0) + p(1))).polarity(), Polarity::Negative);
+    assert_eq!((-quest(p(0))).polarity(), Polarity::Positive);
+    assert_eq!(p(0).lollipop(p(1)).polarity(), Polarity::Negative);
+}
+
+#[test]
+fn focused_agrees_with_prove() {
+    let p = IndexedAst::Value;
+    let tensors = (1..4).fold(p(0), |ast, i| ast * p(i));
+    let duals = (1..4).fold(-p(0), |ast, i| ast.par(-p(i)));
+    for original in [
+        IndexedAst::Zero,
+        IndexedAst::One,
+        IndexedAst::Top,
+        IndexedAst::Zero.par(IndexedAst::Top),
+        IndexedAst::Zero - IndexedAst::Zero,
+        IndexedAst::Zero + IndexedAst::One,
+        IndexedAst::One & IndexedAst::One & IndexedAst::One,
+        IndexedAst::Zero & IndexedAst::One,
+        IndexedAst::One & IndexedAst::Zero,
+        (p(0) & p(1)) - p(0),
+        (p(0) & p(1)) - p(1),
+        IndexedAst::Bottom - IndexedAst::Bottom,
+        IndexedAst::One * IndexedAst::One * IndexedAst::One,
+        IndexedAst::One * IndexedAst::Zero,
+        IndexedAst::Zero * IndexedAst::Zero,
+        ll!(1 -o 1 -o 1 -o 1 -o 1 * 1),
+        p(0).par(-p(0)),
+        p(0) + -p(0),
+        p(0) & -p(0),
+        bang(IndexedAst::One).par(quest(IndexedAst::Bottom)),
+        bang(IndexedAst::One),
+        quest(-p(0)).par(quest(-p(1))).par(bang(p(0) * p(1))),
+        p(0).par((-p(0)).par(IndexedAst::Bottom)),
+        duals.clone().par(tensors.clone()),
+        duals.par(tensors) & quest(p(0) * p(0)).par(quest(-p(0))),
+    ] {
+        assert_eq!(
+            prove_focused(original.clone()).is_ok(),
+            prove(original.clone()).is_ok(),
+            "{original}",
+        );
+    }
+}
+
+#[test]
+fn focused_proof_of_par_times_alternation() {
+    let p = IndexedAst::Value;
+    // `(P0 ⊗ P1) ⅋ (P2 ⊗ P3) ⅋ (P4 ⊗ P5)` against its dual, split the same way.
+    let times = (p(0) * p(1)).par(p(2) * p(3)).par(p(4) * p(5));
+    let pars = (-p(0)).par(-p(1)) * (-p(2)).par(-p(3)) * (-p(4)).par(-p(5));
+    let original = times.par(pars);
+    assert_eq!(
+        prove_with_limit(original.clone(), 64),
+        Err(Error::LimitExceeded { explored: 64 }),
+    );
+    prove_with_limit(original.clone(), 1000).unwrap();
+    prove_with_limit(Focused::from(original), 64).unwrap();
+}
+
+#[test]
+fn fragments_by_connectives() {
+    let p = IndexedAst::Value;
+    assert_eq!(
+        (p(0) * -p(1)).lollipop(IndexedAst::Bottom).fragment(),
+        Fragment::Mll
+    );
+    assert_eq!(
+        (p(0) & p(1)).par(IndexedAst::One).fragment(),
+        Fragment::Mall
+    );
+    assert_eq!((-IndexedAst::Top).fragment(), Fragment::Mall);
+    assert_eq!(bang(p(0)).par(-p(0)).fragment(), Fragment::Mell);
+    assert_eq!((quest(p(0)) + p(1)).fragment(), Fragment::Full);
+}
+
+#[test]
+fn auto_agrees_with_prove() {
+    let p = IndexedAst::Value;
+    let tensors = (1..4).fold(p(0), |ast, i| ast * p(i));
+    let duals = (1..4).fold(-p(0), |ast, i| ast.par(-p(i)));
+    for original in [
+        IndexedAst::Zero,
+        IndexedAst::One,
+        IndexedAst::Top,
+        IndexedAst::Bottom,
+        IndexedAst::Zero.par(IndexedAst::Top),
+        IndexedAst::Zero + IndexedAst::One,
+        IndexedAst::One & IndexedAst::Zero,
+        (p(0) & p(1)) - p(0),
+        IndexedAst::Bottom - IndexedAst::Bottom,
+        IndexedAst::One * IndexedAst::One * IndexedAst::One,
+        IndexedAst::One * IndexedAst::Bottom,
+        ll!(1 -o 1 -o 1 -o 1 -o 1 * 1),
+        p(0).par(-p(0)),
+        p(0) * -p(0),
+        p(0).par(p(1)),
+        (p(0) * p(1)).lollipop(p(1) * p(0)),
+        (p(0).par(p(1))).lollipop(p(0) * p(1)),
+        -(-p(0)).par(-p(0)),
+        p(0) + -p(0),
+        bang(IndexedAst::One).par(quest(IndexedAst::Bottom)),
+        quest(-p(0)).par(quest(-p(1))).par(bang(p(0) * p(1))),
+        p(0).par((-p(0)).par(IndexedAst::Bottom)),
+        duals.clone().par(tensors.clone()),
+        duals.par(tensors) & quest(p(0) * p(0)).par(quest(-p(0))),
+    ] {
+        let auto = prove_auto(original.clone());
+        assert_eq!(auto.is_ok(), prove(original.clone()).is_ok(), "{original}");
+        if let Ok(proof) = auto {
+            proof.validate().unwrap();
+        }
+    }
+}
+
+#[test]
+fn auto_rejects_unbalanced_mll_without_searching() {
+    let p = IndexedAst::Value;
+    // Two `P0`s but only one `~P0`, however deeply buried.
+    let original = (p(0) * p(1))
+        .par(-p(1))
+        .par((-p(0)).lollipop(p(0) * IndexedAst::One));
+    assert_eq!(original.fragment(), Fragment::Mll);
+    let (result, stats) = prove_auto_with_stats(original.clone());
+    assert_eq!(result, Err(Error::RanOutOfPaths));
+    assert_eq!(stats.dequeued, 0);
+    assert!(prove_with_stats(original).1.dequeued > 0);
+}
+
+#[test]
+fn mll_search_explores_less_than_prove() {
+    let p = IndexedAst::Value;
+    // `(P0 ⊗ P1) ⅋ (P2 ⊗ P3) ⅋ (P4 ⊗ P5)` against its dual, split the same way.
+    let times = (p(0) * p(1)).par(p(2) * p(3)).par(p(4) * p(5));
+    let pars = (-p(0)).par(-p(1)) * (-p(2)).par(-p(3)) * (-p(4)).par(-p(5));
+    let original = times.par(pars);
+    let (mll, mll_stats) = prove_mll(original.clone());
+    mll.unwrap().validate().unwrap();
+    let (general, general_stats) = prove_with_stats(original);
+    drop(general.unwrap());
+    assert!(
+        mll_stats.dequeued < general_stats.dequeued,
+        "{} vs {}",
+        mll_stats.dequeued,
+        general_stats.dequeued,
+    );
+}
+
+#[cfg(feature = "quickcheck")]
+quickcheck::quickcheck! {
+    /// `prove_auto` and `prove` agree on small expressions
+    /// (skipping any that the general search gives up on).
+    fn auto_agrees_with_prove_on_small_asts(ast: Ast) -> quickcheck::TestResult {
+        const BUDGET: usize = 200;
+        if ast.to_string().len() > 24 {
+            return quickcheck::TestResult::discard();
+        }
+        match prove_with_limit(ast.clone(), BUDGET) {
+            Err(Error::LimitExceeded { .. }) => quickcheck::TestResult::discard(),
+            general => quickcheck::TestResult::from_bool(general.is_ok() == prove_auto(ast).is_ok()),
+        }
+    }
+}
+
+#[test]
+fn audit_finds_a_rule_for_every_connective_but_zero() {
+    let p = || IndexedAst::Value(0);
+    let samples = [
+        IndexedAst::One,
+        IndexedAst::Bottom,
+        IndexedAst::Top,
+        IndexedAst::Zero,
+        IndexedAst::Bang(Arc::new(p())),
+        IndexedAst::Quest(Arc::new(p())),
+        -IndexedAst::One,
+        p() * p(),
+        p().par(p()),
+        p() & p(),
+        p() + p(),
+        p().lollipop(p()),
+    ];
+    let report = audit::<_, RhsOnlyWithExchange<_>, _>(samples.clone());
+    assert_eq!(report.stuck().collect::<Vec<_>>(), ["0"]);
+    for sample in samples {
+        let printed = sample.to_string();
+        let rules = report.rules_for(&printed).unwrap();
+        assert_eq!(rules.is_empty(), sample == IndexedAst::Zero, "{printed}");
+    }
+    assert!(report.fired().contains("?C"));
+    assert_eq!(
+        report.to_string(),
+        "\
+sample   rules
+1        1
+\u{22a5}        \u{22a5}
+\u{22a4}        \u{22a4}
+0        (none)
+!P0      !
+?P0      ?W, ?D, ?C
+~1       ~
+P0 \u{2297} P0  \u{2297}
+P0 \u{214b} P0  \u{214b}
+P0 & P0  &
+P0 \u{2295} P0  +L, +R
+P0 \u{22b8} P0  \u{22b8}
+",
+    );
+}
+
+#[test]
+fn render_stacks_premises_that_dont_fit_side_by_side() {
+    let p = IndexedAst::Value;
+    let original = ((p(0) * p(1)) * (p(2) * p(3))).lollipop((p(1) * p(0)) * (p(3) * p(2)));
+    let proof = prove(original).unwrap();
+    let wide = proof.render(&RenderOptions {
+        max_width: Some(200),
+        ..RenderOptions::new()
+    });
+    assert_eq!(
+        format!("\n{wide}"),
+        "
+--------- axiom   --------- axiom             --------- axiom   --------- axiom
+⊢ P0, ~P0         ⊢ P1, ~P1                   ⊢ P2, ~P2         ⊢ P3, ~P3
+--------------------------- ⊗ on (P1 ⊗ P0)    --------------------------- ⊗ on (P3 ⊗ P2)
+    ⊢ ~P0, ~P1, P1 ⊗ P0                           ⊢ ~P2, ~P3, P3 ⊗ P2
+    -------------------- ⅋ on (~P0 ⅋ ~P1)         -------------------- ⅋ on (~P2 ⅋ ~P3)
+    ⊢ P1 ⊗ P0, ~P0 ⅋ ~P1                          ⊢ P3 ⊗ P2, ~P2 ⅋ ~P3
+    --------------------- ~ on (~(P0 ⊗ P1))       --------------------- ~ on (~(P2 ⊗ P3))
+    ⊢ ~(P0 ⊗ P1), P1 ⊗ P0                         ⊢ ~(P2 ⊗ P3), P3 ⊗ P2
+    ------------------------------------------------------------------- ⊗ on (P1 ⊗ P0 ⊗ (P3 ⊗ P2))
+               ⊢ ~(P0 ⊗ P1), ~(P2 ⊗ P3), P1 ⊗ P0 ⊗ (P3 ⊗ P2)
+               ---------------------------------------------- ⅋ on (~(P0 ⊗ P1) ⅋ ~(P2 ⊗ P3))
+               ⊢ P1 ⊗ P0 ⊗ (P3 ⊗ P2), ~(P0 ⊗ P1) ⅋ ~(P2 ⊗ P3)
+               ---------------------------------------------- ~ on (~(P0 ⊗ P1 ⊗ (P2 ⊗ P3)))
+               ⊢ ~(P0 ⊗ P1 ⊗ (P2 ⊗ P3)), P1 ⊗ P0 ⊗ (P3 ⊗ P2)
+               --------------------------------------------- ⊸ on (P0 ⊗ P1 ⊗ (P2 ⊗ P3) ⊸ P1 ⊗ P0 ⊗ (P3 ⊗ P2))
+                ⊢ P0 ⊗ P1 ⊗ (P2 ⊗ P3) ⊸ P1 ⊗ P0 ⊗ (P3 ⊗ P2)
+",
+    );
+    // Nothing was too wide, so it's just what `Display` prints.
+    assert_eq!(format!("\n{wide}"), proof.to_string());
+    let narrow = proof.render(&RenderOptions {
+        max_width: Some(60),
+        ..RenderOptions::new()
+    });
+    assert_eq!(
+        format!("\n{narrow}"),
+        "
+   --------- axiom   --------- axiom
+   ⊢ P2, ~P2         ⊢ P3, ~P3
+   --------------------------- ⊗ on (P3 ⊗ P2)
+       ⊢ ~P2, ~P3, P3 ⊗ P2
+       -------------------- ⅋ on (~P2 ⅋ ~P3)
+       ⊢ P3 ⊗ P2, ~P2 ⅋ ~P3
+       --------------------- ~ on (~(P2 ⊗ P3))
+       ⊢ ~(P2 ⊗ P3), P3 ⊗ P2
+
+   --------- axiom   --------- axiom
+   ⊢ P0, ~P0         ⊢ P1, ~P1
+   --------------------------- ⊗ on (P1 ⊗ P0)
+       ⊢ ~P0, ~P1, P1 ⊗ P0
+       -------------------- ⅋ on (~P0 ⅋ ~P1)
+       ⊢ P1 ⊗ P0, ~P0 ⅋ ~P1
+       --------------------- ~ on (~(P0 ⊗ P1))
+       ⊢ ~(P0 ⊗ P1), P1 ⊗ P0
+--------------------------------------------- ⊗
+⊢ ~(P0 ⊗ P1), ~(P2 ⊗ P3), P1 ⊗ P0 ⊗ (P3 ⊗ P2)
+---------------------------------------------- ⅋
+⊢ P1 ⊗ P0 ⊗ (P3 ⊗ P2), ~(P0 ⊗ P1) ⅋ ~(P2 ⊗ P3)
+---------------------------------------------- ~
+⊢ ~(P0 ⊗ P1 ⊗ (P2 ⊗ P3)), P1 ⊗ P0 ⊗ (P3 ⊗ P2)
+--------------------------------------------- ⊸
+ ⊢ P0 ⊗ P1 ⊗ (P2 ⊗ P3) ⊸ P1 ⊗ P0 ⊗ (P3 ⊗ P2)
+",
+    );
+    assert!(narrow.lines().all(|line| line.chars().count() <= 60));
+    assert!(wide.lines().any(|line| line.chars().count() > 60));
+}
+
+#[test]
+fn format_centers_premises_three_deep() {
+    let p = IndexedAst::Value;
+    let original = (-p(0))
+        .par(-p(1))
+        .par(-p(2))
+        .par(-p(3))
+        .par(p(0) * ((p(1) * p(2)) * p(3)));
+    let proof = prove(original).unwrap();
+    // Each conclusion sits centered under the line spanning its premises' conclusions,
+    // however much wider one premise is than the other.
+    assert_eq!(
+        proof.to_string(),
+        "
+                                    --------- axiom   --------- axiom
+                                    ⊢ P1, ~P1         ⊢ P2, ~P2
+                  --------- axiom   --------------------------- ⊗ on (P1 ⊗ P2)
+                  ⊢ P3, ~P3             ⊢ ~P1, ~P2, P1 ⊗ P2
+--------- axiom   ----------------------------------------- ⊗ on (P1 ⊗ P2 ⊗ P3)
+⊢ P0, ~P0               ⊢ ~P1, ~P2, ~P3, P1 ⊗ P2 ⊗ P3
+----------------------------------------------------- ⊗ on (P0 ⊗ (P1 ⊗ P2 ⊗ P3))
+      ⊢ ~P0, ~P1, ~P2, ~P3, P0 ⊗ (P1 ⊗ P2 ⊗ P3)
+      ------------------------------------------ ⅋ on (~P0 ⅋ ~P1)
+      ⊢ ~P2, ~P3, P0 ⊗ (P1 ⊗ P2 ⊗ P3), ~P0 ⅋ ~P1
+      ------------------------------------------- ⅋ on (~P0 ⅋ ~P1 ⅋ ~P2)
+      ⊢ ~P3, P0 ⊗ (P1 ⊗ P2 ⊗ P3), ~P0 ⅋ ~P1 ⅋ ~P2
+      -------------------------------------------- ⅋ on (~P0 ⅋ ~P1 ⅋ ~P2 ⅋ ~P3)
+      ⊢ P0 ⊗ (P1 ⊗ P2 ⊗ P3), ~P0 ⅋ ~P1 ⅋ ~P2 ⅋ ~P3
+      --------------------------------------------- ⅋ on (~P0 ⅋ ~P1 ⅋ ~P2 ⅋ ~P3 ⅋ P0 ⊗ (P1 ⊗ P2 ⊗ P3))
+      ⊢ ~P0 ⅋ ~P1 ⅋ ~P2 ⅋ ~P3 ⅋ P0 ⊗ (P1 ⊗ P2 ⊗ P3)
+",
+    );
+}
+
+#[test]
+fn replay_rebuilds_a_traced_proof() {
+    let expr = IndexedAst::One + (IndexedAst::One * IndexedAst::One);
+    let (result, events) = prove_traced::<_, RhsOnlyWithExchange<_>>(expr.clone());
+    let tree = result.unwrap();
+    let goal = RhsOnlyWithExchange::from_rhs(expr);
+    assert_eq!(events.first(), Some(&TraceEvent::Dequeued(goal.clone())));
+    assert_eq!(events.last(), Some(&TraceEvent::Finished(goal)));
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, TraceEvent::RuleGenerated { .. })));
+    assert_eq!(replay(events.clone()).unwrap(), tree);
+    assert_eq!(
+        replay(events.iter().take(events.len() - 1).cloned()),
+        Err(ReplayError::Unfinished),
+    );
+    let forged: Vec<_> = events
+        .into_iter()
+        .filter(|event| !matches!(event, TraceEvent::RuleGenerated { .. }))
+        .collect();
+    assert!(matches!(
+        replay(forged),
+        Err(ReplayError::NeverGenerated(_)),
+    ));
+}
+
+#[test]
+fn promotion_keeps_the_context_for_dereliction_to_strip() {
+    let formula = |s: &str| s.parse::<IndexedAst>().unwrap();
+    let goal = RhsOnlyWithExchange::new(
+        [formula("?~P0"), formula("?P1"), formula("!P0")]
+            .into_iter()
+            .collect(),
+    );
+    let (_, rules) = apply(&goal)
+        .into_iter()
+        .find(|(focus, _)| *focus == formula("!P0"))
+        .unwrap();
+    let kept = RhsOnlyWithExchange::new(
+        [formula("?~P0"), formula("?P1"), formula("P0")]
+            .into_iter()
+            .collect(),
+    );
+    assert_eq!(
+        rules
+            .iter()
+            .map(|rule| (
+                rule.name.as_ref(),
+                rule.above.iter_repeat().collect::<Vec<_>>()
+            ))
+            .collect::<Vec<_>>(),
+        [("!", vec![&kept])],
+    );
+    // Stripping a `?` on the way up is dereliction after promotion, so it needs no rule of its own.
+    prove_sequent(goal).unwrap();
+    let mixed = RhsOnlyWithExchange::new([formula("P1"), formula("!P0")].into_iter().collect());
+    assert!(apply(&mixed)
+        .into_iter()
+        .flat_map(|(_, offered)| offered)
+        .all(|rule| rule.name != "!"));
+}
+
+#[test]
+fn prove_bang_of_quest_implies_quest() {
+    let formula = |s: &str| s.parse::<IndexedAst>().unwrap();
+    assert!(prove(formula("!(?P0 -o ?P0)")).is_ok());
+    assert!(prove(formula("?P0 -o ?P1 -o !(?P0 -o ?P1 -o P0 * P1)")).is_err());
+    assert!(prove(formula("!(!P0 -o ?P0) * !(?~P0 -o ?~P0)")).is_ok());
+}
+
+#[test]
+fn copy_cap_starts_at_what_the_original_has() {
+    let p = IndexedAst::Value;
+    let tensor = -p(0) * -p(0) * -p(0) * -p(0) * -p(0);
+    let goal = RhsOnlyWithExchange::from_rhs(tensor.clone()).with([p(0), p(0), p(0), p(0), p(0)]);
+    prove_sequent(goal).unwrap();
+    // Built up one `⅋` at a time instead, five copies are past the default cap.
+    let original = tensor.par(p(0)).par(p(0)).par(p(0)).par(p(0)).par(p(0));
+    assert_eq!(
+        prove(original.clone()),
+        Err(Error::Incomplete {
+            max_width: None,
+            max_copies: Some(4),
+        }),
+    );
+    let uncapped = SearchConfig {
+        max_copies: None,
+        ..SearchConfig::new()
+    };
+    prove_with_config(original, uncapped).unwrap();
+}
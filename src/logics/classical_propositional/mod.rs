@@ -0,0 +1,276 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Classical propositional logic in a two-sided sequent calculus (G3cp),
+//! with truth tables to check it against.
+
+#[cfg(test)]
+mod test;
+
+use crate::{
+    prove,
+    sequents::{InferLk, LkWithExchange, Sided},
+    Error, Rule, Tree,
+};
+use alloc::{boxed::Box, collections::BTreeSet, vec, vec::Vec};
+
+/// Propositional formula.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Ast {
+    /// Falsehood.
+    Bottom,
+    /// Truth.
+    Top,
+    /// Propositional variable identified by number (for efficient comparison).
+    Atom(usize),
+    /// Negation.
+    Not(Box<Self>),
+    /// Conjunction.
+    And(Box<Self>, Box<Self>),
+    /// Disjunction.
+    Or(Box<Self>, Box<Self>),
+    /// Implication.
+    Implies(Box<Self>, Box<Self>),
+}
+
+/// Derivation of a propositional formula in a two-sided sequent calculus.
+pub type Derivation = Tree<LkWithExchange<Ast>>;
+
+impl core::fmt::Display for Ast {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::Bottom => write!(f, "\u{22a5}"),
+            Self::Top => write!(f, "\u{22a4}"),
+            Self::Atom(i) => write!(f, "p{i}"),
+            Self::Not(ref arg) => write!(f, "\u{ac}{arg}"),
+            Self::And(ref lhs, ref rhs) => write!(f, "({lhs} \u{2227} {rhs})"),
+            Self::Or(ref lhs, ref rhs) => write!(f, "({lhs} \u{2228} {rhs})"),
+            Self::Implies(ref lhs, ref rhs) => write!(f, "({lhs} \u{2192} {rhs})"),
+        }
+    }
+}
+
+impl Ast {
+    /// Implication, since there's no operator for it.
+    #[must_use]
+    #[inline(always)]
+    pub fn implies(self, rhs: Self) -> Self {
+        Self::Implies(Box::new(self), Box::new(rhs))
+    }
+
+    /// Attempt to prove this formula, returning the whole derivation if we can.
+    /// # Errors
+    /// If we can't.
+    #[inline(always)]
+    pub fn prove(self) -> Result<Derivation, Error> {
+        prove(Sided::right(self))
+    }
+
+    /// Whether this formula is true when exactly the atoms in `assignment` are.
+    #[must_use]
+    #[inline]
+    pub fn eval(&self, assignment: &BTreeSet<usize>) -> bool {
+        match *self {
+            Self::Bottom => false,
+            Self::Top => true,
+            Self::Atom(i) => assignment.contains(&i),
+            Self::Not(ref arg) => !arg.eval(assignment),
+            Self::And(ref lhs, ref rhs) => lhs.eval(assignment) && rhs.eval(assignment),
+            Self::Or(ref lhs, ref rhs) => lhs.eval(assignment) || rhs.eval(assignment),
+            Self::Implies(ref lhs, ref rhs) => !lhs.eval(assignment) || rhs.eval(assignment),
+        }
+    }
+
+    /// Every distinct atom in this formula.
+    #[must_use]
+    #[inline]
+    pub fn atoms(&self) -> BTreeSet<usize> {
+        let mut atoms = BTreeSet::new();
+        let mut stack = vec![self];
+        while let Some(ast) = stack.pop() {
+            match *ast {
+                Self::Bottom | Self::Top => {}
+                Self::Atom(i) => drop(atoms.insert(i)),
+                Self::Not(ref arg) => stack.push(arg),
+                Self::And(ref lhs, ref rhs)
+                | Self::Or(ref lhs, ref rhs)
+                | Self::Implies(ref lhs, ref rhs) => stack.extend([&**lhs, &**rhs]),
+            }
+        }
+        atoms
+    }
+
+    /// Whether this formula is true however its atoms are assigned, checked by truth table
+    /// (so it takes time exponential in the number of distinct atoms).
+    #[must_use]
+    #[inline]
+    pub fn is_tautology(&self) -> bool {
+        let atoms: Vec<usize> = self.atoms().into_iter().collect();
+        self.true_under_every(&atoms, &mut BTreeSet::new())
+    }
+
+    /// Whether this formula is true under `assignment` extended by every way to assign `atoms`.
+    #[inline]
+    fn true_under_every(&self, atoms: &[usize], assignment: &mut BTreeSet<usize>) -> bool {
+        let Some((&atom, rest)) = atoms.split_first() else {
+            return self.eval(assignment);
+        };
+        if !self.true_under_every(rest, assignment) {
+            return false;
+        }
+        let _ = assignment.insert(atom);
+        let holds = self.true_under_every(rest, assignment);
+        let _ = assignment.remove(&atom);
+        holds
+    }
+}
+
+impl core::ops::BitAnd<Self> for Ast {
+    type Output = Self;
+    #[inline(always)]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self::And(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl core::ops::BitOr<Self> for Ast {
+    type Output = Self;
+    #[inline(always)]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self::Or(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl core::ops::Not for Ast {
+    type Output = Self;
+    #[inline(always)]
+    fn not(self) -> Self::Output {
+        Self::Not(Box::new(self))
+    }
+}
+
+// Context-sharing rules (G3cp), which admit weakening and contraction
+// and are complete for classical logic without either.
+impl InferLk for Ast {
+    #[inline]
+    fn left(&self, context: LkWithExchange<Self>) -> Vec<Rule<LkWithExchange<Self>>> {
+        match *self {
+            Self::Bottom => vec![Rule::axiom("\u{22a5}L")],
+            Self::Top => vec![Rule::invertible("\u{22a4}L", [context])],
+            Self::Atom(_) if context.rhs.contains(self) => vec![Rule::axiom("axiom")],
+            Self::Atom(_) => vec![],
+            Self::Not(ref arg) => vec![Rule::unary(
+                "\u{ac}L",
+                context.with_rhs([arg.as_ref().clone()]),
+            )],
+            Self::And(ref lhs, ref rhs) => vec![Rule::unary(
+                "\u{2227}L",
+                context.with_lhs([lhs.as_ref().clone(), rhs.as_ref().clone()]),
+            )],
+            Self::Or(ref lhs, ref rhs) => vec![Rule::binary(
+                "\u{2228}L",
+                context.with_lhs([lhs.as_ref().clone()]),
+                context.with_lhs([rhs.as_ref().clone()]),
+            )],
+            Self::Implies(ref lhs, ref rhs) => vec![Rule::binary(
+                "\u{2192}L",
+                context.with_rhs([lhs.as_ref().clone()]),
+                context.with_lhs([rhs.as_ref().clone()]),
+            )],
+        }
+    }
+
+    #[inline]
+    fn right(&self, context: LkWithExchange<Self>) -> Vec<Rule<LkWithExchange<Self>>> {
+        match *self {
+            Self::Top => vec![Rule::axiom("\u{22a4}R")],
+            Self::Atom(_) if context.lhs.contains(self) => vec![Rule::axiom("axiom")],
+            Self::Bottom | Self::Atom(_) => vec![],
+            Self::Not(ref arg) => vec![Rule::unary(
+                "\u{ac}R",
+                context.with_lhs([arg.as_ref().clone()]),
+            )],
+            Self::And(ref lhs, ref rhs) => vec![Rule::binary(
+                "\u{2227}R",
+                context.with_rhs([lhs.as_ref().clone()]),
+                context.with_rhs([rhs.as_ref().clone()]),
+            )],
+            Self::Or(ref lhs, ref rhs) => vec![Rule::unary(
+                "\u{2228}R",
+                context.with_rhs([lhs.as_ref().clone(), rhs.as_ref().clone()]),
+            )],
+            Self::Implies(ref lhs, ref rhs) => vec![Rule::unary(
+                "\u{2192}R",
+                context
+                    .with_lhs([lhs.as_ref().clone()])
+                    .with_rhs([rhs.as_ref().clone()]),
+            )],
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl quickcheck::Arbitrary for Ast {
+    /// Formulas over only three atoms, so that tautologies turn up often.
+    #[inline]
+    #[allow(clippy::indexing_slicing)]
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        let size = g.size();
+        let mut smaller = quickcheck::Gen::new(size.saturating_sub(1).overflowing_shr(1).0);
+        match g.choose(&[0_u8, 1, 2, 3, 4, 5, 6][..size.clamp(3, 7)]) {
+            None | Some(&0) => Self::Bottom,
+            Some(&1) => Self::Top,
+            Some(&2) => Self::Atom(g.choose(&[0, 1, 2]).copied().unwrap_or(0)),
+            Some(&3) => Self::Not(Box::arbitrary(&mut quickcheck::Gen::new(
+                size.saturating_sub(1),
+            ))),
+            Some(&4) => Self::And(Box::arbitrary(&mut smaller), Box::arbitrary(&mut smaller)),
+            Some(&5) => Self::Or(Box::arbitrary(&mut smaller), Box::arbitrary(&mut smaller)),
+            Some(_) => Self::Implies(Box::arbitrary(&mut smaller), Box::arbitrary(&mut smaller)),
+        }
+    }
+    #[inline]
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        match *self {
+            Self::Bottom => Box::new(core::iter::empty()),
+            Self::Top => Box::new(core::iter::once(Self::Bottom)),
+            Self::Atom(_) => Box::new([Self::Bottom, Self::Top].into_iter()),
+            Self::Not(ref arg) => {
+                Box::new(core::iter::once(arg.as_ref().clone()).chain(arg.shrink().map(Self::Not)))
+            }
+            Self::And(ref lhs, ref rhs) => Box::new(
+                [lhs.as_ref().clone(), rhs.as_ref().clone()]
+                    .into_iter()
+                    .chain(
+                        (lhs.clone(), rhs.clone())
+                            .shrink()
+                            .map(|(l, r)| Self::And(l, r)),
+                    ),
+            ),
+            Self::Or(ref lhs, ref rhs) => Box::new(
+                [lhs.as_ref().clone(), rhs.as_ref().clone()]
+                    .into_iter()
+                    .chain(
+                        (lhs.clone(), rhs.clone())
+                            .shrink()
+                            .map(|(l, r)| Self::Or(l, r)),
+                    ),
+            ),
+            Self::Implies(ref lhs, ref rhs) => Box::new(
+                [lhs.as_ref().clone(), rhs.as_ref().clone()]
+                    .into_iter()
+                    .chain(
+                        (lhs.clone(), rhs.clone())
+                            .shrink()
+                            .map(|(l, r)| Self::Implies(l, r)),
+                    ),
+            ),
+        }
+    }
+}
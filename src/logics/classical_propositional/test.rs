@@ -0,0 +1,160 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+#![allow(
+    clippy::iter_on_single_items,
+    clippy::non_ascii_literal,
+    clippy::unwrap_used,
+    unused_results
+)]
+
+use super::*;
+use crate::{prelude::*, ProverState};
+
+#[cfg(feature = "quickcheck")]
+quickcheck::quickcheck! {
+    /// A formula has a proof exactly when its truth table says it's valid,
+    /// which checks the whole engine for soundness and completeness at once
+    /// (skipping large formulas to keep each search short).
+    fn provable_iff_tautology(ast: Ast) -> quickcheck::TestResult {
+        if ast.to_string().len() > 48 {
+            return quickcheck::TestResult::discard();
+        }
+        quickcheck::TestResult::from_bool(ast.clone().prove().is_ok() == ast.is_tautology())
+    }
+}
+
+/// Whether `other` has at least one copy of everything in `proven`,
+/// in which case proving `proven` proves `other` too, since G3cp admits weakening and contraction.
+fn weakens_to(proven: &LkWithExchange<Ast>, other: &LkWithExchange<Ast>) -> bool {
+    proven.lhs.keys().all(|item| other.lhs.contains(item))
+        && proven.rhs.keys().all(|item| other.rhs.contains(item))
+}
+
+#[test]
+fn prove_peirces_law() {
+    let (a, b) = (Ast::Atom(0), Ast::Atom(1));
+    a.clone()
+        .implies(b)
+        .implies(a.clone())
+        .implies(a)
+        .prove()
+        .unwrap();
+}
+
+#[test]
+fn prove_excluded_middle() {
+    let a = Ast::Atom(0);
+    let proof = (a.clone() | !a).prove().unwrap();
+    assert_eq!(proof.rule, "\u{2228}R");
+}
+
+#[test]
+fn prove_double_negation_elimination() {
+    let a = Ast::Atom(0);
+    (!!a.clone()).implies(a).prove().unwrap();
+}
+
+#[test]
+fn prove_de_morgan() {
+    let (a, b) = (Ast::Atom(0), Ast::Atom(1));
+    (!(a.clone() & b.clone())).implies(!a | !b).prove().unwrap();
+}
+
+#[test]
+fn cant_prove_an_atom() {
+    assert_eq!(Ast::Atom(0).prove(), Err(Error::RanOutOfPaths));
+}
+
+#[test]
+fn cant_prove_converse_implication() {
+    let (a, b) = (Ast::Atom(0), Ast::Atom(1));
+    assert_eq!(
+        a.clone().implies(b.clone()).implies(b.implies(a)).prove(),
+        Err(Error::RanOutOfPaths),
+    );
+}
+
+#[test]
+fn cant_prove_bottom() {
+    assert_eq!(Ast::Bottom.prove(), Err(Error::RanOutOfPaths));
+}
+
+#[test]
+fn bottom_on_the_left_proves_anything() {
+    Ast::Bottom.implies(Ast::Atom(0)).prove().unwrap();
+}
+
+#[test]
+fn sequents_display_both_sides() {
+    let (a, b) = (Ast::Atom(0), Ast::Atom(1));
+    let sequent = LkWithExchange::<Ast>::default()
+        .with_lhs([a.clone(), b.clone()])
+        .with_rhs([a]);
+    assert_eq!(sequent.to_string(), "p0, p1 \u{22a2} p0");
+    assert_eq!(
+        LkWithExchange::<Ast>::default().with_rhs([b]).to_string(),
+        "\u{22a2} p1"
+    );
+}
+
+#[test]
+fn subsumption_skips_rederiving_with_more_context() {
+    let (a, b) = (Ast::Atom(0), Ast::Atom(1));
+    let lemma = (a.clone() & b.clone()).implies(b & a);
+    let padded = Ast::Atom(2).implies(Ast::Atom(3).implies(lemma.clone()));
+    let mut plain = ProverState::new();
+    let mut subsuming = ProverState::with_config(SearchConfig {
+        subsumes: Some(weakens_to),
+        ..SearchConfig::new()
+    });
+    for state in [&mut plain, &mut subsuming] {
+        state.prove(Sided::right(lemma.clone())).unwrap();
+        let proof = state.prove(Sided::right(padded.clone())).unwrap();
+        assert_eq!(
+            proof.below,
+            LkWithExchange::default().with_rhs([padded.clone()])
+        );
+    }
+    assert_eq!(plain.stats().subsumed, 0);
+    assert!(subsuming.stats().subsumed > 0);
+    assert!(
+        subsuming.stats().dequeued < plain.stats().dequeued,
+        "explored {} sequents with subsumption and {} without",
+        subsuming.stats().dequeued,
+        plain.stats().dequeued,
+    );
+}
+
+#[test]
+fn prove_top() {
+    let proof = Ast::Top.prove().unwrap();
+    assert_eq!(proof.rule, "\u{22a4}R");
+    Ast::Top.implies(Ast::Atom(0)).prove().unwrap_err();
+    Ast::Top
+        .implies(Ast::Atom(0).implies(Ast::Atom(0)))
+        .prove()
+        .unwrap();
+}
+
+#[test]
+fn truth_tables() {
+    let (a, b) = (Ast::Atom(0), Ast::Atom(1));
+    let only_a: BTreeSet<usize> = [0].into_iter().collect();
+    assert!(a.eval(&only_a));
+    assert!(!b.eval(&only_a));
+    assert!(!a.clone().implies(b.clone()).eval(&only_a));
+    assert!(b.clone().implies(a.clone()).eval(&only_a));
+    assert!((a.clone() | !a.clone()).is_tautology());
+    assert!(!(a.clone() & !a.clone()).is_tautology());
+    assert!(!a
+        .clone()
+        .implies(b.clone())
+        .implies(b.implies(a))
+        .is_tautology());
+    assert!(Ast::Top.is_tautology());
+    assert!(!Ast::Bottom.is_tautology());
+}
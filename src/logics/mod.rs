@@ -0,0 +1,13 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Ready-made logics, each behind a feature of its own, for anyone who'd rather not write one.
+
+#[cfg(feature = "linear-logic")]
+pub mod classical_linear;
+
+#[cfg(feature = "propositional-logic")]
+pub mod classical_propositional;
@@ -6,19 +6,29 @@
 
 //! Unordered collection of (potentially many of the same) elements.
 
+use alloc::{
+    collections::{btree_map::IntoIter, BTreeMap},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
 use core::num::NonZeroUsize;
-use std::collections::{btree_map::IntoIter, BTreeMap};
 
 /// Unordered collection of (potentially many of the same) elements.
-#[repr(transparent)]
+/// Clones share one map until either is changed, so cloning a multiset is cheap.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
-pub struct Multiset<T: Ord>(pub(crate) BTreeMap<T, NonZeroUsize>);
+pub struct Multiset<T: Ord> {
+    /// Whole number of elements, counting all duplicates.
+    /// Always the sum of the counts in `map`, so comparing it first only ever saves time.
+    len: usize,
+    /// How many copies of each distinct element we have.
+    pub(crate) map: Arc<BTreeMap<T, NonZeroUsize>>,
+}
 
 impl<T: Ord> Default for Multiset<T> {
     #[inline]
     fn default() -> Self {
-        #[allow(clippy::default_trait_access)]
-        Self(Default::default())
+        Self::new()
     }
 }
 
@@ -34,12 +44,12 @@ impl<T: Ord> Ord for Multiset<T> {
     fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         match self.len().cmp(&other.len()) {
             diff @ (core::cmp::Ordering::Less | core::cmp::Ordering::Greater) => diff,
-            core::cmp::Ordering::Equal => self.0.cmp(&other.0),
+            core::cmp::Ordering::Equal => self.map.cmp(&other.map),
         }
     }
 }
 
-impl<T: Ord> FromIterator<T> for Multiset<T> {
+impl<T: Clone + Ord> FromIterator<T> for Multiset<T> {
     #[inline(always)]
     #[allow(unsafe_code)]
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
@@ -51,14 +61,56 @@ impl<T: Ord> FromIterator<T> for Multiset<T> {
     }
 }
 
+/// Each pair is an element and how many copies of it to add,
+/// summing counts for any element listed more than once.
+impl<T: Clone + Ord> FromIterator<(T, NonZeroUsize)> for Multiset<T> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = (T, NonZeroUsize)>>(iter: I) -> Self {
+        let mut ms = Self::new();
+        for (element, n) in iter {
+            let _ = ms.insert_n(element, n);
+        }
+        ms
+    }
+}
+
+impl<T: Clone + Ord> Extend<T> for Multiset<T> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            let _ = self.insert(element);
+        }
+    }
+}
+
+/// Counts are already nonzero, so the map is used as is.
+impl<T: Ord> From<BTreeMap<T, NonZeroUsize>> for Multiset<T> {
+    #[inline(always)]
+    fn from(map: BTreeMap<T, NonZeroUsize>) -> Self {
+        Self::from_map(map)
+    }
+}
+
 impl<T: core::fmt::Display + Ord> core::fmt::Display for Multiset<T> {
+    /// Write duplicates with a multiplicity prefix, e.g. `{ 2×A, B }`,
+    /// or, in the alternate form (`{:#}`), write each copy separately, e.g. `{ A, A, B }`.
     #[inline]
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{{ ")?;
+        write!(f, "{{")?;
+        let mut first = true;
         for (element, count) in self.iter_unique() {
-            write!(f, "{count}x {element}")?;
+            let copies = if f.alternate() { count.get() } else { 1 };
+            for _ in 0..copies {
+                write!(f, "{} ", if first { "" } else { "," })?;
+                first = false;
+                if count.get() == 1 || f.alternate() {
+                    write!(f, "{element}")?;
+                } else {
+                    write!(f, "{count}\u{d7}{element}")?;
+                }
+            }
         }
-        write!(f, "}}")
+        write!(f, " }}")
     }
 }
 
@@ -66,55 +118,57 @@ impl<T: Ord> Multiset<T> {
     /// Empty multiset.
     #[must_use]
     #[inline(always)]
-    pub const fn new() -> Self {
-        Self(BTreeMap::new())
+    pub fn new() -> Self {
+        Self {
+            len: 0,
+            map: Arc::new(BTreeMap::new()),
+        }
     }
 
-    /// Add an element to the set, even if it's a duplicate. Return how many there _now_ are.
+    /// Wrap a map, adding up its counts once and for all.
     /// # Panics
     /// If we overflow a `usize` (many other things, including maybe your death, will happen first).
     #[inline]
-    #[allow(unsafe_code)]
-    pub fn insert(&mut self, element: T) -> NonZeroUsize {
-        *self
-            .0
-            .entry(element)
-            .and_modify(|i| *i = i.checked_add(1).expect("Ridiculously huge value"))
-            // SAFETY: Always 1, which is nonzero.
-            .or_insert(unsafe { NonZeroUsize::new_unchecked(1) })
+    fn from_map(map: BTreeMap<T, NonZeroUsize>) -> Self {
+        Self {
+            len: map.values().fold(0, |acc: usize, i| {
+                acc.checked_add(i.get()).expect("Ridiculously huge value")
+            }),
+            map: Arc::new(map),
+        }
+    }
+
+    /// How many copies of an element we have (zero if it's absent).
+    #[must_use]
+    #[inline(always)]
+    pub fn count(&self, element: &T) -> usize {
+        self.map.get(element).map_or(0, |i| i.get())
+    }
+
+    /// Most copies of any one element (zero if empty).
+    #[must_use]
+    #[inline]
+    pub fn most_copies(&self) -> usize {
+        self.map.values().map(|n| n.get()).max().unwrap_or(0)
     }
 
     /// Look for an element, no matter how many, without changing anything.
     #[inline(always)]
     pub fn contains(&self, element: &T) -> bool {
-        self.0.contains_key(element)
+        self.map.contains_key(element)
     }
 
-    /// Take an element by decreasing its count if we can.
+    /// Whether every element satisfies a predicate (vacuously true if empty).
     #[inline]
-    pub fn take(&mut self, element: &T) -> bool {
-        match self.0.get_mut(element) {
-            Some(i) => {
-                if let Some(decr) = NonZeroUsize::new(i.get().overflowing_sub(1).0) {
-                    *i = decr;
-                    return true;
-                }
-            }
-            None => return false,
-        }
-        let _ = self.0.remove(element);
-        true
+    pub fn all<F: FnMut(&T) -> bool>(&self, f: F) -> bool {
+        self.map.keys().all(f)
     }
 
     /// Whole number of elements, counting all duplicates.
-    /// # Panics
-    /// If we overflow a `usize` (many other things, including maybe your death, will happen first).
-    #[inline]
+    #[inline(always)]
     #[must_use]
-    pub fn len(&self) -> usize {
-        self.0.values().fold(0, |acc, i| {
-            acc.checked_add(i.get()).expect("Ridiculously huge value")
-        })
+    pub const fn len(&self) -> usize {
+        self.len
     }
 
     /// View an arbitrary element without taking it out.
@@ -124,59 +178,181 @@ impl<T: Ord> Multiset<T> {
         self.iter_repeat().next()
     }
 
+    /// If this collection has exactly `N` elements (counting duplicates),
+    /// view them all in order without taking them out.
+    #[must_use]
+    #[inline]
+    pub fn exactly<const N: usize>(&self) -> Option<[&T; N]> {
+        if self.len != N {
+            return None;
+        }
+        let Some(first) = self.any_element() else {
+            // Empty, so `N` is zero.
+            return <[&T; N]>::try_from(&[][..]).ok();
+        };
+        let mut array = [first; N];
+        for (slot, element) in array.iter_mut().zip(self.iter_repeat()) {
+            *slot = element;
+        }
+        Some(array)
+    }
+
     /// If this collection has exactly one element, view it without taking it out.
     #[must_use]
     #[inline(always)]
     pub fn only(&self) -> Option<&T> {
-        let mut iter = self.iter_repeat();
-        let maybe = iter.next();
-        if iter.next().is_none() {
-            maybe
-        } else {
-            None
-        }
+        self.exactly().map(|[only]| only)
     }
 
     /// If this collection has exactly two elements, view them without taking them out.
     #[must_use]
     #[inline(always)]
     pub fn pair(&self) -> Option<(&T, &T)> {
-        let mut iter = self.iter_repeat();
-        iter.next().and_then(|first| {
-            iter.next()
-                .and_then(|second| iter.next().is_none().then_some((first, second)))
-        })
+        self.exactly().map(<(&T, &T)>::from)
+    }
+
+    /// How many copies of each distinct element we have.
+    #[must_use]
+    #[inline(always)]
+    pub fn counts(&self) -> &BTreeMap<T, NonZeroUsize> {
+        &self.map
     }
 
     /// Iterate over elements without copying them, visiting duplicate elements only once.
     #[inline]
-    pub fn iter_unique(&self) -> std::collections::btree_map::Iter<'_, T, NonZeroUsize> {
-        self.0.iter()
+    pub fn iter_unique(&self) -> alloc::collections::btree_map::Iter<'_, T, NonZeroUsize> {
+        self.map.iter()
     }
 
-    /// Iterate over elements, visiting duplicate elements only once.
+    /// Iterate over distinct elements without copying them, ignoring how many copies of each we have.
     #[inline]
-    pub fn into_iter_unique(self) -> std::collections::btree_map::IntoKeys<T, NonZeroUsize> {
-        self.0.into_keys()
+    pub fn keys(&self) -> alloc::collections::btree_map::Keys<'_, T, NonZeroUsize> {
+        self.map.keys()
+    }
+
+    /// Iterate over elements without copying them, visiting duplicate elements more than once
+    /// (the same as `iter_repeat`, named like the standard collections).
+    #[must_use]
+    #[inline(always)]
+    pub fn iter(&self) -> IterRepeat<'_, T> {
+        self.iter_repeat()
     }
 
     /// Iterate over elements without copying them, visiting duplicate elements more than once.
+    #[must_use]
     #[inline]
-    pub fn iter_repeat(&self) -> impl Iterator<Item = &T> {
-        self.0
-            .iter()
-            .flat_map(|(t, i)| core::iter::repeat(t).take(i.get()))
+    pub fn iter_repeat(&self) -> IterRepeat<'_, T> {
+        IterRepeat {
+            unique: self.map.iter(),
+            current: None,
+            remaining: self.len,
+        }
     }
 
     /// Whether there are any elements.
     #[must_use]
     #[inline(always)]
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.map.is_empty()
     }
 }
 
 impl<T: Clone + Ord> Multiset<T> {
+    /// The underlying map, copied first if any other multiset shares it.
+    #[inline(always)]
+    fn map_mut(&mut self) -> &mut BTreeMap<T, NonZeroUsize> {
+        Arc::make_mut(&mut self.map)
+    }
+
+    /// The underlying map, copied only if any other multiset shares it.
+    #[inline(always)]
+    fn into_map(self) -> BTreeMap<T, NonZeroUsize> {
+        Arc::unwrap_or_clone(self.map)
+    }
+
+    /// Add an element to the set, even if it's a duplicate. Return how many there _now_ are.
+    /// # Panics
+    /// If we overflow a `usize` (many other things, including maybe your death, will happen first).
+    #[inline]
+    #[allow(unsafe_code)]
+    pub fn insert(&mut self, element: T) -> NonZeroUsize {
+        self.len = self.len.checked_add(1).expect("Ridiculously huge value");
+        *self
+            .map_mut()
+            .entry(element)
+            .and_modify(|i| *i = i.checked_add(1).expect("Ridiculously huge value"))
+            // SAFETY: Always 1, which is nonzero.
+            .or_insert(unsafe { NonZeroUsize::new_unchecked(1) })
+    }
+
+    /// Add `n` copies of an element to the set at once. Return how many there _now_ are.
+    /// # Panics
+    /// If we overflow a `usize` (many other things, including maybe your death, will happen first).
+    #[inline]
+    pub fn insert_n(&mut self, element: T, n: NonZeroUsize) -> NonZeroUsize {
+        self.len = self
+            .len
+            .checked_add(n.get())
+            .expect("Ridiculously huge value");
+        *self
+            .map_mut()
+            .entry(element)
+            .and_modify(|i| *i = i.checked_add(n.get()).expect("Ridiculously huge value"))
+            .or_insert(n)
+    }
+
+    /// Take an element by decreasing its count if we can.
+    #[inline]
+    pub fn take(&mut self, element: &T) -> bool {
+        if !self.contains(element) {
+            return false;
+        }
+        self.len = self.len.saturating_sub(1);
+        if let Some(i) = self.map_mut().get_mut(element) {
+            if let Some(decr) = NonZeroUsize::new(i.get().overflowing_sub(1).0) {
+                *i = decr;
+                return true;
+            }
+        }
+        let _ = self.map_mut().remove(element);
+        true
+    }
+
+    /// Remove every copy of an element. Return how many copies were removed.
+    #[inline]
+    pub fn remove_all(&mut self, element: &T) -> usize {
+        let removed = self.map_mut().remove(element).map_or(0, NonZeroUsize::get);
+        self.len = self.len.saturating_sub(removed);
+        removed
+    }
+
+    /// Keep only elements satisfying a predicate, removing every copy of the rest.
+    #[inline]
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        let mut removed: usize = 0;
+        self.map_mut().retain(|element, i| {
+            let keep = f(element);
+            if !keep {
+                removed = removed.saturating_add(i.get());
+            }
+            keep
+        });
+        self.len = self.len.saturating_sub(removed);
+    }
+
+    /// How many copies of each distinct element we have, copied only if any other multiset shares them.
+    #[must_use]
+    #[inline(always)]
+    pub fn into_counts(self) -> BTreeMap<T, NonZeroUsize> {
+        self.into_map()
+    }
+
+    /// Iterate over elements, visiting duplicate elements only once.
+    #[inline]
+    pub fn into_iter_unique(self) -> alloc::collections::btree_map::IntoKeys<T, NonZeroUsize> {
+        self.into_map().into_keys()
+    }
+
     /// Clone and insert an element into the clone.
     #[inline]
     #[must_use]
@@ -188,20 +364,204 @@ impl<T: Clone + Ord> Multiset<T> {
         ms
     }
 
+    /// Add every element of another multiset into this one, summing multiplicities.
+    /// # Panics
+    /// If we overflow a `usize` (many other things, including maybe your death, will happen first).
+    #[inline]
+    pub fn extend_from(&mut self, other: &Self) {
+        for (element, &n) in other.iter_unique() {
+            let _ = self.insert_n(element.clone(), n);
+        }
+    }
+
+    /// Every element of either multiset, summing multiplicities.
+    /// # Panics
+    /// If we overflow a `usize` (many other things, including maybe your death, will happen first).
+    #[inline]
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        let mut ms = self.clone();
+        ms.extend_from(other);
+        ms
+    }
+
+    /// Elements in both multisets, each as many times as the smaller of its multiplicities.
+    #[inline]
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self::from_map(
+            self.map
+                .iter()
+                .filter_map(|(element, &i)| {
+                    other.map.get(element).map(|&j| (element.clone(), i.min(j)))
+                })
+                .collect(),
+        )
+    }
+
+    /// Elements of this multiset with as many copies removed as `other` has (stopping at zero).
+    #[inline]
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        Self::from_map(
+            self.map
+                .iter()
+                .filter_map(|(element, i)| {
+                    NonZeroUsize::new(i.get().saturating_sub(other.count(element)))
+                        .map(|n| (element.clone(), n))
+                })
+                .collect(),
+        )
+    }
+
+    /// Every way to split this multiset in two, as ordered `(left, right)` pairs,
+    /// each distinct split exactly once (iterating multiplicities per element,
+    /// so duplicates don't produce the same split twice).
+    #[inline]
+    pub fn partitions(&self) -> impl Iterator<Item = (Self, Self)> + '_ {
+        Partitions::new(self, false)
+    }
+
+    /// Like `partitions`, but treating `(left, right)` and `(right, left)` as the same split,
+    /// so each unordered pair is yielded exactly once.
+    #[inline]
+    pub fn unordered_partitions(&self) -> impl Iterator<Item = (Self, Self)> + '_ {
+        Partitions::new(self, true)
+    }
+
     /// Iterate over elements, visiting duplicate elements more than once.
     #[inline]
     pub fn into_iter_repeat(self) -> IntoIterRepeat<T> {
-        self.0
+        self.into_map()
             .into_iter()
-            .flat_map(|(t, i)| core::iter::repeat(t).take(i.get()))
+            .flat_map(|(t, i)| core::iter::repeat_n(t, i.get()))
+    }
+}
+
+/// Output of `Multiset::partitions` and `Multiset::unordered_partitions`.
+#[derive(Debug)]
+struct Partitions<'a, T: Ord> {
+    /// Each unique element and how many copies of it there are overall.
+    elements: Vec<(&'a T, NonZeroUsize)>,
+    /// How many copies of each element go on the left; `None` once we've seen every split.
+    counter: Option<Vec<usize>>,
+    /// Whether to skip the mirror image of a split we've already yielded.
+    unordered: bool,
+}
+
+impl<'a, T: Ord> Partitions<'a, T> {
+    /// Start with everything on the right.
+    #[inline]
+    fn new(ms: &'a Multiset<T>, unordered: bool) -> Self {
+        let elements: Vec<_> = ms.map.iter().map(|(t, &i)| (t, i)).collect();
+        Self {
+            counter: Some(vec![0; elements.len()]),
+            elements,
+            unordered,
+        }
+    }
+
+    /// Whether the current split comes no later than its mirror image.
+    #[inline]
+    fn canonical(&self, counter: &[usize]) -> bool {
+        counter
+            .iter()
+            .zip(&self.elements)
+            .map(|(&left, &(_, n))| left.cmp(&n.get().saturating_sub(left)))
+            .find(|&ord| ord != core::cmp::Ordering::Equal)
+            .is_none_or(|ord| ord == core::cmp::Ordering::Less)
+    }
+
+    /// Move on to the next split (like an odometer), or mark that we're finished.
+    #[inline]
+    fn advance(&mut self) {
+        let Some(ref mut counter) = self.counter else {
+            return;
+        };
+        for (left, &(_, n)) in counter.iter_mut().zip(&self.elements) {
+            if *left < n.get() {
+                *left = left.saturating_add(1);
+                return;
+            }
+            *left = 0;
+        }
+        self.counter = None;
+    }
+}
+
+impl<T: Clone + Ord> Iterator for Partitions<'_, T> {
+    type Item = (Multiset<T>, Multiset<T>);
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let counter = self.counter.clone()?;
+            self.advance();
+            if self.unordered && !self.canonical(&counter) {
+                continue;
+            }
+            let (mut lhs, mut rhs) = (Multiset::new(), Multiset::new());
+            for (&left, &(t, n)) in counter.iter().zip(&self.elements) {
+                if let Some(l) = NonZeroUsize::new(left) {
+                    let _ = lhs.insert_n(t.clone(), l);
+                }
+                if let Some(r) = NonZeroUsize::new(n.get().saturating_sub(left)) {
+                    let _ = rhs.insert_n(t.clone(), r);
+                }
+            }
+            return Some((lhs, rhs));
+        }
+    }
+}
+
+/// Output of `Multiset::iter_repeat`, also used to iterate over `&Multiset`.
+#[derive(Clone, Debug)]
+pub struct IterRepeat<'a, T> {
+    /// Distinct elements we haven't started on yet.
+    unique: alloc::collections::btree_map::Iter<'a, T, NonZeroUsize>,
+    /// The element we're on and how many more copies of it to visit.
+    current: Option<(&'a T, usize)>,
+    /// How many elements are left altogether, counting duplicates.
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for IterRepeat<'a, T> {
+    type Item = &'a T;
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((element, ref mut copies @ 1..)) = self.current {
+                *copies = copies.saturating_sub(1);
+                self.remaining = self.remaining.saturating_sub(1);
+                return Some(element);
+            }
+            let (element, n) = self.unique.next()?;
+            self.current = Some((element, n.get()));
+        }
+    }
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for IterRepeat<'_, T> {}
+
+impl<T> core::iter::FusedIterator for IterRepeat<'_, T> {}
+
+impl<'a, T: Ord> IntoIterator for &'a Multiset<T> {
+    type Item = &'a T;
+    type IntoIter = IterRepeat<'a, T>;
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_repeat()
     }
 }
 
 /// Output of `Multiset::into_iter_repeat`.
 type IntoIterRepeat<T> = core::iter::FlatMap<
     IntoIter<T, NonZeroUsize>,
-    core::iter::Take<core::iter::Repeat<T>>,
-    fn((T, NonZeroUsize)) -> core::iter::Take<core::iter::Repeat<T>>,
+    core::iter::RepeatN<T>,
+    fn((T, NonZeroUsize)) -> core::iter::RepeatN<T>,
 >;
 
 impl<T: Clone + Ord> IntoIterator for Multiset<T> {
@@ -213,11 +573,36 @@ impl<T: Clone + Ord> IntoIterator for Multiset<T> {
     }
 }
 
+/// Serialized as a list of `[element, count]` pairs, one per distinct element,
+/// since formats like JSON only allow strings as map keys.
+#[cfg(feature = "serde")]
+impl<T: Ord + serde::Serialize> serde::Serialize for Multiset<T> {
+    #[inline]
+    fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.collect_seq(self.map.iter())
+    }
+}
+
+/// Deserialized from a list of `[element, count]` pairs, rejecting zero counts
+/// and adding up the counts of any element listed more than once.
+#[cfg(feature = "serde")]
+impl<'de, T: Clone + Ord + serde::Deserialize<'de>> serde::Deserialize<'de> for Multiset<T> {
+    #[inline]
+    fn deserialize<De: serde::Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        let pairs = <Vec<(T, NonZeroUsize)> as serde::Deserialize>::deserialize(deserializer)?;
+        let mut ms = Self::new();
+        for (element, count) in pairs {
+            let _ = ms.insert_n(element, count);
+        }
+        Ok(ms)
+    }
+}
+
 #[cfg(feature = "quickcheck")]
 impl<T: quickcheck::Arbitrary + Ord> quickcheck::Arbitrary for Multiset<T> {
     #[inline]
     fn arbitrary(g: &mut quickcheck::Gen) -> Self {
-        Self::from_iter(Vec::arbitrary(g))
+        Self::from_iter(Vec::<T>::arbitrary(g))
     }
     #[inline]
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
@@ -230,3 +615,22 @@ impl<T: quickcheck::Arbitrary + Ord> quickcheck::Arbitrary for Multiset<T> {
         )
     }
 }
+
+#[cfg(feature = "proptest")]
+impl<T: proptest::arbitrary::Arbitrary + Clone + Ord> proptest::arbitrary::Arbitrary
+    for Multiset<T>
+{
+    type Parameters = <Vec<T> as proptest::arbitrary::Arbitrary>::Parameters;
+    type Strategy = proptest::strategy::Map<
+        <Vec<T> as proptest::arbitrary::Arbitrary>::Strategy,
+        fn(Vec<T>) -> Self,
+    >;
+    /// Shrinks like the underlying `Vec`, i.e. by removing and shrinking elements.
+    #[inline]
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        proptest::strategy::Strategy::prop_map(
+            <Vec<T> as proptest::arbitrary::Arbitrary>::arbitrary_with(args),
+            Self::from_iter,
+        )
+    }
+}
@@ -0,0 +1,78 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Hooks into the proof-search loop, e.g. for progress bars or traces.
+
+use crate::{Rule, Sequent};
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use crate::Progress;
+
+/// Hooks into the proof-search loop, e.g. for progress bars or traces.
+/// Every method does nothing by default, so implement only what you need.
+pub trait SearchObserver<S: Sequent> {
+    /// We just took this sequent out of the queue and are about to expand it.
+    #[inline(always)]
+    fn on_dequeue(&mut self, _sequent: &S) {}
+    /// We found an inference whose conclusion is `below` and set it aside until its premises are proven.
+    #[inline(always)]
+    fn on_pause(&mut self, _below: &S, _rule: &Rule<S>) {}
+    /// We proved this sequent with this rule.
+    #[inline(always)]
+    fn on_prove(&mut self, _sequent: &S, _rule: &Rule<S>) {}
+    /// We just took a sequent out of the queue, and this is where the search stands.
+    #[inline(always)]
+    #[cfg(feature = "std")]
+    fn on_progress(&mut self, _progress: &Progress) {}
+}
+
+/// Observe nothing.
+impl<S: Sequent> SearchObserver<S> for () {}
+
+/// Something that happened during proof search.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SearchEvent<S: Sequent> {
+    /// We took this sequent out of the queue.
+    Dequeued(S),
+    /// We set aside an inference until its premises are proven.
+    Paused {
+        /// Conclusion of the inference.
+        below: S,
+        /// Rule that would prove it.
+        rule: Rule<S>,
+    },
+    /// We proved a sequent.
+    Proved {
+        /// What we proved.
+        sequent: S,
+        /// How we proved it.
+        rule: Rule<S>,
+    },
+}
+
+/// Record every event in order.
+impl<S: Sequent> SearchObserver<S> for Vec<SearchEvent<S>> {
+    #[inline]
+    fn on_dequeue(&mut self, sequent: &S) {
+        self.push(SearchEvent::Dequeued(sequent.clone()));
+    }
+    #[inline]
+    fn on_pause(&mut self, below: &S, rule: &Rule<S>) {
+        self.push(SearchEvent::Paused {
+            below: below.clone(),
+            rule: rule.clone(),
+        });
+    }
+    #[inline]
+    fn on_prove(&mut self, sequent: &S, rule: &Rule<S>) {
+        self.push(SearchEvent::Proved {
+            sequent: sequent.clone(),
+            rule: rule.clone(),
+        });
+    }
+}
@@ -0,0 +1,102 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Everything that changes when the `parallel` feature expands sequents on several threads at once.
+
+use crate::{Infer as _, Rule, Sequent};
+use alloc::{vec, vec::Vec};
+
+/// `Send + Sync` with the `parallel` feature, or nothing at all without it,
+/// so single-threaded searches never have to pay for thread safety.
+#[cfg(feature = "parallel")]
+pub trait MaybeSendSync: Send + Sync {}
+
+#[cfg(feature = "parallel")]
+impl<T: ?Sized + Send + Sync> MaybeSendSync for T {}
+
+/// `Send + Sync` with the `parallel` feature, or nothing at all without it,
+/// so single-threaded searches never have to pay for thread safety.
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSendSync {}
+
+#[cfg(not(feature = "parallel"))]
+impl<T: ?Sized> MaybeSendSync for T {}
+
+/// Reference-counted pointer, atomic only if other threads might see it.
+#[cfg(feature = "parallel")]
+pub(crate) type Shared<T> = alloc::sync::Arc<T>;
+
+/// Reference-counted pointer, atomic only if other threads might see it.
+#[cfg(not(feature = "parallel"))]
+pub(crate) type Shared<T> = alloc::rc::Rc<T>;
+
+/// Every inference `item` offers with `context` everywhere else, each marked as acting on `item`.
+#[inline]
+pub(crate) fn acting_on<S: Sequent>(item: &S::Item, context: S) -> Vec<Rule<S>> {
+    let mut rules = item.above(context);
+    for rule in &mut rules {
+        let _ = rule.principal.get_or_insert_with(|| item.clone());
+    }
+    rules
+}
+
+/// The one inference that closes this sequent outright, if it's initial (see `Sequent::closed`).
+#[inline]
+fn initial<S: Sequent>(sequent: &S) -> Option<Rule<S>> {
+    sequent.closed().map(|name| Rule::invertible(name, []))
+}
+
+/// Fewest items in a sequent worth handing to the thread pool:
+/// below this, expanding them one after another beats waking other threads.
+#[cfg(feature = "parallel")]
+pub(crate) const PARALLEL_MIN_ITEMS: usize = 8;
+
+/// Every inference that could conclude this sequent, one item at a time
+/// (or, with the `parallel` feature, splitting large sequents' items among a pool of threads),
+/// or just the one that closes it if it's initial.
+#[inline]
+pub(crate) fn expand<S: Sequent>(sequent: &S) -> Vec<Rule<S>> {
+    if let Some(rule) = initial(sequent) {
+        return vec![rule];
+    }
+    let mut rules = acting_on_each(sequent);
+    rules.extend(sequent.structural());
+    rules
+}
+
+/// Every inference each of these items offers with its context, one item at a time.
+#[inline]
+pub(crate) fn acting_on_each_serially<S: Sequent, I: IntoIterator<Item = (S::Item, S)>>(
+    samples: I,
+) -> Vec<Rule<S>> {
+    samples
+        .into_iter()
+        .flat_map(|(item, context)| acting_on(&item, context))
+        .collect()
+}
+
+/// Every inference each item in this sequent offers, one item at a time.
+#[inline]
+#[cfg(not(feature = "parallel"))]
+pub(crate) fn acting_on_each<S: Sequent>(sequent: &S) -> Vec<Rule<S>> {
+    acting_on_each_serially(sequent.sample())
+}
+
+/// Every inference each item in this sequent offers, in the same order as one at a time,
+/// but on rayon's thread pool if it has more than one thread and there are at least `PARALLEL_MIN_ITEMS` items.
+#[inline]
+#[cfg(feature = "parallel")]
+pub(crate) fn acting_on_each<S: Sequent>(sequent: &S) -> Vec<Rule<S>> {
+    use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
+    let samples: Vec<_> = sequent.sample().collect();
+    if samples.len() < PARALLEL_MIN_ITEMS || rayon::current_num_threads() <= 1 {
+        return acting_on_each_serially(samples);
+    }
+    samples
+        .into_par_iter()
+        .flat_map_iter(|(item, context)| acting_on(&item, context))
+        .collect()
+}
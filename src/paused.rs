@@ -0,0 +1,150 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Inferences waiting on proofs of the sequents above them.
+
+use crate::{
+    hash::{Entry, HashMap, HashSet, RandomState},
+    inference::Inference,
+    parallel::Shared,
+    stats::bump,
+    thunk::Thunk,
+    Sequent,
+};
+use alloc::{collections::VecDeque, vec::Vec};
+use core::hash::BuildHasher;
+
+/// Index of a waiting inference in `Paused::waiting`.
+type InferenceId = usize;
+
+/// Inferences waiting on proofs of the sequents above them,
+/// indexed by the premises they're missing,
+/// so that proving a sequent only touches the inferences that actually need it.
+/// Hashed fields are only ever looked up; everything handed back comes out of `ready` in order,
+/// so search stays deterministic.
+#[derive(Clone, Debug)]
+pub(crate) struct Paused<S: Sequent, H = RandomState> {
+    /// Every inference not yet handed back by `next_ready`, so we never pause one twice at once.
+    pending: HashSet<Inference<S>, H>,
+    /// Each inference still missing premises, with how many distinct premises it's missing.
+    waiting: HashMap<InferenceId, (Inference<S>, usize), H>,
+    /// Each unproven premise and every inference waiting on it.
+    dependents: HashMap<S, Vec<InferenceId>, H>,
+    /// Each conclusion and every inference still waiting to prove it.
+    by_below: HashMap<Shared<S>, Vec<InferenceId>, H>,
+    /// Inferences whose premises are all proven, in the order they got that way.
+    ready: VecDeque<Inference<S>>,
+    /// Next unused `InferenceId`.
+    next_id: InferenceId,
+}
+
+impl<S: Sequent> Default for Paused<S> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Sequent> Paused<S> {
+    /// Nothing waiting.
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self::with_hasher(RandomState::default())
+    }
+}
+
+impl<S: Sequent, H: BuildHasher + Clone> Paused<S, H> {
+    /// Nothing waiting, hashing with `hasher`.
+    #[inline]
+    pub(crate) fn with_hasher(hasher: H) -> Self {
+        Self {
+            pending: HashSet::with_hasher(hasher.clone()),
+            waiting: HashMap::with_hasher(hasher.clone()),
+            dependents: HashMap::with_hasher(hasher.clone()),
+            by_below: HashMap::with_hasher(hasher),
+            ready: VecDeque::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Set an inference aside until its premises are proven,
+    /// or return `false` if an equal inference is already set aside.
+    #[inline]
+    pub(crate) fn pause(&mut self, inference: Inference<S>, thunk: &mut Thunk<S, H>) -> bool {
+        if !self.pending.insert(inference.clone()) {
+            return false;
+        }
+        let stats = thunk.stats_mut();
+        stats.peak_paused = stats.peak_paused.max(self.pending.len());
+        let mut missing: usize = 0;
+        let id = self.next_id;
+        for (sequent, _) in inference.rule.above.iter_unique() {
+            bump(&mut thunk.stats_mut().premise_checks);
+            if thunk.proven(sequent).is_none() {
+                missing = missing.saturating_add(1);
+                self.dependents.entry(sequent.clone()).or_default().push(id);
+            }
+        }
+        if missing == 0 {
+            self.ready.push_back(inference);
+        } else {
+            self.next_id = self.next_id.saturating_add(1);
+            self.by_below
+                .entry(Shared::clone(&inference.below))
+                .or_default()
+                .push(id);
+            drop(self.waiting.insert(id, (inference, missing)));
+        }
+        true
+    }
+
+    /// Note that this sequent is now proven, readying every inference that was waiting only on it.
+    #[inline]
+    pub(crate) fn notify(&mut self, proven: &S, thunk: &mut Thunk<S, H>) {
+        let Some(ids) = self.dependents.remove(proven) else {
+            return;
+        };
+        for id in ids {
+            bump(&mut thunk.stats_mut().premise_checks);
+            if let Entry::Occupied(mut entry) = self.waiting.entry(id) {
+                let missing = &mut entry.get_mut().1;
+                *missing = missing.saturating_sub(1);
+                if *missing == 0 {
+                    self.ready.push_back(entry.remove().0);
+                }
+            }
+        }
+    }
+
+    /// Forget every inference still waiting to prove this sequent, since it's now proven.
+    #[inline]
+    pub(crate) fn prune(&mut self, proven: &S, thunk: &mut Thunk<S, H>) {
+        let Some(ids) = self.by_below.remove(proven) else {
+            return;
+        };
+        for id in ids {
+            if let Some((inference, _)) = self.waiting.remove(&id) {
+                let _ = self.pending.remove(&inference);
+                bump(&mut thunk.stats_mut().inferences_pruned);
+            }
+        }
+    }
+
+    /// Take out an inference whose premises are all proven, if there is one.
+    #[inline]
+    pub(crate) fn next_ready(&mut self) -> Option<Inference<S>> {
+        let inference = self.ready.pop_front()?;
+        let _ = self.pending.remove(&inference);
+        Some(inference)
+    }
+
+    /// How many inferences are set aside right now.
+    #[inline(always)]
+    #[cfg(feature = "std")]
+    pub(crate) fn len(&self) -> usize {
+        self.pending.len()
+    }
+}
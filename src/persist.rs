@@ -0,0 +1,102 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Proven sequents saved by one run and checked again before another trusts them.
+
+use crate::{hash::HashSet, parallel::expand, thunk::WEAKENING, ProofCheckError, Rule, Sequent};
+use alloc::vec::Vec;
+
+/// Why a saved cache couldn't be written or read back.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum PersistError<S: Sequent> {
+    /// Couldn't write or read, or what we read wasn't a saved cache.
+    Format(serde_json::Error),
+    /// A saved inference doesn't follow from the rules.
+    Invalid(ProofCheckError<S>),
+    /// A saved sequent whose proof depends on something never proven, or on itself.
+    Unfounded(S),
+}
+
+impl<S: Sequent> core::fmt::Display for PersistError<S> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::Format(ref error) => write!(f, "Couldn't save or load proofs: {error}"),
+            Self::Invalid(ref error) => write!(f, "Saved proofs don't check: {error}"),
+            Self::Unfounded(ref sequent) => write!(
+                f,
+                "Saved proof of {sequent} depends on something never proven",
+            ),
+        }
+    }
+}
+
+impl<S: Sequent> core::error::Error for PersistError<S> {}
+
+impl<S: Sequent> From<serde_json::Error> for PersistError<S> {
+    #[inline(always)]
+    fn from(error: serde_json::Error) -> Self {
+        Self::Format(error)
+    }
+}
+
+/// Check that each saved inference follows from the rules
+/// (or, for weakening, from `subsumes` if we check subsumption at all)
+/// and that every premise is itself proven without going in circles,
+/// returning the same entries in an order that proves each premise before anything above it.
+/// # Errors
+/// At the first inference that doesn't follow, or at any sequent that never bottoms out.
+#[inline]
+pub(crate) fn validated<S: Sequent>(
+    entries: Vec<(S, Rule<S>)>,
+    subsumes: Option<fn(&S, &S) -> bool>,
+) -> Result<Vec<(S, Rule<S>)>, PersistError<S>> {
+    for &(ref sequent, ref proof) in &entries {
+        let follows = if proof.name == WEAKENING {
+            proof
+                .above
+                .only()
+                .is_some_and(|premise| subsumes.is_some_and(|check| check(premise, sequent)))
+        } else {
+            expand(sequent)
+                .iter()
+                .any(|rule| rule.name == proof.name && rule == proof)
+        };
+        if !follows {
+            return Err(PersistError::Invalid(ProofCheckError {
+                sequent: sequent.clone(),
+                rule: proof.name.clone(),
+                available: expand(sequent),
+            }));
+        }
+    }
+    // Accept, round after round, everything whose premises are all accepted;
+    // anything left once a round accepts nothing is missing a premise or proves itself.
+    let mut accepted: HashSet<S> = HashSet::new();
+    let mut ordered = Vec::with_capacity(entries.len());
+    let mut pending = entries;
+    loop {
+        let (ready, rest): (Vec<_>, Vec<_>) = pending.into_iter().partition(|&(_, ref proof)| {
+            proof
+                .above
+                .iter_unique()
+                .all(|(premise, _)| accepted.contains(premise))
+        });
+        pending = rest;
+        if ready.is_empty() {
+            break;
+        }
+        accepted.extend(ready.iter().map(|&(ref sequent, _)| sequent.clone()));
+        ordered.extend(ready);
+    }
+    pending
+        .into_iter()
+        .next()
+        .map_or(Ok(ordered), |(sequent, _)| {
+            Err(PersistError::Unfounded(sequent))
+        })
+}
@@ -0,0 +1,24 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Everything it takes to glue together a new logic, in one import:
+//! ```rust
+//! use gentzen::prelude::*;
+//! ```
+
+pub use crate::{
+    proof::{
+        prove, prove_sequent, prove_with_axioms, prove_with_config, prove_with_limit,
+        prove_with_stats, Error,
+    },
+    sequents::{
+        Element, Hypersequent, InComponent, InferHyper, InferLabelled, InferLj, InferLk,
+        IntuitionistWithExchange, Labelled, LkWithExchange, RhsOnlyOrdered, RhsOnlyStructural,
+        RhsOnlyWithExchange, Side, Sided,
+    },
+    Extract, Heuristic, Infer, MaybeSendSync, Multiset, Rule, SearchConfig, SearchObserver,
+    SearchStats, Sequent, SequentRenderer, Subsumes, Term, Tree,
+};
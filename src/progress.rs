@@ -0,0 +1,58 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Heartbeat for long-running proof searches.
+
+use crate::{SearchObserver, Sequent};
+use core::time::Duration;
+
+/// Snapshot of a running proof search, taken each time we take a sequent out of the queue.
+#[allow(clippy::exhaustive_structs)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Progress {
+    /// Sequents taken out of the queue so far, including this one.
+    pub dequeued: usize,
+    /// Sequents still waiting in the queue.
+    pub queue_len: usize,
+    /// Inferences set aside until their premises are proven.
+    pub paused: usize,
+    /// Distinct sequents we've seen so far, proven or not.
+    pub cache_size: usize,
+    /// Time since this search started.
+    pub elapsed: Duration,
+}
+
+/// Pass progress along to a callback, but at most once per interval.
+pub(crate) struct Throttled<F> {
+    /// Least time between two reports.
+    every: Duration,
+    /// Elapsed time before which we stay quiet.
+    next: Duration,
+    /// What to call with each report.
+    report: F,
+}
+
+impl<F: FnMut(&Progress)> Throttled<F> {
+    /// Call `report` with the first snapshot, then with the first after each `every` has passed.
+    #[inline]
+    pub(crate) const fn new(every: Duration, report: F) -> Self {
+        Self {
+            every,
+            next: Duration::ZERO,
+            report,
+        }
+    }
+}
+
+impl<S: Sequent, F: FnMut(&Progress)> SearchObserver<S> for Throttled<F> {
+    #[inline]
+    fn on_progress(&mut self, progress: &Progress) {
+        if progress.elapsed >= self.next {
+            (self.report)(progress);
+            self.next = progress.elapsed.saturating_add(self.every);
+        }
+    }
+}
@@ -12,7 +12,7 @@
 // A, ~A
 //
 // |- G, A   |- ~A, D
-// ------------------ Cut
+// ------------------ Cut (only on lemmas passed to `prove_with_lemmas`)
 // |- G, D
 //
 // |- G, A, B, D
@@ -202,65 +202,615 @@
 
 use crate::{
     inference::Inference,
-    thunk::{Qed, Thunk},
-    Infer, Rule, Sequent, Tree,
+    lemma::Lemmas,
+    observe::SearchObserver,
+    parallel::{expand, Shared},
+    paused::Paused,
+    proofs::Proofs,
+    stats::bump,
+    thunk::{CacheError, Qed, Thunk, AXIOM},
+    CancellationToken, FailureReport, Heuristic, Infer, ProofDag, SearchConfig, SearchStats,
+    SearchStrategy, Sequent, Tree,
+};
+use alloc::{borrow::Cow, vec};
+use core::hash::{BuildHasher, Hash};
+#[cfg(feature = "std")]
+use {
+    crate::{progress::Throttled, Progress},
+    core::time::Duration,
+    std::time::Instant,
 };
-use core::hash::Hash;
-use std::{collections::HashSet, rc::Rc};
 
 /// Unsuccessful proof.
 #[non_exhaustive]
+#[allow(clippy::error_impl_error)]
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum Error {
     /// Ran out of actionable sequents to manipulate.
     RanOutOfPaths,
+    /// Tried as many sequents as we were allowed to without finding a proof.
+    LimitExceeded {
+        /// How many sequents we took out of the queue before giving up.
+        explored: usize,
+    },
+    /// Hit the deadline without finding a proof.
+    TimedOut,
+    /// Someone cancelled the search through a `CancellationToken`.
+    Cancelled,
+    /// No proof is at most this deep, but there might be a deeper one.
+    DepthExceeded {
+        /// Deepest proof we looked for.
+        max_depth: usize,
+    },
+    /// No proof stays within these caps, but there might be one through a sequent over them.
+    Incomplete {
+        /// Most items we allowed in any one sequent (see `SearchConfig::max_width`),
+        /// if we skipped any sequent for having more.
+        max_width: Option<usize>,
+        /// Most copies of any one item we allowed in any one sequent (see `SearchConfig::max_copies`),
+        /// if we skipped any sequent for having more.
+        max_copies: Option<usize>,
+    },
+}
+
+impl core::fmt::Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::RanOutOfPaths => write!(f, "Ran out of sequents to try without finding a proof"),
+            Self::LimitExceeded { explored } => write!(
+                f,
+                "Gave up after exploring {explored} sequents without finding a proof",
+            ),
+            Self::TimedOut => write!(f, "Ran out of time before finding a proof"),
+            Self::Cancelled => write!(f, "Proof search was cancelled"),
+            Self::DepthExceeded { max_depth } => {
+                write!(f, "No proof at most {max_depth} inference lines deep")
+            }
+            Self::Incomplete {
+                max_width,
+                max_copies,
+            } => {
+                write!(f, "No proof with")?;
+                if let Some(width) = max_width {
+                    write!(f, " at most {width} items")?;
+                    if max_copies.is_some() {
+                        write!(f, " and")?;
+                    }
+                }
+                if let Some(copies) = max_copies {
+                    write!(f, " at most {copies} copies of any one item")?;
+                }
+                write!(f, " in every sequent")
+            }
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+/// How many sequents to take out of the queue between checks of the clock.
+#[cfg(feature = "std")]
+const CLOCK_CHECK_INTERVAL: usize = 64;
+
+/// Deepest proof `prove_shortest` looks for before giving up.
+const DEFAULT_MAX_DEPTH: usize = 256;
+
+/// When to give up early.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Budget<'token> {
+    /// Maximum number of sequents to take out of the queue.
+    max_sequents: usize,
+    /// Point in time after which we stop (if any).
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+    /// Flag that another thread may set to stop us (if any).
+    cancel: Option<&'token CancellationToken>,
+}
+
+impl Budget<'_> {
+    /// No limits at all.
+    pub(crate) const UNLIMITED: Self = Self {
+        max_sequents: usize::MAX,
+        #[cfg(feature = "std")]
+        deadline: None,
+        cancel: None,
+    };
+
+    /// Check whether we have to stop, given how many sequents we've already taken out of the queue.
+    #[inline]
+    fn check(&self, explored: usize) -> Result<(), Error> {
+        if explored >= self.max_sequents {
+            return Err(Error::LimitExceeded { explored });
+        }
+        if self.cancel.is_some_and(CancellationToken::is_cancelled) {
+            return Err(Error::Cancelled);
+        }
+        #[cfg(feature = "std")]
+        if let Some(deadline) = self.deadline {
+            if explored.is_multiple_of(CLOCK_CHECK_INTERVAL) && Instant::now() >= deadline {
+                return Err(Error::TimedOut);
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Attempt to prove this expression with sequent-calculus proof search.
+///
+/// Search is deterministic: when several derivations exist, the same expression always gets the same one,
+/// on every run and every platform (and with or without the `parallel` feature).
+/// Like every entry point but `prove_with_config`, never looks at a sequent with more than four copies of any one item
+/// (or more than the original starts with), so contraction can't grow sequents forever,
+/// and fails with `Error::Incomplete` if it runs out of sequents after skipping any (see `SearchConfig::max_copies`).
 /// # Errors
 /// If we can't.
 #[inline]
 pub fn prove<I: Infer<S>, S: Sequent<Item = I>>(expr: I) -> Result<Tree<S>, Error> {
-    let mut queue: Thunk<S> = Thunk::new(expr.clone());
-    let mut paused = HashSet::new();
+    search(S::from_rhs(expr), Budget::UNLIMITED)
+}
+
+/// Attempt to prove this expression exactly as `prove` would,
+/// but keep each shared subproof as one node that every inference using it points to.
+/// # Errors
+/// If we can't.
+#[inline]
+pub fn prove_dag<I: Infer<S>, S: Sequent<Item = I>>(expr: I) -> Result<ProofDag<S>, Error> {
+    let goal = S::from_rhs(expr);
+    let mut queue = Thunk::new(goal.clone());
+    drop(run(&goal, &mut queue, Budget::UNLIMITED, &mut ())?);
+    ProofDag::cached(&goal, &queue).ok_or(Error::RanOutOfPaths)
+}
+
+/// Attempt to prove this whole sequent with sequent-calculus proof search,
+/// e.g. one with several formulas on a side, or anything on the left, which `prove` can't start from.
+/// # Errors
+/// If we can't.
+#[inline]
+pub fn prove_sequent<S: Sequent>(goal: S) -> Result<Tree<S>, Error> {
+    search(goal, Budget::UNLIMITED)
+}
+
+/// Attempt to prove this expression with sequent-calculus proof search,
+/// giving up after taking `max_sequents` sequents out of the queue.
+/// # Errors
+/// If we can't, or if we run out of budget.
+#[inline]
+pub fn prove_with_limit<I: Infer<S>, S: Sequent<Item = I>>(
+    expr: I,
+    max_sequents: usize,
+) -> Result<Tree<S>, Error> {
+    search(
+        S::from_rhs(expr),
+        Budget {
+            max_sequents,
+            ..Budget::UNLIMITED
+        },
+    )
+}
+
+/// Attempt to prove this expression with sequent-calculus proof search,
+/// giving up once `timeout` has elapsed (checked every so often, not after every step).
+/// # Errors
+/// If we can't, or if we run out of time.
+#[inline]
+#[cfg(feature = "std")]
+pub fn prove_with_deadline<I: Infer<S>, S: Sequent<Item = I>>(
+    expr: I,
+    timeout: Duration,
+) -> Result<Tree<S>, Error> {
+    search(
+        S::from_rhs(expr),
+        Budget {
+            deadline: Instant::now().checked_add(timeout),
+            ..Budget::UNLIMITED
+        },
+    )
+}
+
+/// Attempt to prove this expression with sequent-calculus proof search,
+/// calling `report` with how far along we are at most once every `every`.
+///
+/// The first sequent we take out of the queue always gets a report,
+/// and with `Duration::ZERO`, so does every one after it.
+/// # Errors
+/// If we can't.
+#[inline]
+#[cfg(feature = "std")]
+pub fn prove_with_progress<I: Infer<S>, S: Sequent<Item = I>, F: FnMut(&Progress)>(
+    expr: I,
+    every: Duration,
+    report: F,
+) -> Result<Tree<S>, Error> {
+    let goal = S::from_rhs(expr);
+    let mut queue = Thunk::new(goal.clone());
+    run(
+        &goal,
+        &mut queue,
+        Budget::UNLIMITED,
+        &mut Throttled::new(every, report),
+    )
+}
+
+/// Attempt to prove this expression with sequent-calculus proof search,
+/// stopping early (checked after every step) if anyone cancels `token`.
+/// # Errors
+/// If we can't, or if we're cancelled.
+#[inline]
+pub fn prove_cancellable<I: Infer<S>, S: Sequent<Item = I>>(
+    expr: I,
+    token: &CancellationToken,
+) -> Result<Tree<S>, Error> {
+    search(
+        S::from_rhs(expr),
+        Budget {
+            cancel: Some(token),
+            ..Budget::UNLIMITED
+        },
+    )
+}
+
+/// Attempt to prove this expression with sequent-calculus proof search,
+/// reporting how much work it took whether or not we succeed.
+#[inline]
+pub fn prove_with_stats<I: Infer<S>, S: Sequent<Item = I>>(
+    expr: I,
+) -> (Result<Tree<S>, Error>, SearchStats) {
+    search_with_stats(S::from_rhs(expr), Budget::UNLIMITED)
+}
+
+/// Attempt to prove this expression with sequent-calculus proof search,
+/// expanding sequents in the order `strategy` picks.
+/// # Errors
+/// If we can't.
+#[inline]
+pub fn prove_with_strategy<I: Infer<S>, S: Sequent<Item = I>>(
+    expr: I,
+    strategy: SearchStrategy<S>,
+) -> Result<Tree<S>, Error> {
+    let mut queue: Thunk<S> = Thunk::with_strategy(expr.clone(), strategy);
+    run(&S::from_rhs(expr), &mut queue, Budget::UNLIMITED, &mut ())
+}
+
+/// Attempt to prove this expression with sequent-calculus proof search,
+/// tuned by `config` (e.g. to hash sequents with something other than the standard library's default).
+///
+/// With the default config, finds exactly the proof `prove` would:
+/// the hasher changes only how fast we look things up.
+/// # Errors
+/// If we can't.
+#[inline]
+pub fn prove_with_config<I: Infer<S>, S: Sequent<Item = I>, H: BuildHasher + Clone>(
+    expr: I,
+    config: SearchConfig<S, H>,
+) -> Result<Tree<S>, Error> {
+    let mut queue = Thunk::with_config(expr.clone(), config);
+    run(&S::from_rhs(expr), &mut queue, Budget::UNLIMITED, &mut ())
+}
+
+/// Attempt to prove this expression with sequent-calculus proof search,
+/// or if we can't, report which sequents no rule could conclude
+/// and the chain of sequents leading from the original to each.
+/// # Errors
+/// If we can't.
+#[inline]
+pub fn prove_or_explain<I: Infer<S>, S: Sequent<Item = I>>(
+    expr: I,
+) -> Result<Tree<S>, FailureReport<S>> {
+    let goal = S::from_rhs(expr);
+    let mut queue = Thunk::new(goal.clone());
+    queue.trace();
+    run(&goal, &mut queue, Budget::UNLIMITED, &mut ()).map_err(|_ran_out_of_paths| {
+        let dead_ends = queue.dead_ends();
+        FailureReport {
+            paths: dead_ends
+                .iter()
+                .map(|dead_end| (dead_end.clone(), queue.path_to(dead_end)))
+                .collect(),
+            dead_ends,
+            explored: queue.stats().dequeued,
+        }
+    })
+}
+
+/// Attempt to prove this expression with sequent-calculus proof search,
+/// also allowed to cut on any of `lemmas`, whose duals `dual` computes.
+///
+/// Cutting on the right lemma can shortcut an otherwise huge search,
+/// but every lemma multiplies the inferences tried at each step.
+/// # Errors
+/// If we can't.
+#[inline]
+pub fn prove_with_lemmas<I: Infer<S>, S: Sequent<Item = I>, F: Fn(&I) -> I>(
+    expr: I,
+    lemmas: &[I],
+    dual: F,
+) -> Result<Tree<S>, Error> {
+    let mut queue: Thunk<S> = Thunk::with_lemmas(expr.clone(), Lemmas::new(lemmas, dual));
+    run(&S::from_rhs(expr), &mut queue, Budget::UNLIMITED, &mut ())
+}
+
+/// Attempt to prove this expression with sequent-calculus proof search,
+/// taking every one of `axioms` as proven by a rule named `axiom` with nothing above it.
+///
+/// Any branch that reaches an axiom closes immediately,
+/// so non-logical axioms (e.g. `⊢ ~coin, candy` to buy candy with a coin) act as a theory to reason in.
+/// Note that `Tree::validate` checks only the logical rules, so it rejects any proof that uses an axiom.
+/// # Errors
+/// If we can't.
+#[inline]
+pub fn prove_with_axioms<I: Infer<S>, S: Sequent<Item = I>, A: IntoIterator<Item = S>>(
+    expr: I,
+    axioms: A,
+) -> Result<Tree<S>, Error> {
+    let Ok(mut queue) = Thunk::with_axioms(expr.clone(), axioms) else {
+        return Ok(Tree {
+            above: vec![],
+            rule: Cow::Borrowed(AXIOM),
+            principal: None,
+            below: S::from_rhs(expr),
+        });
+    };
+    run(&S::from_rhs(expr), &mut queue, Budget::UNLIMITED, &mut ())
+}
+
+/// Attempt to prove this expression with sequent-calculus proof search,
+/// exploring the premises of whichever inferences `heuristic` scores highest first.
+///
+/// Finds a proof exactly when `prove` would, though not necessarily the same one,
+/// and with `&()` as the heuristic, finds exactly the proof `prove` would.
+/// # Errors
+/// If we can't.
+#[inline]
+pub fn prove_guided<I: Infer<S>, S: Sequent<Item = I>, G: Heuristic<S> + ?Sized>(
+    expr: I,
+    heuristic: &G,
+) -> Result<Tree<S>, Error> {
+    let goal = S::from_rhs(expr);
+    let mut queue = Thunk::new(goal.clone());
+    resume(
+        &goal,
+        &mut queue,
+        &mut Paused::new(),
+        Budget::UNLIMITED,
+        &mut (),
+        heuristic,
+    )
+}
+
+/// Attempt to prove this expression with sequent-calculus proof search,
+/// calling `observer`'s hooks at each step.
+/// # Errors
+/// If we can't.
+#[inline]
+pub fn prove_observed<I: Infer<S>, S: Sequent<Item = I>, O: SearchObserver<S> + ?Sized>(
+    expr: I,
+    observer: &mut O,
+) -> Result<Tree<S>, Error> {
+    let goal = S::from_rhs(expr);
+    let mut queue = Thunk::new(goal.clone());
+    run(&goal, &mut queue, Budget::UNLIMITED, observer)
+}
+
+/// Every distinct proof of this expression, found lazily as the search goes on.
+/// Never ends if the search never runs out of sequents to try.
+#[inline]
+pub fn proofs<I: Infer<S>, S: Sequent<Item = I>>(expr: I) -> impl Iterator<Item = Tree<S>> {
+    Proofs::new(expr)
+}
+
+/// Find a proof as shallow as possible, returning it alongside its depth.
+///
+/// Searches at each depth limit in turn (iterative deepening) up to 256 inference lines.
+/// # Errors
+/// If there's no proof at all, or none that shallow.
+#[inline]
+pub fn prove_shortest<I: Infer<S>, S: Sequent<Item = I>>(
+    expr: I,
+) -> Result<(Tree<S>, usize), Error> {
+    prove_shortest_within(expr, DEFAULT_MAX_DEPTH)
+}
+
+/// Find a proof as shallow as possible, returning it alongside its depth,
+/// but giving up on proofs deeper than `max_depth`.
+///
+/// Searches at each depth limit in turn (iterative deepening),
+/// returning the first proof no deeper than the limit it was found at,
+/// with every subproof written out in full (see `Tree::expand_shared`).
+/// A proof may reuse a subproof found for the same sequent elsewhere, with more room above it,
+/// so any proof deeper than its limit is set aside and the search goes one deeper.
+/// # Errors
+/// If there's no proof at all, or none that shallow.
+/// If any limit skipped sequents for having too many copies (see `SearchConfig::max_copies`)
+/// and no deeper limit found a proof either, `Error::Incomplete`.
+#[inline]
+#[allow(clippy::needless_pass_by_value)] // Same signature as every other entry point
+pub fn prove_shortest_within<I: Infer<S>, S: Sequent<Item = I>>(
+    expr: I,
+    max_depth: usize,
+) -> Result<(Tree<S>, usize), Error> {
+    let mut incomplete = None;
+    for depth_limit in 1..=max_depth {
+        let mut queue: Thunk<S> = Thunk::with_depth_limit(expr.clone(), depth_limit);
+        match run(
+            &S::from_rhs(expr.clone()),
+            &mut queue,
+            Budget::UNLIMITED,
+            &mut (),
+        ) {
+            Err(Error::RanOutOfPaths) if queue.pruned() => {}
+            // Capped copies at this depth might be unnecessary with more room, so look deeper.
+            Err(error @ Error::Incomplete { .. }) => incomplete = Some(error),
+            Ok(mut tree) => {
+                tree.expand_shared();
+                let depth = tree.depth();
+                // Reused a subproof found with more room elsewhere, so look deeper for one that fits.
+                if depth <= depth_limit {
+                    return Ok((tree, depth));
+                }
+            }
+            Err(error) => return Err(error),
+        }
+    }
+    Err(incomplete.unwrap_or(Error::DepthExceeded { max_depth }))
+}
+
+/// Sequent-calculus proof search within a budget.
+#[inline]
+fn search<S: Sequent>(goal: S, budget: Budget<'_>) -> Result<Tree<S>, Error> {
+    search_with_stats(goal, budget).0
+}
+
+/// Sequent-calculus proof search within a budget, reporting how much work it took.
+#[inline]
+#[allow(clippy::needless_pass_by_value)] // Callers only build the goal to hand it over
+fn search_with_stats<S: Sequent>(
+    goal: S,
+    budget: Budget<'_>,
+) -> (Result<Tree<S>, Error>, SearchStats) {
+    let mut queue = Thunk::new(goal.clone());
+    let result = run(&goal, &mut queue, budget, &mut ());
+    (result, queue.stats())
+}
+
+/// The main proof-search loop.
+#[inline]
+fn run<S: Sequent, H: BuildHasher + Clone, O: SearchObserver<S> + ?Sized>(
+    goal: &S,
+    queue: &mut Thunk<S, H>,
+    budget: Budget<'_>,
+    observer: &mut O,
+) -> Result<Tree<S>, Error> {
+    resume(
+        goal,
+        queue,
+        &mut Paused::with_hasher(queue.hasher().clone()),
+        budget,
+        observer,
+        &(),
+    )
+}
+
+/// The main proof-search loop, picking up wherever a previous search left off.
+#[inline]
+pub(crate) fn resume<
+    I: Infer<S>,
+    S: Sequent<Item = I>,
+    H: BuildHasher + Clone,
+    O: SearchObserver<S> + ?Sized,
+    G: Heuristic<S> + ?Sized,
+>(
+    goal: &S,
+    queue: &mut Thunk<S, H>,
+    paused: &mut Paused<S, H>,
+    budget: Budget<'_>,
+    observer: &mut O,
+    heuristic: &G,
+) -> Result<Tree<S>, Error> {
+    // A previous search may have left inferences ready to discharge.
+    if let Some(tree) = discharge(goal, queue, paused, observer) {
+        return Ok(tree);
+    }
+    #[cfg(feature = "std")]
+    let start = Instant::now();
+    let mut explored: usize = 0;
     while let Some(sequent) = queue.next() {
+        #[cfg(feature = "std")]
+        observer.on_progress(&Progress {
+            dequeued: queue.stats().dequeued,
+            queue_len: queue.queue_len(),
+            paused: paused.len(),
+            cache_size: queue.cache_len(),
+            elapsed: start.elapsed(),
+        });
+        if queue.proven(&sequent).is_some() {
+            // Proven some other way while it waited in the queue.
+            continue;
+        }
+        budget.check(explored)?;
+        explored = explored.saturating_add(1);
         dbg_println!("Trying {sequent}");
-        let rc = Rc::new(sequent);
-        for inference in rc
-            .sample()
-            .into_iter()
-            .flat_map(move |(item, context)| item.above(context))
-            .map(|rule| Inference {
-                rule,
-                below: Rc::clone(&rc),
-            })
-        {
+        observer.on_dequeue(&sequent);
+        let rc = Shared::new(sequent);
+        // An initial sequent closes on its own, so there's no need to weaken anything else.
+        let weaker = if rc.closed().is_some() {
+            None
+        } else {
+            queue.weakening(&rc)
+        };
+        let rules = if let Some(weakening) = weaker {
+            // Already proven with less context.
+            bump(&mut queue.stats_mut().subsumed);
+            vec![weakening]
+        } else {
+            let mut rules = expand(rc.as_ref());
+            let generated = &mut queue.stats_mut().rules_generated;
+            *generated = generated.saturating_add(rules.len());
+            if let Some(i) = rules.iter().position(|rule| rule.invertible) {
+                // Anything that proves this sequent can go through this rule instead.
+                rules = vec![rules.swap_remove(i)];
+            } else {
+                rules.extend(queue.cuts(&rc));
+            }
+            rules
+        };
+        queue.record_expansion(&rc, !rules.is_empty());
+        for inference in rules.into_iter().map(|rule| Inference {
+            rule,
+            below: Shared::clone(&rc),
+        }) {
             // dbg_println!("    Pausing {inference}");
+            observer.on_pause(&inference.below, &inference.rule);
+            let score = heuristic.score(&inference.below, &inference.rule);
             let sequents = inference.rule.above.clone();
-            let _ = paused.insert(inference);
-            queue.extend(sequents);
-        }
-        let mut done = HashSet::new();
-        'inferences: loop {
-            for inference in &paused {
-                if !done.contains(inference) && inference.proven(&queue) {
-                    dbg_println!("    Proved {inference}");
-                    match queue.cache(inference.below.as_ref().clone(), inference.rule.clone()) {
-                        Ok(()) => {
-                            let _ = done.insert(inference.clone());
-                            continue 'inferences;
-                        }
-                        Err(Qed {
-                            proof: Rule { name, above },
-                        }) => return Ok(Tree::connect(S::from_rhs(expr), name, above, &mut queue)),
-                    };
-                }
+            if paused.pause(inference, queue) {
+                bump(&mut queue.stats_mut().inferences_paused);
             }
-            break 'inferences;
+            queue.extend_above(&rc, sequents, score);
+        }
+        if let Some(tree) = discharge(goal, queue, paused, observer) {
+            return Ok(tree);
+        }
+    }
+    Err(match queue.caps_exceeded() {
+        (None, None) => Error::RanOutOfPaths,
+        (max_width, max_copies) => Error::Incomplete {
+            max_width,
+            max_copies,
+        },
+    })
+}
+
+/// Discharge every paused inference whose premises are all proven,
+/// returning the whole proof if that proves the goal.
+#[inline]
+fn discharge<S: Sequent, H: BuildHasher + Clone, O: SearchObserver<S> + ?Sized>(
+    goal: &S,
+    queue: &mut Thunk<S, H>,
+    paused: &mut Paused<S, H>,
+    observer: &mut O,
+) -> Option<Tree<S>> {
+    while let Some(inference) = paused.next_ready() {
+        if queue.proven(&inference.below).is_some() {
+            // Another inference already proved the same sequent.
+            continue;
         }
-        for inference in &done {
-            let _ = paused.remove(inference);
+        dbg_println!("    Proved {inference}");
+        observer.on_prove(&inference.below, &inference.rule);
+        bump(&mut queue.stats_mut().inferences_discharged);
+        let cached = queue
+            .cache(inference.below.as_ref().clone(), inference.rule.clone())
+            .unwrap_or_else(|CacheError { sequent, proof }| {
+                // Can't happen, since it came off the queue, but it's still proven.
+                queue.restore(sequent, proof);
+                None
+            });
+        // Even the goal might be a premise of something we're asked to prove later.
+        paused.notify(&inference.below, queue);
+        paused.prune(&inference.below, queue);
+        if let Some(Qed { proof }) = cached {
+            return Some(Tree::connect(goal.clone(), proof, queue));
         }
     }
-    Err(Error::RanOutOfPaths)
+    None
 }
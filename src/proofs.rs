@@ -0,0 +1,252 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Every proof of an expression, found lazily.
+
+use crate::{
+    hash::{Entry, HashMap},
+    inference::Inference,
+    parallel::{expand, Shared},
+    paused::Paused,
+    thunk::Thunk,
+    Rule, Sequent, Tree,
+};
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+/// Every proof of an expression, found lazily.
+///
+/// Each step builds only the proofs that use a rule discharged in that step,
+/// so every proof gets built exactly once and the work per step grows with what's new.
+#[derive(Clone, Debug)]
+pub(crate) struct Proofs<S: Sequent> {
+    /// Sequents left to expand, plus the first proof found of each.
+    queue: Thunk<S>,
+    /// The sequent we're trying to prove overall.
+    original: S,
+    /// Inferences waiting on proofs of the sequents above them.
+    paused: Paused<S>,
+    /// Rules `paused` turned away for having the same premises and conclusion as one it already held
+    /// (but, say, a different name), to discharge alongside that one.
+    twins: HashMap<Inference<S>, Vec<Rule<S>>>,
+    /// Every rule we've discharged, not only the first for each sequent, alongside what it proves.
+    discharged: Vec<(S, Rule<S>)>,
+    /// Index in `discharged` of each rule with this sequent above it.
+    dependents: HashMap<S, Vec<usize>>,
+    /// Every proof we've built of each sequent, oldest first,
+    /// none of which proves any sequent in terms of itself.
+    trees: HashMap<S, Vec<Tree<S>>>,
+    /// How many proofs of the original we've yielded.
+    yielded: usize,
+}
+
+impl<S: Sequent> Proofs<S> {
+    /// Start looking for proofs of this expression.
+    #[inline]
+    pub(crate) fn new(expr: S::Item) -> Self {
+        let original = S::from_rhs(expr);
+        Self {
+            queue: Thunk::new(original.clone()),
+            original,
+            paused: Paused::new(),
+            twins: HashMap::new(),
+            discharged: vec![],
+            dependents: HashMap::new(),
+            trees: HashMap::new(),
+            yielded: 0,
+        }
+    }
+
+    /// Expand one sequent from the queue, discharge whatever that lets us discharge,
+    /// and build every proof that uses any of what we just discharged.
+    /// Returns `false` if the queue was already empty.
+    #[inline]
+    fn step(&mut self) -> bool {
+        let Some(sequent) = self.queue.next() else {
+            return false;
+        };
+        let rc = Shared::new(sequent);
+        for inference in expand(rc.as_ref()).into_iter().map(|rule| Inference {
+            rule,
+            below: Shared::clone(&rc),
+        }) {
+            let sequents = inference.rule.above.clone();
+            if !self.paused.pause(inference.clone(), &mut self.queue) {
+                let rule = inference.rule.clone();
+                self.twins.entry(inference).or_default().push(rule);
+            }
+            // Every proof eventually, so no point preferring any.
+            self.queue.extend_above(&rc, sequents, 0);
+        }
+        let fresh = self.discharged.len();
+        while let Some(inference) = self.paused.next_ready() {
+            let below = inference.below.as_ref();
+            if *below != self.original && self.queue.proven(below).is_none() {
+                drop(self.queue.cache(below.clone(), inference.rule.clone()));
+                self.paused.notify(below, &mut self.queue);
+            }
+            let twins = self.twins.remove(&inference).unwrap_or_default();
+            for rule in core::iter::once(inference.rule).chain(twins) {
+                let id = self.discharged.len();
+                for (premise, _) in rule.above.iter_unique() {
+                    self.dependents.entry(premise.clone()).or_default().push(id);
+                }
+                self.discharged.push((below.clone(), rule));
+            }
+        }
+        if fresh < self.discharged.len() {
+            self.build(fresh);
+        }
+        true
+    }
+
+    /// Build every proof that uses a rule at or past index `fresh` in `discharged`, round by round:
+    /// first each new rule over proofs we already had, then each round every rule over
+    /// at least one proof the round before built.
+    #[inline]
+    fn build(&mut self, fresh: usize) {
+        let mut built: Vec<(S, Tree<S>)> = vec![];
+        for id in fresh..self.discharged.len() {
+            self.combine(id, &HashMap::new(), &mut built);
+        }
+        while !built.is_empty() {
+            // Where each sequent's proofs from this round start: everything before is older.
+            let mut starts: HashMap<S, usize> = HashMap::new();
+            let mut ids = vec![];
+            for (below, tree) in core::mem::take(&mut built) {
+                let trees = self.trees.entry(below.clone()).or_default();
+                if let Entry::Vacant(slot) = starts.entry(below) {
+                    if let Some(dependents) = self.dependents.get(slot.key()) {
+                        ids.extend_from_slice(dependents);
+                    }
+                    let _ = slot.insert(trees.len());
+                }
+                trees.push(tree);
+            }
+            ids.sort_unstable();
+            ids.dedup();
+            for id in ids {
+                self.combine(id, &starts, &mut built);
+            }
+        }
+    }
+
+    /// Every proof that ends in the rule at this index in `discharged`
+    /// and, for any premise in `starts`, uses at least one proof from there on,
+    /// without proving any sequent in terms of itself.
+    /// Copies of the same premise take proofs in the order we built them, so no proof comes out twice.
+    #[inline]
+    fn combine(&self, id: usize, starts: &HashMap<S, usize>, built: &mut Vec<(S, Tree<S>)>) {
+        let Some(&(ref below, ref rule)) = self.discharged.get(id) else {
+            return;
+        };
+        // Each copy of each premise, the proofs of it we may use, and where the newest start.
+        let mut slots: Vec<(&S, Vec<usize>, usize)> = vec![];
+        for premise in &rule.above {
+            let Some(trees) = self.trees.get(premise) else {
+                return;
+            };
+            let usable = trees
+                .iter()
+                .enumerate()
+                .filter(|&(_, tree)| tree.iter().all(|node| node.below != *below))
+                .map(|(index, _)| index)
+                .collect();
+            let start = starts.get(premise).copied().unwrap_or(trees.len());
+            slots.push((premise, usable, start));
+        }
+        let mut combinations: Vec<Vec<usize>> = vec![];
+        if starts.is_empty() {
+            let everything: Vec<_> = slots.iter().map(|_| 0..usize::MAX).collect();
+            combinations.extend(Self::choose(&slots, &everything));
+        } else {
+            // Partition by the first copy to use a new proof, so none comes out twice.
+            for first in 0..slots.len() {
+                let ranges: Vec<Range<usize>> = slots
+                    .iter()
+                    .enumerate()
+                    .map(|(index, &(_, _, start))| match index.cmp(&first) {
+                        core::cmp::Ordering::Less => 0..start,
+                        core::cmp::Ordering::Equal => start..usize::MAX,
+                        core::cmp::Ordering::Greater => 0..usize::MAX,
+                    })
+                    .collect();
+                combinations.extend(Self::choose(&slots, &ranges));
+            }
+        }
+        for choice in combinations {
+            let above = slots
+                .iter()
+                .zip(choice)
+                .filter_map(|(&(premise, _, _), index)| {
+                    let trees = self.trees.get(premise)?;
+                    trees.get(index)
+                })
+                .cloned()
+                .collect();
+            built.push((
+                below.clone(),
+                Tree {
+                    above,
+                    rule: rule.name.clone(),
+                    principal: rule.principal.clone(),
+                    below: below.clone(),
+                },
+            ));
+        }
+    }
+
+    /// Every way to pick one usable proof per slot from within its range,
+    /// never picking an older proof for a later copy of the same premise.
+    #[inline]
+    fn choose(slots: &[(&S, Vec<usize>, usize)], ranges: &[Range<usize>]) -> Vec<Vec<usize>> {
+        let mut combinations = vec![vec![]];
+        let mut last = None;
+        for (&(premise, ref usable, _), range) in slots.iter().zip(ranges) {
+            let repeated = last == Some(premise);
+            last = Some(premise);
+            combinations = combinations
+                .into_iter()
+                .flat_map(|partial: Vec<usize>| {
+                    let floor = if repeated {
+                        partial.last().copied().unwrap_or(0)
+                    } else {
+                        0
+                    };
+                    usable
+                        .iter()
+                        .filter(move |&&option| range.contains(&option) && option >= floor)
+                        .map(move |&option| {
+                            let mut extended = partial.clone();
+                            extended.push(option);
+                            extended
+                        })
+                })
+                .collect();
+        }
+        combinations
+    }
+}
+
+impl<S: Sequent> Iterator for Proofs<S> {
+    type Item = Tree<S>;
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(tree) = self
+                .trees
+                .get(&self.original)
+                .and_then(|trees| trees.get(self.yielded))
+            {
+                self.yielded = self.yielded.saturating_add(1);
+                return Some(tree.clone());
+            }
+            if !self.step() {
+                return None;
+            }
+        }
+    }
+}
@@ -0,0 +1,161 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! One layout for sequents, printed with whichever symbols suit where it's going.
+
+use crate::{
+    ascii::to_ascii,
+    html,
+    latex::{escape, LATEX_SYMBOLS},
+    Sequent,
+};
+use alloc::string::{String, ToString as _};
+use core::fmt::Display;
+
+/// Symbols and escaping for printing a sequent somewhere in particular, e.g. a terminal or a LaTeX document.
+pub trait SequentRenderer {
+    /// What goes between the two sides.
+    fn turnstile(&self) -> &str;
+    /// What goes between two items on the same side.
+    fn separator(&self) -> &str;
+    /// One item, with its symbols translated.
+    fn item(&self, item: &dyn Display) -> String;
+    /// Items on the left, then the turnstile, then items on the right,
+    /// with a space on either side of the turnstile unless that side is empty.
+    #[inline]
+    fn layout<L: IntoIterator<Item = A>, A: Display, R: IntoIterator<Item = B>, B: Display>(
+        &self,
+        lhs: L,
+        rhs: R,
+    ) -> String {
+        let mut out = String::new();
+        let mut any_lhs = false;
+        for item in lhs {
+            if any_lhs {
+                out.push_str(self.separator());
+            }
+            out.push_str(&self.item(&item));
+            any_lhs = true;
+        }
+        if any_lhs {
+            out.push(' ');
+        }
+        out.push_str(self.turnstile());
+        for (i, item) in rhs.into_iter().enumerate() {
+            out.push_str(if i == 0 { " " } else { self.separator() });
+            out.push_str(&self.item(&item));
+        }
+        out
+    }
+}
+
+/// The usual Unicode symbols, exactly as `Display` prints them.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[allow(clippy::exhaustive_structs)]
+pub struct Unicode;
+
+impl SequentRenderer for Unicode {
+    #[inline(always)]
+    fn turnstile(&self) -> &'static str {
+        "\u{22a2}"
+    }
+    #[inline(always)]
+    fn separator(&self) -> &'static str {
+        ", "
+    }
+    #[inline(always)]
+    fn item(&self, item: &dyn Display) -> String {
+        item.to_string()
+    }
+}
+
+/// Plain ASCII, translated with `ASCII_SYMBOLS`, exactly as the alternate form (`{:#}`) of `Display` prints it.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[allow(clippy::exhaustive_structs)]
+pub struct Ascii;
+
+impl SequentRenderer for Ascii {
+    #[inline(always)]
+    fn turnstile(&self) -> &'static str {
+        "|-"
+    }
+    #[inline(always)]
+    fn separator(&self) -> &'static str {
+        ", "
+    }
+    #[inline(always)]
+    fn item(&self, item: &dyn Display) -> String {
+        to_ascii(&item.to_string())
+    }
+}
+
+/// The usual Unicode symbols, escaped for HTML.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[allow(clippy::exhaustive_structs)]
+pub struct Html;
+
+impl SequentRenderer for Html {
+    #[inline(always)]
+    fn turnstile(&self) -> &'static str {
+        "\u{22a2}"
+    }
+    #[inline(always)]
+    fn separator(&self) -> &'static str {
+        ", "
+    }
+    #[inline(always)]
+    fn item(&self, item: &dyn Display) -> String {
+        html::escape(&item.to_string())
+    }
+}
+
+/// LaTeX math mode, translating any symbol in a table (`LATEX_SYMBOLS` by default) and escaping everything else.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Latex<'symbols> {
+    /// Unicode symbols and their LaTeX equivalents, checked in order.
+    symbols: &'symbols [(&'symbols str, &'symbols str)],
+}
+
+impl<'symbols> Latex<'symbols> {
+    /// Translate any symbol in `symbols` (checked in order) into its LaTeX equivalent.
+    #[must_use]
+    #[inline(always)]
+    pub const fn with(symbols: &'symbols [(&'symbols str, &'symbols str)]) -> Self {
+        Self { symbols }
+    }
+}
+
+impl Default for Latex<'_> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::with(LATEX_SYMBOLS)
+    }
+}
+
+impl SequentRenderer for Latex<'_> {
+    #[inline]
+    fn turnstile(&self) -> &str {
+        self.symbols
+            .iter()
+            .find(|&&(from, _)| from == "\u{22a2}")
+            .map_or("\\vdash", |&(_, to)| to)
+    }
+    #[inline(always)]
+    fn separator(&self) -> &'static str {
+        ", "
+    }
+    #[inline(always)]
+    fn item(&self, item: &dyn Display) -> String {
+        escape(&item.to_string(), self.symbols)
+    }
+}
+
+/// Print any sequent with `renderer`'s symbols.
+#[must_use]
+#[inline(always)]
+pub fn render_sequent<S: Sequent, R: SequentRenderer>(sequent: &S, renderer: &R) -> String {
+    sequent.render_with(renderer)
+}
@@ -0,0 +1,49 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Why a proof search came up empty, and how it got there.
+
+use crate::Sequent;
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+
+/// Why a proof search came up empty.
+#[allow(clippy::exhaustive_structs)]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FailureReport<S: Sequent> {
+    /// Sequents we tried to expand but that no rule could conclude.
+    pub dead_ends: BTreeSet<S>,
+    /// For each dead end, every sequent from the original down to it,
+    /// each generated by expanding the one before.
+    pub paths: BTreeMap<S, Vec<S>>,
+    /// Sequents taken out of the queue to be expanded.
+    pub explored: usize,
+}
+
+impl<S: Sequent> core::fmt::Display for FailureReport<S> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for dead_end in &self.dead_ends {
+            let mut path = self
+                .paths
+                .get(dead_end)
+                .map_or_else(|| core::slice::from_ref(dead_end), Vec::as_slice)
+                .iter();
+            if let Some(first) = path.next() {
+                write!(f, "{first}")?;
+            }
+            for next in path {
+                write!(f, " \u{27f6} {next}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: Sequent> core::error::Error for FailureReport<S> {}
@@ -7,16 +7,117 @@
 //! A set of sequents above an inference line.
 
 use crate::{Multiset, Sequent};
+use alloc::borrow::Cow;
 use core::hash::Hash;
 
 /// A set of sequents above an inference line.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "S: serde::Serialize, S::Item: serde::Serialize",
+        deserialize = "S: serde::Deserialize<'de>, S::Item: serde::Deserialize<'de>",
+    ))
+)]
 #[allow(clippy::exhaustive_structs)]
 pub struct Rule<S: Sequent> {
     /// Name of the rule that allowed this inference.
-    pub name: &'static str,
+    pub name: Cow<'static, str>,
     /// Everything above the inference line: effectively next steps.
+    ///
+    /// A multiset rather than a list, since premises have no order worth keeping,
+    /// but one that counts copies: a rule needing the same premise twice (e.g. `&` on `1 & 1`)
+    /// still has two premises (see `arity`), and a `Tree` writes out a proof for each copy,
+    /// even though search proves it only once.
     pub above: Multiset<S>,
+    /// Formula this rule acted on, filled in by proof search for rules that came from an item
+    /// (so `None` for structural rules and cuts).
+    /// Equality, ordering, and hashing ignore it (and the name), looking only at the premises.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub principal: Option<S::Item>,
+    /// Whether the premises are provable whenever the conclusion is,
+    /// so proof search can commit to this rule without trying any others.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub invertible: bool,
+}
+
+impl<S: Sequent> Rule<S> {
+    /// Name an inference and list everything above its line.
+    /// String literals stay borrowed, but a name built at runtime (e.g. with `format!`) works too.
+    /// # Panics
+    /// In debug builds, if `name` is empty (every inference line in a printed proof needs a label).
+    #[inline]
+    #[must_use]
+    pub fn new<N: Into<Cow<'static, str>>, A: IntoIterator<Item = S>>(name: N, above: A) -> Self {
+        let owned = name.into();
+        debug_assert!(!owned.is_empty(), "Rules must have non-empty names");
+        Self {
+            name: owned,
+            above: above.into_iter().collect(),
+            principal: None,
+            invertible: false,
+        }
+    }
+
+    /// Name an inference with nothing above its line, e.g. an axiom or a unit.
+    /// # Panics
+    /// In debug builds, if `name` is empty (every inference line in a printed proof needs a label).
+    #[inline]
+    #[must_use]
+    pub fn axiom<N: Into<Cow<'static, str>>>(name: N) -> Self {
+        Self::new(name, [])
+    }
+
+    /// Name an inference with exactly one sequent above its line.
+    /// # Panics
+    /// In debug builds, if `name` is empty (every inference line in a printed proof needs a label).
+    #[inline]
+    #[must_use]
+    pub fn unary<N: Into<Cow<'static, str>>>(name: N, premise: S) -> Self {
+        Self::new(name, [premise])
+    }
+
+    /// Name an inference with exactly two sequents above its line,
+    /// which stay two even if they're the same sequent.
+    /// # Panics
+    /// In debug builds, if `name` is empty (every inference line in a printed proof needs a label).
+    #[inline]
+    #[must_use]
+    pub fn binary<N: Into<Cow<'static, str>>>(name: N, lhs: S, rhs: S) -> Self {
+        Self::new(name, [lhs, rhs])
+    }
+
+    /// How many sequents are above the inference line, counting each copy of a repeated premise.
+    #[inline(always)]
+    #[must_use]
+    pub const fn arity(&self) -> usize {
+        self.above.len()
+    }
+
+    /// Whether nothing is above the inference line, so it proves its conclusion outright.
+    #[inline(always)]
+    #[must_use]
+    pub fn is_axiom(&self) -> bool {
+        self.above.is_empty()
+    }
+
+    /// Name an invertible inference and list everything above its line:
+    /// since its premises are provable whenever its conclusion is,
+    /// searching for a proof applies it eagerly and never tries any other way to conclude the same sequent.
+    /// # Panics
+    /// In debug builds, if `name` is empty (every inference line in a printed proof needs a label).
+    #[inline]
+    #[must_use]
+    pub fn invertible<N: Into<Cow<'static, str>>, A: IntoIterator<Item = S>>(
+        name: N,
+        above: A,
+    ) -> Self {
+        Self {
+            invertible: true,
+            ..Self::new(name, above)
+        }
+    }
 }
 
 impl<S: Sequent> PartialEq for Rule<S> {
@@ -53,18 +154,17 @@ impl<S: Sequent> Hash for Rule<S> {
 impl<S: Sequent + quickcheck::Arbitrary> quickcheck::Arbitrary for Rule<S> {
     #[inline]
     fn arbitrary(g: &mut quickcheck::Gen) -> Self {
-        Self {
-            name: "",
-            above: quickcheck::Arbitrary::arbitrary(g),
-        }
+        let name = *g.choose(&["a", "b", "c"]).unwrap_or(&"a");
+        Self::new(name, Multiset::arbitrary(g))
     }
     #[inline]
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let name = self.name.clone();
         Box::new(
             self.above
                 .clone()
                 .shrink()
-                .map(|above| Self { name: "", above }),
+                .map(move |above| Self::new(name.clone(), above)),
         )
     }
 }
@@ -8,7 +8,8 @@
 //! i.e. a turnstile symbol with either nothing or
 //! a comma-separated list of things on either side.
 
-use crate::Infer;
+use crate::{Infer, MaybeSendSync, Rule, SequentRenderer};
+use alloc::{string::String, vec, vec::Vec};
 use core::{
     fmt::{Debug, Display},
     hash::Hash,
@@ -17,20 +18,74 @@ use core::{
 /// Anything that can represent a sequent,
 /// i.e. a turnstile symbol with either nothing or
 /// a comma-separated list of things on either side.
-pub trait Sequent: Clone + Debug + Display + Hash + Ord {
+pub trait Sequent: Clone + Debug + Display + Hash + MaybeSendSync + Ord {
     /// Whatever is separated by commas on either side of a turnstile.
-    type Item: Infer<Self>;
-    /// Iterator that separates each unique element from everything else.
-    // TODO: when `impl ...` is stabilized here, switch instead of building a vector
-    // type Sampler = core::iter::Map<
-    //     std::collections::btree_map::IntoKeys<Ast, NonZeroUsize>,
-    //     fn(Ast) -> (Ast, Self),
-    // >;
+    type Item: Debug + Display + Hash + Infer<Self> + Ord;
     /// Sequent with nothing on the left and this argument on the right.
     #[must_use]
     fn from_rhs(rhs_element: Self::Item) -> Self;
     /// For each unique item in the sequent (defined however you'd like),
     /// return a pair that separates that item from everything else.
+    /// Pairs are built lazily, so a search that stops early never clones the rest of the context.
     #[must_use]
-    fn sample(&self) -> Vec<(Self::Item, Self)>;
+    fn sample(&self) -> impl Iterator<Item = (Self::Item, Self)> + '_;
+    /// Most copies of any one item in this sequent,
+    /// which proof search can cap to stop contraction from growing sequents forever.
+    /// Zero by default, so sequents that don't count copies are never capped.
+    #[inline(always)]
+    #[must_use]
+    fn most_copies(&self) -> usize {
+        0
+    }
+    /// Name of the rule that proves this sequent outright with nothing above it
+    /// (e.g. an axiom), if it's an initial sequent.
+    /// Checked as soon as the sequent comes out of the queue, before sampling any item,
+    /// so rules like `A, ~A` live in one place instead of in every item's `above`.
+    /// `None` by default.
+    #[inline(always)]
+    #[must_use]
+    fn closed(&self) -> Option<&'static str> {
+        None
+    }
+    /// How many items this sequent holds in all, counting every copy,
+    /// which proof search can cap to stop rules like `⅋` or contraction from widening sequents forever.
+    /// Zero by default, so sequents that don't count their items are never capped.
+    /// (Not `len`, which some bundled sequents already define to count only part of themselves.)
+    #[inline(always)]
+    #[must_use]
+    fn width(&self) -> usize {
+        0
+    }
+    /// This sequent with `additions` beside everything already in it,
+    /// or `None` if it has no room for more (the default).
+    #[inline(always)]
+    #[must_use]
+    fn alongside<A: IntoIterator<Item = Self::Item>>(&self, additions: A) -> Option<Self> {
+        drop(additions);
+        None
+    }
+    /// Inferences that come from the sequent structure itself rather than any one item,
+    /// e.g. weakening or contraction (none by default).
+    #[inline(always)]
+    #[must_use]
+    fn structural(&self) -> Vec<Rule<Self>> {
+        vec![]
+    }
+    /// Every way to cut on a lemma and its dual,
+    /// i.e. to split this sequent's context between one premise with `lemma` and another with `dual`
+    /// (none by default).
+    #[inline(always)]
+    #[must_use]
+    fn cut(&self, lemma: &Self::Item, dual: &Self::Item) -> Vec<Rule<Self>> {
+        let _ = (lemma, dual);
+        vec![]
+    }
+    /// Print this sequent with `renderer`'s symbols.
+    /// By default, the whole of `Display` counts as one item, so its symbols are still translated,
+    /// but a sequent that lays out its own sides should override this.
+    #[inline(always)]
+    #[must_use]
+    fn render_with<R: SequentRenderer>(&self, renderer: &R) -> String {
+        renderer.item(self)
+    }
 }
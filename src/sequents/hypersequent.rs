@@ -0,0 +1,226 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Ordinary sequents side by side, separated by `|`, as in hypersequent calculi.
+
+use crate::{Infer, Multiset, Rule, Sequent};
+use alloc::{vec, vec::Vec};
+use core::fmt::Display;
+
+/// An item focused within one component of a hypersequent, alongside everything else in that component.
+#[allow(clippy::exhaustive_structs)]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct InComponent<S: Sequent> {
+    /// The item itself.
+    pub item: S::Item,
+    /// Everything else in the same component, as `Sequent::sample` separates it.
+    pub rest: S,
+}
+
+impl<S: Sequent + Default> InComponent<S> {
+    /// An item with nothing else in its component, e.g. to start proof search with.
+    #[must_use]
+    #[inline(always)]
+    pub fn alone(item: S::Item) -> Self {
+        Self {
+            item,
+            rest: S::default(),
+        }
+    }
+}
+
+impl<S: Sequent> Display for InComponent<S> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.item, f)
+    }
+}
+
+/// A set of hypersequent-calculus inference rules for items within one component of a hypersequent.
+pub trait InferHyper<S: Sequent<Item = Self>>: Infer<S> {
+    /// All possible "next moves" with this item in a component with `rest` everywhere else,
+    /// alongside every other component in `others`.
+    ///
+    /// By default, the rules this item offers in an ordinary sequent, applied within its own component.
+    #[inline]
+    fn above_in(&self, rest: S, others: &Hypersequent<S>) -> Vec<Rule<Hypersequent<S>>> {
+        others.lift(self.above(rest))
+    }
+    /// Rules that act on a whole hypersequent rather than on any one item,
+    /// e.g. external weakening or communication (none by default).
+    #[inline(always)]
+    #[must_use]
+    fn external(hypersequent: &Hypersequent<S>) -> Vec<Rule<Hypersequent<S>>> {
+        let _ = hypersequent;
+        vec![]
+    }
+}
+
+impl<S: Sequent> Infer<Hypersequent<S>> for InComponent<S>
+where
+    S::Item: InferHyper<S>,
+{
+    #[inline]
+    fn above(&self, context: Hypersequent<S>) -> Vec<Rule<Hypersequent<S>>> {
+        self.item.above_in(self.rest.clone(), &context)
+    }
+}
+
+/// Ordinary sequents side by side, separated by `|`, as in hypersequent calculi.
+#[allow(clippy::exhaustive_structs)]
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Hypersequent<S: Sequent> {
+    /// Every component, i.e. every ordinary sequent between the bars.
+    pub components: Multiset<S>,
+}
+
+impl<S: Sequent> Sequent for Hypersequent<S>
+where
+    S::Item: InferHyper<S>,
+{
+    type Item = InComponent<S>;
+    /// A single component holding just this item (so its `rest` is ignored).
+    #[inline]
+    fn from_rhs(rhs_element: Self::Item) -> Self {
+        Self::new(core::iter::once(S::from_rhs(rhs_element.item)).collect())
+    }
+    #[inline]
+    fn sample(&self) -> impl Iterator<Item = (Self::Item, Self)> + '_ {
+        self.components.iter_unique().flat_map(|(component, _)| {
+            let mut others = self.components.clone();
+            let _ = others.take(component);
+            component.sample().map(move |(item, rest)| {
+                (
+                    InComponent { item, rest },
+                    Self {
+                        components: others.clone(),
+                    },
+                )
+            })
+        })
+    }
+    /// Counts copies of whole components as well as copies of an item within any one component.
+    #[inline]
+    fn most_copies(&self) -> usize {
+        self.components
+            .keys()
+            .map(Sequent::most_copies)
+            .fold(self.components.most_copies(), usize::max)
+    }
+    /// Every item in every component.
+    #[inline]
+    fn width(&self) -> usize {
+        self.components
+            .iter_repeat()
+            .map(Sequent::width)
+            .fold(0, usize::saturating_add)
+    }
+    /// Structural rules within each component, then `InferHyper::external`.
+    #[inline]
+    fn structural(&self) -> Vec<Rule<Self>> {
+        let mut rules: Vec<_> = self
+            .components
+            .iter_unique()
+            .flat_map(|(component, _)| {
+                let mut others = self.components.clone();
+                let _ = others.take(component);
+                Self::new(others).lift(component.structural())
+            })
+            .collect();
+        rules.extend(S::Item::external(self));
+        rules
+    }
+}
+
+impl<S: Sequent> Hypersequent<S> {
+    /// New hypersequent with exactly these components.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(components: Multiset<S>) -> Self {
+        Self { components }
+    }
+
+    /// Total number of components.
+    #[must_use]
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Whether there are no components at all.
+    #[must_use]
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Clone and add components to the clone.
+    #[must_use]
+    #[inline(always)]
+    pub fn with<I: IntoIterator<Item = S>>(&self, additions: I) -> Self {
+        Self {
+            components: self.components.with(additions),
+        }
+    }
+}
+
+impl<S: Sequent> Hypersequent<S>
+where
+    S::Item: InferHyper<S>,
+{
+    /// Turn rules for one ordinary sequent into rules for a hypersequent with these components beside it,
+    /// keeping their names and invertibility.
+    #[must_use]
+    #[inline]
+    pub fn lift(&self, rules: Vec<Rule<S>>) -> Vec<Rule<Self>> {
+        rules
+            .into_iter()
+            .map(|rule| Rule {
+                name: rule.name,
+                above: rule
+                    .above
+                    .into_iter_repeat()
+                    .map(|premise| self.with([premise]))
+                    .collect(),
+                principal: None,
+                invertible: rule.invertible,
+            })
+            .collect()
+    }
+}
+
+impl<S: Sequent> Display for Hypersequent<S> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut iter = self.components.iter_repeat();
+        if let Some(first) = iter.next() {
+            Display::fmt(first, f)?;
+            for next in iter {
+                write!(f, " | ")?;
+                Display::fmt(next, f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl<S: Sequent + quickcheck::Arbitrary> quickcheck::Arbitrary for Hypersequent<S> {
+    #[inline]
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        Self {
+            components: quickcheck::Arbitrary::arbitrary(g),
+        }
+    }
+    #[inline]
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(
+            self.components
+                .shrink()
+                .map(|components| Self { components }),
+        )
+    }
+}
@@ -6,52 +6,104 @@
 
 //! A turnstile symbol with a comma-separated expression on the left and a single expression on the right.
 
-use crate::{Infer, Multiset, Sequent};
+use crate::{
+    sequents::{Side, Sided},
+    Ascii, Infer, MaybeSendSync, Multiset, Rule, Sequent, SequentRenderer, Unicode,
+};
+use alloc::{string::String, vec::Vec};
 use core::{
     fmt::{Debug, Display},
     hash::Hash,
 };
 
+/// A set of sequent-calculus inference rules for expressions on either side of an intuitionistic sequent.
+pub trait InferLj: Clone + Debug + Display + Hash + MaybeSendSync + Ord {
+    /// All possible "next moves" with this expression on the left and `context` everywhere else.
+    fn left(
+        &self,
+        context: IntuitionistWithExchange<Self>,
+    ) -> Vec<Rule<IntuitionistWithExchange<Self>>>;
+    /// All possible "next moves" with this expression on the right.
+    /// Since there's always exactly one expression on the right,
+    /// `context` still has this one there, for each premise to replace with `with_rhs`.
+    fn right(
+        &self,
+        context: IntuitionistWithExchange<Self>,
+    ) -> Vec<Rule<IntuitionistWithExchange<Self>>>;
+}
+
+impl<Item: InferLj> Infer<IntuitionistWithExchange<Item>> for Sided<Item> {
+    #[inline]
+    fn above(
+        &self,
+        context: IntuitionistWithExchange<Item>,
+    ) -> Vec<Rule<IntuitionistWithExchange<Item>>> {
+        match self.side {
+            Side::Left => self.item.left(context),
+            Side::Right => self.item.right(context),
+        }
+    }
+}
+
 /// A turnstile symbol with a comma-separated expression on the left and a single expression on the right.
 #[allow(clippy::exhaustive_structs)]
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct IntuitionistWithExchange<Item: Debug + Display + Hash + Infer<Self> + Ord> {
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct IntuitionistWithExchange<Item: Clone + Debug + Display + Hash + Ord> {
     /// Left side of the turnstile, on which comma means times.
     pub lhs: Multiset<Item>,
     /// Right side of the turnstile, on which comma means par.
     pub rhs: Item,
 }
 
-impl<Item: Debug + Display + Hash + Infer<Self> + Ord> Sequent for IntuitionistWithExchange<Item> {
-    type Item = Item;
+impl<Item: InferLj> Sequent for IntuitionistWithExchange<Item> {
+    type Item = Sided<Item>;
+    /// Always puts the expression on the right, whichever side it's tagged with,
+    /// since there has to be something there.
     #[inline(always)]
-    fn from_rhs(rhs: Self::Item) -> Self {
+    fn from_rhs(rhs_element: Self::Item) -> Self {
         Self {
             lhs: Multiset::new(),
-            rhs,
+            rhs: rhs_element.item,
         }
     }
     #[inline]
-    fn sample(&self) -> Vec<(Self::Item, Self)> {
+    fn sample(&self) -> impl Iterator<Item = (Self::Item, Self)> + '_ {
         self.lhs
             .iter_unique()
-            .map(|(ast, _)| {
+            .map(|(item, _)| {
                 let mut ablation = self.lhs.clone();
-                let _ = ablation.take(ast);
+                let _ = ablation.take(item);
                 (
-                    ast.clone(),
+                    Sided::left(item.clone()),
                     Self {
                         lhs: ablation,
                         rhs: self.rhs.clone(),
                     },
                 )
             })
-            .collect()
+            .chain(core::iter::once_with(|| {
+                (Sided::right(self.rhs.clone()), self.clone())
+            }))
+    }
+    /// Counts only the left-hand side, since the right always holds exactly one item.
+    #[inline]
+    fn most_copies(&self) -> usize {
+        self.lhs.most_copies().max(1)
+    }
+    /// Everything on the left, plus the one formula on the right.
+    #[inline(always)]
+    fn width(&self) -> usize {
+        self.lhs.len().saturating_add(1)
+    }
+    #[inline(always)]
+    fn render_with<R: SequentRenderer>(&self, renderer: &R) -> String {
+        self.render(renderer)
     }
 }
 
-impl<Item: Debug + Display + Hash + Infer<Self> + Ord> IntuitionistWithExchange<Item> {
-    /// New sequent with exactly this on the right-hand side.
+impl<Item: Clone + Debug + Display + Hash + Ord> IntuitionistWithExchange<Item> {
+    /// New sequent with exactly these on either side.
     #[must_use]
     #[inline(always)]
     pub const fn new(lhs: Multiset<Item>, rhs: Item) -> Self {
@@ -60,18 +112,18 @@ impl<Item: Debug + Display + Hash + Infer<Self> + Ord> IntuitionistWithExchange<
     /// Total number of comma-separated expressions, not counting the right-hand side.
     #[must_use]
     #[inline(always)]
-    pub fn len(&self) -> usize {
+    pub const fn len(&self) -> usize {
         self.lhs.len()
     }
 
-    /// Whether there are any statements on either side.
+    /// Whether there are any statements on the left.
     #[must_use]
     #[inline(always)]
     pub fn is_empty(&self) -> bool {
         self.lhs.is_empty()
     }
 
-    /// Clone and insert an element into the clone.
+    /// Clone and insert elements on the left into the clone.
     #[must_use]
     #[inline(always)]
     pub fn with<I: IntoIterator<Item = Item>>(&self, additions: I) -> Self {
@@ -80,27 +132,38 @@ impl<Item: Debug + Display + Hash + Infer<Self> + Ord> IntuitionistWithExchange<
             rhs: self.rhs.clone(),
         }
     }
+
+    /// Clone with something else on the right.
+    #[must_use]
+    #[inline(always)]
+    pub fn with_rhs(&self, rhs: Item) -> Self {
+        Self {
+            lhs: self.lhs.clone(),
+            rhs,
+        }
+    }
+
+    /// Print this sequent with `renderer`'s symbols.
+    #[must_use]
+    #[inline]
+    pub fn render<R: SequentRenderer>(&self, renderer: &R) -> String {
+        renderer.layout(self.lhs.iter_repeat(), [&self.rhs])
+    }
 }
 
-impl<Item: Debug + Display + Hash + Infer<Self> + Ord> Display for IntuitionistWithExchange<Item> {
+impl<Item: Clone + Debug + Display + Hash + Ord> Display for IntuitionistWithExchange<Item> {
     #[inline]
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "\u{22a2}")?;
-        let mut iter = self.lhs.iter_repeat();
-        if let Some(first) = iter.next() {
-            write!(f, "{first}")?;
-            for next in iter {
-                write!(f, ", {next}")?;
-            }
-            write!(f, " \u{22a2} {}", self.rhs)
+        if f.alternate() {
+            f.write_str(&self.render(&Ascii))
         } else {
-            write!(f, "\u{22a2} {}", self.rhs)
+            f.write_str(&self.render(&Unicode))
         }
     }
 }
 
 #[cfg(feature = "quickcheck")]
-impl<Item: Debug + Display + Hash + Infer<Self> + Ord + quickcheck::Arbitrary> quickcheck::Arbitrary
+impl<Item: Clone + Debug + Display + Hash + Ord + quickcheck::Arbitrary> quickcheck::Arbitrary
     for IntuitionistWithExchange<Item>
 {
     #[inline]
@@ -119,3 +182,21 @@ impl<Item: Debug + Display + Hash + Infer<Self> + Ord + quickcheck::Arbitrary> q
         )
     }
 }
+
+#[cfg(feature = "proptest")]
+impl<Item: Clone + Debug + Display + Hash + Ord + proptest::arbitrary::Arbitrary>
+    proptest::arbitrary::Arbitrary for IntuitionistWithExchange<Item>
+{
+    type Parameters = <(Multiset<Item>, Item) as proptest::arbitrary::Arbitrary>::Parameters;
+    type Strategy = proptest::strategy::Map<
+        <(Multiset<Item>, Item) as proptest::arbitrary::Arbitrary>::Strategy,
+        fn((Multiset<Item>, Item)) -> Self,
+    >;
+    #[inline]
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        proptest::strategy::Strategy::prop_map(
+            <(Multiset<Item>, Item) as proptest::arbitrary::Arbitrary>::arbitrary_with(args),
+            |(lhs, rhs)| Self { lhs, rhs },
+        )
+    }
+}
@@ -0,0 +1,278 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! A two-sided sequent of formulas labelled with worlds, plus accessibility between worlds, as in labelled modal calculi.
+
+use crate::{
+    sequents::{Side, Sided},
+    Ascii, Infer, MaybeSendSync, Multiset, Rule, Sequent, SequentRenderer, Unicode,
+};
+use alloc::{string::String, vec, vec::Vec};
+use core::{
+    fmt::{Debug, Display},
+    hash::Hash,
+};
+
+/// Either a formula that holds at a world or an accessibility atom between two worlds.
+#[allow(clippy::exhaustive_enums)]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Element<Item> {
+    /// A formula at a world, written `w: A`.
+    Labelled {
+        /// The world the formula holds at.
+        world: usize,
+        /// The formula itself.
+        formula: Item,
+    },
+    /// The first world can access the second, written `wRv`.
+    Rel(usize, usize),
+}
+
+impl<Item> Element<Item> {
+    /// A formula at a world.
+    #[must_use]
+    #[inline(always)]
+    pub const fn at(world: usize, formula: Item) -> Self {
+        Self::Labelled { world, formula }
+    }
+}
+
+impl<Item: Display> Display for Element<Item> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::Labelled { world, ref formula } => write!(f, "w{world}: {formula}"),
+            Self::Rel(from, to) => write!(f, "w{from}Rw{to}"),
+        }
+    }
+}
+
+/// A set of labelled sequent-calculus inference rules for formulas on either side of a labelled sequent.
+pub trait InferLabelled: Clone + Debug + Display + Hash + MaybeSendSync + Ord {
+    /// All possible "next moves" with this formula on the left at `world` and `context` everywhere else.
+    fn left(&self, world: usize, context: Labelled<Self>) -> Vec<Rule<Labelled<Self>>>;
+    /// All possible "next moves" with this formula on the right at `world` and `context` everywhere else.
+    fn right(&self, world: usize, context: Labelled<Self>) -> Vec<Rule<Labelled<Self>>>;
+}
+
+impl<Item: InferLabelled> Infer<Labelled<Item>> for Sided<Element<Item>> {
+    #[inline]
+    fn above(&self, context: Labelled<Item>) -> Vec<Rule<Labelled<Item>>> {
+        match self.item {
+            Element::Labelled { world, ref formula } => match self.side {
+                Side::Left => formula.left(world, context),
+                Side::Right => formula.right(world, context),
+            },
+            // Never sampled, but nothing to do with one anyway.
+            Element::Rel(..) => vec![],
+        }
+    }
+}
+
+/// A two-sided sequent of formulas labelled with worlds, plus accessibility between worlds, as in labelled modal calculi.
+#[allow(clippy::exhaustive_structs)]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Labelled<Item: Clone + Debug + Display + Hash + Ord> {
+    /// Left side of the turnstile, on which comma means "and."
+    pub lhs: Multiset<Element<Item>>,
+    /// Right side of the turnstile, on which comma means "or."
+    pub rhs: Multiset<Element<Item>>,
+}
+
+impl<Item: Clone + Debug + Display + Hash + Ord> Default for Labelled<Item> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new(Multiset::new(), Multiset::new())
+    }
+}
+
+impl<Item: InferLabelled> Sequent for Labelled<Item> {
+    type Item = Sided<Element<Item>>;
+    #[inline(always)]
+    fn from_rhs(rhs_element: Self::Item) -> Self {
+        match rhs_element.side {
+            Side::Left => Self::default().with_lhs([rhs_element.item]),
+            Side::Right => Self::default().with_rhs([rhs_element.item]),
+        }
+    }
+    /// Only labelled formulas: accessibility atoms are context for them, never anything to act on.
+    #[inline]
+    fn sample(&self) -> impl Iterator<Item = (Self::Item, Self)> + '_ {
+        let lhs = self
+            .lhs
+            .iter_unique()
+            .filter(|&(element, _)| matches!(*element, Element::Labelled { .. }))
+            .map(|(element, _)| {
+                let mut ablation = self.lhs.clone();
+                let _ = ablation.take(element);
+                (
+                    Sided::left(element.clone()),
+                    Self::new(ablation, self.rhs.clone()),
+                )
+            });
+        let rhs = self
+            .rhs
+            .iter_unique()
+            .filter(|&(element, _)| matches!(*element, Element::Labelled { .. }))
+            .map(|(element, _)| {
+                let mut ablation = self.rhs.clone();
+                let _ = ablation.take(element);
+                (
+                    Sided::right(element.clone()),
+                    Self::new(self.lhs.clone(), ablation),
+                )
+            });
+        lhs.chain(rhs)
+    }
+    #[inline]
+    fn most_copies(&self) -> usize {
+        self.lhs.most_copies().max(self.rhs.most_copies())
+    }
+    #[inline(always)]
+    fn width(&self) -> usize {
+        self.lhs.len().saturating_add(self.rhs.len())
+    }
+    #[inline(always)]
+    fn render_with<R: SequentRenderer>(&self, renderer: &R) -> String {
+        self.render(renderer)
+    }
+}
+
+impl<Item: Clone + Debug + Display + Hash + Ord> Labelled<Item> {
+    /// New sequent with exactly these on either side.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(lhs: Multiset<Element<Item>>, rhs: Multiset<Element<Item>>) -> Self {
+        Self { lhs, rhs }
+    }
+
+    /// Total number of comma-separated elements on both sides.
+    #[must_use]
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.lhs.len().saturating_add(self.rhs.len())
+    }
+
+    /// Whether there are any elements on either side.
+    #[must_use]
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.lhs.is_empty() && self.rhs.is_empty()
+    }
+
+    /// Clone and insert elements on the left into the clone.
+    #[must_use]
+    #[inline(always)]
+    pub fn with_lhs<I: IntoIterator<Item = Element<Item>>>(&self, additions: I) -> Self {
+        Self {
+            lhs: self.lhs.with(additions),
+            rhs: self.rhs.clone(),
+        }
+    }
+
+    /// Clone and insert elements on the right into the clone.
+    #[must_use]
+    #[inline(always)]
+    pub fn with_rhs<I: IntoIterator<Item = Element<Item>>>(&self, additions: I) -> Self {
+        Self {
+            lhs: self.lhs.clone(),
+            rhs: self.rhs.with(additions),
+        }
+    }
+
+    /// A world that appears nowhere in this sequent, e.g. for the witness of a box on the right.
+    /// Inside an inference rule, `context` no longer holds the formula being acted on,
+    /// so make sure to skip that formula's world too.
+    #[must_use]
+    #[inline]
+    pub fn fresh_world(&self) -> usize {
+        self.lhs
+            .keys()
+            .chain(self.rhs.keys())
+            .map(|element| match *element {
+                Element::Labelled { world, .. } => world,
+                Element::Rel(from, to) => from.max(to),
+            })
+            .max()
+            .map_or(0, |world| world.saturating_add(1))
+    }
+
+    /// Every world the left side says this world can access.
+    #[inline]
+    pub fn accessible_from(&self, world: usize) -> impl Iterator<Item = usize> + '_ {
+        self.lhs.keys().filter_map(move |element| match *element {
+            Element::Rel(from, to) if from == world => Some(to),
+            Element::Labelled { .. } | Element::Rel(..) => None,
+        })
+    }
+
+    /// Print this sequent with `renderer`'s symbols.
+    #[must_use]
+    #[inline]
+    pub fn render<R: SequentRenderer>(&self, renderer: &R) -> String {
+        renderer.layout(self.lhs.iter_repeat(), self.rhs.iter_repeat())
+    }
+}
+
+impl<Item: Clone + Debug + Display + Hash + Ord> Display for Labelled<Item> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if f.alternate() {
+            f.write_str(&self.render(&Ascii))
+        } else {
+            f.write_str(&self.render(&Unicode))
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl<Item: quickcheck::Arbitrary> quickcheck::Arbitrary for Element<Item> {
+    #[inline]
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        if bool::arbitrary(g) {
+            Self::Labelled {
+                world: usize::arbitrary(g),
+                formula: Item::arbitrary(g),
+            }
+        } else {
+            Self::Rel(usize::arbitrary(g), usize::arbitrary(g))
+        }
+    }
+    #[inline]
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        match *self {
+            Self::Labelled { world, ref formula } => Box::new(
+                (world, formula.clone())
+                    .shrink()
+                    .map(|(w, f)| Self::at(w, f)),
+            ),
+            Self::Rel(from, to) => Box::new((from, to).shrink().map(|(f, t)| Self::Rel(f, t))),
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl<Item: Clone + Debug + Display + Hash + Ord + quickcheck::Arbitrary> quickcheck::Arbitrary
+    for Labelled<Item>
+{
+    #[inline]
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        Self {
+            lhs: quickcheck::Arbitrary::arbitrary(g),
+            rhs: quickcheck::Arbitrary::arbitrary(g),
+        }
+    }
+    #[inline]
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(
+            (self.lhs.clone(), self.rhs.clone())
+                .shrink()
+                .map(|(lhs, rhs)| Self { lhs, rhs }),
+        )
+    }
+}
@@ -0,0 +1,243 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! A turnstile symbol with comma-separated expressions on both sides, as in classical LK.
+
+use crate::{Ascii, Infer, MaybeSendSync, Multiset, Rule, Sequent, SequentRenderer, Unicode};
+use alloc::{string::String, vec::Vec};
+use core::{
+    fmt::{Debug, Display},
+    hash::Hash,
+};
+
+/// Which side of the turnstile an expression is on.
+#[allow(clippy::exhaustive_enums)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Side {
+    /// Left of the turnstile, on which comma means "and."
+    Left,
+    /// Right of the turnstile, on which comma means "or."
+    Right,
+}
+
+/// An expression tagged with the side of the turnstile it came from.
+#[allow(clippy::exhaustive_structs)]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Sided<Item> {
+    /// Which side of the turnstile the expression is on.
+    pub side: Side,
+    /// The expression itself.
+    pub item: Item,
+}
+
+impl<Item> Sided<Item> {
+    /// An expression on the left of the turnstile.
+    #[must_use]
+    #[inline(always)]
+    pub const fn left(item: Item) -> Self {
+        Self {
+            side: Side::Left,
+            item,
+        }
+    }
+
+    /// An expression on the right of the turnstile.
+    #[must_use]
+    #[inline(always)]
+    pub const fn right(item: Item) -> Self {
+        Self {
+            side: Side::Right,
+            item,
+        }
+    }
+}
+
+impl<Item: Display> Display for Sided<Item> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.side {
+            Side::Left => write!(f, "{} \u{22a2}", self.item),
+            Side::Right => write!(f, "\u{22a2} {}", self.item),
+        }
+    }
+}
+
+/// A set of sequent-calculus inference rules for expressions on either side of a two-sided sequent.
+pub trait InferLk: Clone + Debug + Display + Hash + MaybeSendSync + Ord {
+    /// All possible "next moves" with this expression on the left and `context` everywhere else.
+    fn left(&self, context: LkWithExchange<Self>) -> Vec<Rule<LkWithExchange<Self>>>;
+    /// All possible "next moves" with this expression on the right and `context` everywhere else.
+    fn right(&self, context: LkWithExchange<Self>) -> Vec<Rule<LkWithExchange<Self>>>;
+}
+
+impl<Item: InferLk> Infer<LkWithExchange<Item>> for Sided<Item> {
+    #[inline]
+    fn above(&self, context: LkWithExchange<Item>) -> Vec<Rule<LkWithExchange<Item>>> {
+        match self.side {
+            Side::Left => self.item.left(context),
+            Side::Right => self.item.right(context),
+        }
+    }
+}
+
+/// A turnstile symbol with comma-separated expressions on both sides, as in classical LK.
+#[allow(clippy::exhaustive_structs)]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LkWithExchange<Item: Debug + Display + Hash + Ord> {
+    /// Left side of the turnstile, on which comma means "and."
+    pub lhs: Multiset<Item>,
+    /// Right side of the turnstile, on which comma means "or."
+    pub rhs: Multiset<Item>,
+}
+
+impl<Item: Debug + Display + Hash + Ord> Default for LkWithExchange<Item> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new(Multiset::new(), Multiset::new())
+    }
+}
+
+impl<Item: InferLk> Sequent for LkWithExchange<Item> {
+    type Item = Sided<Item>;
+    #[inline(always)]
+    fn from_rhs(rhs_element: Self::Item) -> Self {
+        match rhs_element.side {
+            Side::Left => Self::default().with_lhs([rhs_element.item]),
+            Side::Right => Self::default().with_rhs([rhs_element.item]),
+        }
+    }
+    #[inline]
+    fn sample(&self) -> impl Iterator<Item = (Self::Item, Self)> + '_ {
+        let lhs = self.lhs.iter_unique().map(|(item, _)| {
+            let mut ablation = self.lhs.clone();
+            let _ = ablation.take(item);
+            (
+                Sided::left(item.clone()),
+                Self::new(ablation, self.rhs.clone()),
+            )
+        });
+        let rhs = self.rhs.iter_unique().map(|(item, _)| {
+            let mut ablation = self.rhs.clone();
+            let _ = ablation.take(item);
+            (
+                Sided::right(item.clone()),
+                Self::new(self.lhs.clone(), ablation),
+            )
+        });
+        lhs.chain(rhs)
+    }
+    #[inline]
+    fn most_copies(&self) -> usize {
+        self.lhs.most_copies().max(self.rhs.most_copies())
+    }
+    #[inline(always)]
+    fn width(&self) -> usize {
+        self.lhs.len().saturating_add(self.rhs.len())
+    }
+    /// Each addition goes on whichever side it names.
+    #[inline]
+    fn alongside<A: IntoIterator<Item = Self::Item>>(&self, additions: A) -> Option<Self> {
+        let (lhs, rhs): (Vec<_>, Vec<_>) = additions
+            .into_iter()
+            .partition(|addition| addition.side == Side::Left);
+        Some(Self::new(
+            self.lhs.with(lhs.into_iter().map(|addition| addition.item)),
+            self.rhs.with(rhs.into_iter().map(|addition| addition.item)),
+        ))
+    }
+    #[inline(always)]
+    fn render_with<R: SequentRenderer>(&self, renderer: &R) -> String {
+        self.render(renderer)
+    }
+}
+
+impl<Item: Debug + Display + Hash + Ord> LkWithExchange<Item> {
+    /// New sequent with exactly these on either side.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(lhs: Multiset<Item>, rhs: Multiset<Item>) -> Self {
+        Self { lhs, rhs }
+    }
+
+    /// Total number of comma-separated expressions on both sides.
+    #[must_use]
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.lhs.len().saturating_add(self.rhs.len())
+    }
+
+    /// Whether there are any statements on either side.
+    #[must_use]
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.lhs.is_empty() && self.rhs.is_empty()
+    }
+
+    /// Clone and insert elements on the left into the clone.
+    #[must_use]
+    #[inline(always)]
+    pub fn with_lhs<I: IntoIterator<Item = Item>>(&self, additions: I) -> Self
+    where
+        Item: Clone,
+    {
+        Self {
+            lhs: self.lhs.with(additions),
+            rhs: self.rhs.clone(),
+        }
+    }
+
+    /// Clone and insert elements on the right into the clone.
+    #[must_use]
+    #[inline(always)]
+    pub fn with_rhs<I: IntoIterator<Item = Item>>(&self, additions: I) -> Self
+    where
+        Item: Clone,
+    {
+        Self {
+            lhs: self.lhs.clone(),
+            rhs: self.rhs.with(additions),
+        }
+    }
+
+    /// Print this sequent with `renderer`'s symbols.
+    #[must_use]
+    #[inline]
+    pub fn render<R: SequentRenderer>(&self, renderer: &R) -> String {
+        renderer.layout(self.lhs.iter_repeat(), self.rhs.iter_repeat())
+    }
+}
+
+impl<Item: Debug + Display + Hash + Ord> Display for LkWithExchange<Item> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if f.alternate() {
+            f.write_str(&self.render(&Ascii))
+        } else {
+            f.write_str(&self.render(&Unicode))
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl<Item: Debug + Display + Hash + Ord + quickcheck::Arbitrary> quickcheck::Arbitrary
+    for LkWithExchange<Item>
+{
+    #[inline]
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        Self {
+            lhs: quickcheck::Arbitrary::arbitrary(g),
+            rhs: quickcheck::Arbitrary::arbitrary(g),
+        }
+    }
+    #[inline]
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(
+            (self.lhs.clone(), self.rhs.clone())
+                .shrink()
+                .map(|(lhs, rhs)| Self { lhs, rhs }),
+        )
+    }
+}
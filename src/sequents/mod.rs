@@ -6,10 +6,20 @@
 
 //! Various common sequent structures to avoid reinventing the wheel.
 
+mod hypersequent;
 mod intuitionist_with_exchange;
+mod labelled;
+mod lk_with_exchange;
+mod rhs_only_ordered;
+mod rhs_only_structural;
 mod rhs_only_with_exchange;
 
 pub use {
-    intuitionist_with_exchange::IntuitionistWithExchange,
+    hypersequent::{Hypersequent, InComponent, InferHyper},
+    intuitionist_with_exchange::{InferLj, IntuitionistWithExchange},
+    labelled::{Element, InferLabelled, Labelled},
+    lk_with_exchange::{InferLk, LkWithExchange, Side, Sided},
+    rhs_only_ordered::RhsOnlyOrdered,
+    rhs_only_structural::RhsOnlyStructural,
     rhs_only_with_exchange::RhsOnlyWithExchange,
 };
@@ -0,0 +1,148 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! A turnstile symbol with an ordered list of comma-separated expressions on the right and no exchange.
+
+use crate::{Ascii, Infer, Sequent, SequentRenderer, Unicode};
+use alloc::{string::String, vec, vec::Vec};
+use core::{
+    fmt::{Debug, Display},
+    hash::Hash,
+};
+
+/// A turnstile symbol with an ordered list of comma-separated expressions on the right and no exchange.
+///
+/// The only reordering allowed is rotation (as in cyclic linear logic),
+/// so each sampled expression sits, in effect, at the end of what remains.
+#[allow(clippy::exhaustive_structs)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct RhsOnlyOrdered<Item: Debug + Display + Hash + Infer<Self> + Ord> {
+    /// Right side of the turnstile, on which comma means par.
+    pub rhs: Vec<Item>,
+}
+
+impl<Item: Debug + Display + Hash + Infer<Self> + Ord> PartialOrd for RhsOnlyOrdered<Item> {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<Item: Debug + Display + Hash + Infer<Self> + Ord> Ord for RhsOnlyOrdered<Item> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.rhs
+            .len()
+            .cmp(&other.rhs.len())
+            .then_with(|| self.rhs.cmp(&other.rhs))
+    }
+}
+
+impl<Item: Debug + Display + Hash + Infer<Self> + Ord> Sequent for RhsOnlyOrdered<Item> {
+    type Item = Item;
+    #[inline(always)]
+    fn from_rhs(rhs_element: Self::Item) -> Self {
+        Self {
+            rhs: vec![rhs_element],
+        }
+    }
+    #[inline]
+    fn sample(&self) -> impl Iterator<Item = (Self::Item, Self)> + '_ {
+        self.rhs.iter().enumerate().map(|(i, item)| {
+            let (before, after) = self.rhs.split_at(i);
+            (
+                item.clone(),
+                Self {
+                    rhs: after.iter().skip(1).chain(before).cloned().collect(),
+                },
+            )
+        })
+    }
+    #[inline(always)]
+    fn width(&self) -> usize {
+        self.rhs.len()
+    }
+    #[inline(always)]
+    fn render_with<R: SequentRenderer>(&self, renderer: &R) -> String {
+        self.render(renderer)
+    }
+}
+
+impl<Item: Debug + Display + Hash + Infer<Self> + Ord> RhsOnlyOrdered<Item> {
+    /// New sequent with exactly this on the right-hand side.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(rhs: Vec<Item>) -> Self {
+        Self { rhs }
+    }
+
+    /// Total number of comma-separated expressions.
+    #[must_use]
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.rhs.len()
+    }
+
+    /// Whether there are any statements on either side.
+    #[must_use]
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.rhs.is_empty()
+    }
+
+    /// Clone and append elements, in order, to the end of the clone.
+    #[must_use]
+    #[inline(always)]
+    pub fn with<I: IntoIterator<Item = Item>>(&self, additions: I) -> Self {
+        Self {
+            rhs: self.rhs.iter().cloned().chain(additions).collect(),
+        }
+    }
+
+    /// Every way to cut this list in two without reordering anything,
+    /// as `(prefix, suffix)` pairs from an empty prefix to an empty suffix.
+    #[inline]
+    pub fn splits(&self) -> impl Iterator<Item = (Self, Self)> + '_ {
+        (0..=self.rhs.len()).map(|i| {
+            let (prefix, suffix) = self.rhs.split_at(i);
+            (Self::new(prefix.to_vec()), Self::new(suffix.to_vec()))
+        })
+    }
+
+    /// Print this sequent with `renderer`'s symbols.
+    #[must_use]
+    #[inline]
+    pub fn render<R: SequentRenderer>(&self, renderer: &R) -> String {
+        renderer.layout(core::iter::empty::<&Item>(), &self.rhs)
+    }
+}
+
+impl<Item: Debug + Display + Hash + Infer<Self> + Ord> Display for RhsOnlyOrdered<Item> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if f.alternate() {
+            f.write_str(&self.render(&Ascii))
+        } else {
+            f.write_str(&self.render(&Unicode))
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl<Item: Debug + Display + Hash + Infer<Self> + Ord + quickcheck::Arbitrary> quickcheck::Arbitrary
+    for RhsOnlyOrdered<Item>
+{
+    #[inline]
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        Self {
+            rhs: quickcheck::Arbitrary::arbitrary(g),
+        }
+    }
+    #[inline]
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(self.rhs.shrink().map(|rhs| Self { rhs }))
+    }
+}
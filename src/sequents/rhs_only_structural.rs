@@ -0,0 +1,204 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! A turnstile symbol with comma-separated expressions on the right and optional weakening and contraction.
+
+use crate::{Ascii, Infer, Multiset, Rule, Sequent, SequentRenderer, Subsumes, Unicode};
+use alloc::{string::String, vec, vec::Vec};
+use core::{
+    fmt::{Debug, Display},
+    hash::Hash,
+};
+
+/// A turnstile symbol with comma-separated expressions on the right and optional weakening and contraction.
+///
+/// With neither, this is linear; with only weakening, affine; with only contraction, relevant.
+#[allow(clippy::exhaustive_structs)]
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct RhsOnlyStructural<
+    Item: Debug + Display + Hash + Infer<Self> + Ord,
+    const WEAKENING: bool,
+    const CONTRACTION: bool,
+> {
+    /// Right side of the turnstile, on which comma means par.
+    pub rhs: Multiset<Item>,
+}
+
+impl<
+        Item: Debug + Display + Hash + Infer<Self> + Ord,
+        const WEAKENING: bool,
+        const CONTRACTION: bool,
+    > Sequent for RhsOnlyStructural<Item, WEAKENING, CONTRACTION>
+{
+    type Item = Item;
+    #[inline(always)]
+    fn from_rhs(rhs_element: Self::Item) -> Self {
+        let mut rhs = Multiset::new();
+        let _ = rhs.insert(rhs_element);
+        Self { rhs }
+    }
+    #[inline]
+    fn sample(&self) -> impl Iterator<Item = (Self::Item, Self)> + '_ {
+        self.rhs.iter_unique().map(|(ast, _)| {
+            let mut ablation = self.rhs.clone();
+            let _ = ablation.take(ast);
+            (ast.clone(), Self { rhs: ablation })
+        })
+    }
+    #[inline]
+    fn structural(&self) -> Vec<Rule<Self>> {
+        let mut rules = vec![];
+        for (ast, _) in self.rhs.iter_unique() {
+            if WEAKENING {
+                let mut ablation = self.rhs.clone();
+                let _ = ablation.take(ast);
+                rules.push(Rule::new("W", [Self { rhs: ablation }]));
+            }
+            if CONTRACTION {
+                rules.push(Rule::new("C", [self.with([ast.clone()])]));
+            }
+        }
+        rules
+    }
+    #[inline(always)]
+    fn most_copies(&self) -> usize {
+        self.rhs.most_copies()
+    }
+    #[inline(always)]
+    fn width(&self) -> usize {
+        self.rhs.len()
+    }
+    #[inline(always)]
+    fn alongside<A: IntoIterator<Item = Self::Item>>(&self, additions: A) -> Option<Self> {
+        Some(self.with(additions))
+    }
+    #[inline]
+    fn cut(&self, lemma: &Self::Item, dual: &Self::Item) -> Vec<Rule<Self>> {
+        self.rhs
+            .partitions()
+            .map(|(lhs, rhs)| {
+                Rule::new(
+                    "cut",
+                    [
+                        Self {
+                            rhs: lhs.with([lemma.clone()]),
+                        },
+                        Self {
+                            rhs: rhs.with([dual.clone()]),
+                        },
+                    ],
+                )
+            })
+            .collect()
+    }
+    #[inline(always)]
+    fn render_with<R: SequentRenderer>(&self, renderer: &R) -> String {
+        self.render(renderer)
+    }
+}
+
+/// With weakening, a sequent subsumes any with at least as many copies of each item,
+/// or with contraction as well, any with at least one copy of each.
+impl<Item: Debug + Display + Hash + Infer<Self> + Ord, const CONTRACTION: bool> Subsumes
+    for RhsOnlyStructural<Item, true, CONTRACTION>
+{
+    #[inline]
+    fn subsumes(&self, other: &Self) -> bool {
+        self.rhs.iter_unique().all(|(item, copies)| {
+            if CONTRACTION {
+                other.rhs.contains(item)
+            } else {
+                other.rhs.count(item) >= copies.get()
+            }
+        })
+    }
+}
+
+impl<
+        Item: Debug + Display + Hash + Infer<Self> + Ord,
+        const WEAKENING: bool,
+        const CONTRACTION: bool,
+    > RhsOnlyStructural<Item, WEAKENING, CONTRACTION>
+{
+    /// New sequent with exactly this on the right-hand side.
+    #[must_use]
+    #[inline(always)]
+    pub const fn new(rhs: Multiset<Item>) -> Self {
+        Self { rhs }
+    }
+
+    /// Total number of comma-separated expressions.
+    #[must_use]
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.rhs.len()
+    }
+
+    /// Whether there are any statements on either side.
+    #[must_use]
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.rhs.is_empty()
+    }
+
+    /// Clone and insert an element into the clone.
+    #[must_use]
+    #[inline(always)]
+    pub fn with<I: IntoIterator<Item = Item>>(&self, additions: I) -> Self {
+        Self {
+            rhs: self.rhs.with(additions),
+        }
+    }
+
+    /// If this collection has exactly one element, view it without taking it out.
+    #[must_use]
+    #[inline(always)]
+    pub fn only(&self) -> Option<&Item> {
+        self.rhs.only()
+    }
+
+    /// Print this sequent with `renderer`'s symbols.
+    #[must_use]
+    #[inline]
+    pub fn render<R: SequentRenderer>(&self, renderer: &R) -> String {
+        renderer.layout(core::iter::empty::<&Item>(), self.rhs.iter_repeat())
+    }
+}
+
+impl<
+        Item: Debug + Display + Hash + Infer<Self> + Ord,
+        const WEAKENING: bool,
+        const CONTRACTION: bool,
+    > Display for RhsOnlyStructural<Item, WEAKENING, CONTRACTION>
+{
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if f.alternate() {
+            f.write_str(&self.render(&Ascii))
+        } else {
+            f.write_str(&self.render(&Unicode))
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl<
+        Item: Debug + Display + Hash + Infer<Self> + Ord + quickcheck::Arbitrary,
+        const WEAKENING: bool,
+        const CONTRACTION: bool,
+    > quickcheck::Arbitrary for RhsOnlyStructural<Item, WEAKENING, CONTRACTION>
+{
+    #[inline]
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        Self {
+            rhs: quickcheck::Arbitrary::arbitrary(g),
+        }
+    }
+    #[inline]
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(self.rhs.shrink().map(|rhs| Self { rhs }))
+    }
+}
@@ -6,7 +6,8 @@
 
 //! A turnstile symbol with comma-separated expressions on either (but currently just one) side.
 
-use crate::{Infer, Multiset, Sequent};
+use crate::{Ascii, Infer, Multiset, Rule, Sequent, SequentRenderer, Unicode};
+use alloc::{string::String, vec::Vec};
 use core::{
     fmt::{Debug, Display},
     hash::Hash,
@@ -15,6 +16,7 @@ use core::{
 /// A turnstile symbol with comma-separated expressions on either (but currently just one) side.
 #[allow(clippy::exhaustive_structs)]
 #[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RhsOnlyWithExchange<Item: Debug + Display + Hash + Infer<Self> + Ord> {
     /// Right side of the turnstile, on which comma means par.
     pub rhs: Multiset<Item>,
@@ -43,16 +45,52 @@ impl<Item: Debug + Display + Hash + Infer<Self> + Ord> Sequent for RhsOnlyWithEx
         Self { rhs }
     }
     #[inline]
-    fn sample(&self) -> Vec<(Self::Item, Self)> {
+    fn sample(&self) -> impl Iterator<Item = (Self::Item, Self)> + '_ {
+        self.rhs.iter_unique().map(|(ast, _)| {
+            let mut ablation = self.rhs.clone();
+            let _ = ablation.take(ast);
+            (ast.clone(), Self { rhs: ablation })
+        })
+    }
+    #[inline(always)]
+    fn most_copies(&self) -> usize {
+        self.rhs.most_copies()
+    }
+    #[inline(always)]
+    fn width(&self) -> usize {
+        self.rhs.len()
+    }
+    #[inline(always)]
+    fn alongside<A: IntoIterator<Item = Self::Item>>(&self, additions: A) -> Option<Self> {
+        Some(self.with(additions))
+    }
+    #[inline(always)]
+    fn closed(&self) -> Option<&'static str> {
+        Item::initial(self)
+    }
+    #[inline]
+    fn cut(&self, lemma: &Self::Item, dual: &Self::Item) -> Vec<Rule<Self>> {
         self.rhs
-            .iter_unique()
-            .map(|(ast, _)| {
-                let mut ablation = self.rhs.clone();
-                let _ = ablation.take(ast);
-                (ast.clone(), Self { rhs: ablation })
+            .partitions()
+            .map(|(lhs, rhs)| {
+                Rule::new(
+                    "cut",
+                    [
+                        Self {
+                            rhs: lhs.with([lemma.clone()]),
+                        },
+                        Self {
+                            rhs: rhs.with([dual.clone()]),
+                        },
+                    ],
+                )
             })
             .collect()
     }
+    #[inline(always)]
+    fn render_with<R: SequentRenderer>(&self, renderer: &R) -> String {
+        self.render(renderer)
+    }
 }
 
 impl<Item: Debug + Display + Hash + Infer<Self> + Ord> RhsOnlyWithExchange<Item> {
@@ -65,7 +103,7 @@ impl<Item: Debug + Display + Hash + Infer<Self> + Ord> RhsOnlyWithExchange<Item>
     /// Total number of comma-separated expressions.
     #[must_use]
     #[inline(always)]
-    pub fn len(&self) -> usize {
+    pub const fn len(&self) -> usize {
         // self.lhs.len() +
         self.rhs.len()
     }
@@ -93,25 +131,53 @@ impl<Item: Debug + Display + Hash + Infer<Self> + Ord> RhsOnlyWithExchange<Item>
         self.rhs.only()
     }
 
+    /// Whether every comma-separated expression satisfies a predicate (vacuously true if empty).
+    #[inline(always)]
+    pub fn all<F: FnMut(&Item) -> bool>(&self, f: F) -> bool {
+        self.rhs.all(f)
+    }
+
+    /// Find two expressions such that `dual(first, second)` holds, trying both orders for each pair,
+    /// so it doesn't matter which one comes first. Two copies of one expression count as two,
+    /// but no copy is ever paired with itself.
+    #[inline]
+    pub fn dual_pair<F: FnMut(&Item, &Item) -> bool>(&self, mut dual: F) -> Option<(&Item, &Item)> {
+        let mut iter = self.rhs.iter_repeat();
+        while let Some(first) = iter.next() {
+            for second in iter.clone() {
+                if dual(first, second) {
+                    return Some((first, second));
+                }
+                if dual(second, first) {
+                    return Some((second, first));
+                }
+            }
+        }
+        None
+    }
+
     /// Take an element by decreasing its count if we can.
     #[inline(always)]
     pub fn take(&mut self, element: &Item) -> bool {
         self.rhs.take(element)
     }
+
+    /// Print this sequent with `renderer`'s symbols.
+    #[must_use]
+    #[inline]
+    pub fn render<R: SequentRenderer>(&self, renderer: &R) -> String {
+        renderer.layout(core::iter::empty::<&Item>(), self.rhs.iter_repeat())
+    }
 }
 
 impl<Item: Debug + Display + Hash + Infer<Self> + Ord> Display for RhsOnlyWithExchange<Item> {
     #[inline]
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "\u{22a2}")?;
-        let mut iter = self.rhs.iter_repeat();
-        if let Some(first) = iter.next() {
-            write!(f, " {first}")?;
-            for next in iter {
-                write!(f, ", {next}")?;
-            }
+        if f.alternate() {
+            f.write_str(&self.render(&Ascii))
+        } else {
+            f.write_str(&self.render(&Unicode))
         }
-        Ok(())
     }
 }
 
@@ -130,3 +196,21 @@ impl<Item: Debug + Display + Hash + Infer<Self> + Ord + quickcheck::Arbitrary> q
         Box::new(self.rhs.shrink().map(|rhs| Self { rhs }))
     }
 }
+
+#[cfg(feature = "proptest")]
+impl<Item: Debug + Display + Hash + Infer<Self> + Ord + proptest::arbitrary::Arbitrary>
+    proptest::arbitrary::Arbitrary for RhsOnlyWithExchange<Item>
+{
+    type Parameters = <Multiset<Item> as proptest::arbitrary::Arbitrary>::Parameters;
+    type Strategy = proptest::strategy::Map<
+        <Multiset<Item> as proptest::arbitrary::Arbitrary>::Strategy,
+        fn(Multiset<Item>) -> Self,
+    >;
+    #[inline]
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        proptest::strategy::Strategy::prop_map(
+            <Multiset<Item> as proptest::arbitrary::Arbitrary>::arbitrary_with(args),
+            Self::new,
+        )
+    }
+}
@@ -0,0 +1,149 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Proof search that remembers everything it's proven from one goal to the next.
+
+use crate::{
+    paused::Paused,
+    proof::{resume, Budget},
+    thunk::Thunk,
+    Error, SearchConfig, SearchStats, Sequent, Tree,
+};
+
+#[cfg(feature = "persist")]
+use {
+    crate::{persist::validated, PersistError, Rule},
+    alloc::vec::Vec,
+};
+
+/// Proof search that remembers everything it's proven from one goal to the next,
+/// so closely related goals can share the work of proving common subgoals.
+#[derive(Clone, Debug)]
+pub struct ProverState<S: Sequent> {
+    /// Everything we've seen and proven so far, across every goal.
+    queue: Thunk<S>,
+    /// Inferences still waiting on proofs of the sequents above them.
+    paused: Paused<S>,
+}
+
+impl<S: Sequent> Default for ProverState<S> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Sequent> ProverState<S> {
+    /// Start with nothing proven.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            queue: Thunk::empty(None),
+            paused: Paused::new(),
+        }
+    }
+
+    /// Start with nothing proven, tuned by `config`.
+    #[inline]
+    #[must_use]
+    pub fn with_config(config: SearchConfig<S>) -> Self {
+        Self {
+            queue: Thunk::empty_with_config(config),
+            paused: Paused::new(),
+        }
+    }
+
+    /// Attempt to prove this expression, reusing anything proven by earlier calls.
+    /// # Errors
+    /// If we can't.
+    #[inline]
+    pub fn prove(&mut self, expr: S::Item) -> Result<Tree<S>, Error> {
+        let goal = S::from_rhs(expr);
+        if self.queue.set_original(goal.clone()).is_err() {
+            if let Some(proof) = self.queue.proven(&goal) {
+                return Ok(Tree::connect(goal, proof.clone(), &self.queue));
+            }
+        }
+        resume(
+            &goal,
+            &mut self.queue,
+            &mut self.paused,
+            Budget::UNLIMITED,
+            &mut (),
+            &(),
+        )
+    }
+
+    /// How many sequents we've proven so far, across every goal.
+    #[inline]
+    #[must_use]
+    pub fn cached_sequents(&self) -> usize {
+        self.queue.proven_count()
+    }
+
+    /// How much work we've done so far, across every goal.
+    #[inline(always)]
+    #[must_use]
+    pub const fn stats(&self) -> SearchStats {
+        self.queue.stats()
+    }
+
+    /// Write everything we've proven so far (each sequent and the rule that proved it) as compact JSON,
+    /// sorted by sequent so the same proofs always save the same way.
+    /// # Errors
+    /// If `writer` does.
+    #[inline]
+    #[cfg(feature = "persist")]
+    pub fn save<W: std::io::Write>(&self, writer: W) -> Result<(), PersistError<S>>
+    where
+        S: serde::Serialize,
+        S::Item: serde::Serialize,
+    {
+        let mut entries: Vec<(&S, &Rule<S>)> = self.queue.proven_entries().collect();
+        entries.sort_unstable_by_key(|&(sequent, _)| sequent);
+        serde_json::to_writer(writer, &entries)?;
+        Ok(())
+    }
+
+    /// Start with everything `save` wrote already proven,
+    /// after checking every inference against the rules, since the file could have come from anywhere.
+    /// # Errors
+    /// If `reader` does, if it doesn't hold a saved cache,
+    /// or if any saved inference doesn't follow or depends on something never proven.
+    #[inline]
+    #[cfg(feature = "persist")]
+    pub fn load<R: std::io::Read>(reader: R) -> Result<Self, PersistError<S>>
+    where
+        S: serde::de::DeserializeOwned,
+        S::Item: serde::de::DeserializeOwned,
+    {
+        Self::load_with_config(reader, SearchConfig::new())
+    }
+
+    /// Start with everything `save` wrote already proven, tuned by `config`,
+    /// after checking every inference against the rules (and against `config.subsumes` for weakening).
+    /// # Errors
+    /// If `reader` does, if it doesn't hold a saved cache,
+    /// or if any saved inference doesn't follow or depends on something never proven.
+    #[inline]
+    #[cfg(feature = "persist")]
+    pub fn load_with_config<R: std::io::Read>(
+        reader: R,
+        config: SearchConfig<S>,
+    ) -> Result<Self, PersistError<S>>
+    where
+        S: serde::de::DeserializeOwned,
+        S::Item: serde::de::DeserializeOwned,
+    {
+        let entries: Vec<(S, Rule<S>)> = serde_json::from_reader(reader)?;
+        let mut state = Self::with_config(config);
+        for (sequent, proof) in validated(entries, state.queue.subsumes())? {
+            state.queue.restore(sequent, proof);
+        }
+        Ok(state)
+    }
+}
@@ -0,0 +1,46 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Counters describing how much work a proof search did.
+
+/// Counters describing how much work a proof search did.
+#[allow(clippy::exhaustive_structs)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SearchStats {
+    /// Sequents added to the queue for the first time.
+    pub enqueued: usize,
+    /// Sequents taken out of the queue to be expanded.
+    pub dequeued: usize,
+    /// Sequents we tried to add to the queue but had already seen.
+    pub cache_hits: usize,
+    /// Inferences any rule offered for a sequent taken out of the queue,
+    /// including duplicates and any dropped in favor of an invertible rule.
+    pub rules_generated: usize,
+    /// Inferences generated and set aside until their premises are proven.
+    pub inferences_paused: usize,
+    /// Paused inferences whose premises were all proven.
+    pub inferences_discharged: usize,
+    /// Longest the queue ever got.
+    pub peak_queue_len: usize,
+    /// Times we checked whether one premise of a paused inference was proven.
+    pub premise_checks: usize,
+    /// Most inferences ever set aside at once.
+    pub peak_paused: usize,
+    /// Paused inferences dropped because something else proved their conclusion first.
+    pub inferences_pruned: usize,
+    /// Sequents never queued for holding more copies of one item than `SearchConfig::max_copies` allows.
+    pub over_copy_cap: usize,
+    /// Sequents never queued for holding more items than `SearchConfig::max_width` allows.
+    pub over_width_cap: usize,
+    /// Sequents proven by weakening a proven sequent that subsumes them instead of being expanded.
+    pub subsumed: usize,
+}
+
+/// Increment a counter, saturating instead of overflowing.
+#[inline(always)]
+pub(crate) const fn bump(counter: &mut usize) {
+    *counter = counter.saturating_add(1);
+}
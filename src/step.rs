@@ -0,0 +1,45 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! One inference at a time, chosen by hand instead of by proof search.
+//!
+//! Choices are numbered in a fixed order: items in the order `Sequent::sample` yields them,
+//! then rules in the order `Infer::above` returns them.
+//! Every bundled sequent samples its items in sorted order (left side first, and `RhsOnlyOrdered` as written),
+//! so as long as `above` is deterministic, the same sequent always numbers the same choices the same way.
+//! Only rules that come from an item are numbered:
+//! an initial sequent (see `Sequent::closed`) closes with `Rule::new(name, [])` instead,
+//! and structural rules come from `Sequent::structural`.
+
+use crate::{parallel::acting_on, Infer, Rule, Sequent};
+use alloc::vec::Vec;
+
+/// Every item in `goal` alongside every inference it offers with everything else as context,
+/// each rule marked as acting on that item, in the fixed order `step` numbers them.
+#[inline]
+#[must_use]
+pub fn apply<I: Infer<S>, S: Sequent<Item = I>>(goal: &S) -> Vec<(I, Vec<Rule<S>>)> {
+    goal.sample()
+        .map(|(item, context)| {
+            let rules = acting_on(&item, context);
+            (item, rules)
+        })
+        .collect()
+}
+
+/// The inference numbered `rule_index` among those the item numbered `focus_index` offers in `goal`
+/// (both counting from zero, in the order `apply` lists them),
+/// or `None` if there's no such item or rule.
+#[inline]
+#[must_use]
+pub fn step<I: Infer<S>, S: Sequent<Item = I>>(
+    goal: &S,
+    focus_index: usize,
+    rule_index: usize,
+) -> Option<Rule<S>> {
+    let (item, context) = goal.sample().nth(focus_index)?;
+    acting_on(&item, context).into_iter().nth(rule_index)
+}
@@ -0,0 +1,70 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Which sequent proof search expands next.
+
+use crate::{parallel::Shared, MaybeSendSync, Sequent};
+
+/// Score for `SearchStrategy::Custom`, shareable across threads with the `parallel` feature.
+#[cfg(feature = "parallel")]
+pub type Priority<S> = Shared<dyn Fn(&S) -> u64 + Send + Sync>;
+
+/// Score for `SearchStrategy::Custom`, shareable across threads with the `parallel` feature.
+#[cfg(not(feature = "parallel"))]
+pub type Priority<S> = Shared<dyn Fn(&S) -> u64>;
+
+/// Which sequent proof search expands next.
+#[non_exhaustive]
+#[derive(Clone)]
+pub enum SearchStrategy<S: Sequent> {
+    /// Smallest sequent first, according to its `Ord` implementation (the default).
+    SmallestFirst,
+    /// Oldest sequent first, i.e. breadth-first search.
+    Fifo,
+    /// Newest sequent first, i.e. depth-first search.
+    Lifo,
+    /// Lowest priority first, breaking ties oldest first.
+    Custom(Priority<S>),
+}
+
+impl<S: Sequent> Default for SearchStrategy<S> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::SmallestFirst
+    }
+}
+
+impl<S: Sequent> core::fmt::Debug for SearchStrategy<S> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::SmallestFirst => write!(f, "SmallestFirst"),
+            Self::Fifo => write!(f, "Fifo"),
+            Self::Lifo => write!(f, "Lifo"),
+            Self::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+impl<S: Sequent> SearchStrategy<S> {
+    /// Expand whichever sequent `priority` scores lowest first, breaking ties oldest first.
+    #[inline]
+    #[must_use]
+    pub fn custom<F: Fn(&S) -> u64 + MaybeSendSync + 'static>(priority: F) -> Self {
+        Self::Custom(Shared::new(priority))
+    }
+
+    /// Key to order the queue by (lowest first), given how many sequents we've queued before this one.
+    #[inline]
+    pub(crate) fn key(&self, sequent: &S, order: u64) -> (u64, u64) {
+        match *self {
+            Self::SmallestFirst => (0, 0),
+            Self::Fifo => (order, 0),
+            Self::Lifo => (u64::MAX.saturating_sub(order), 0),
+            Self::Custom(ref priority) => (priority(sequent), order),
+        }
+    }
+}
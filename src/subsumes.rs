@@ -0,0 +1,21 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! Sequents that know when one follows from another by weakening.
+
+use crate::Sequent;
+
+/// Sequents that know when one follows from another by weakening,
+/// so proof search (with `SearchConfig::subsuming`) never re-derives a sequent
+/// that's just a proven one with more context.
+pub trait Subsumes: Sequent {
+    /// Whether `other` follows from `self` by weakening alone.
+    ///
+    /// Proof search looks only at proven sequents whose every item is also in `other`,
+    /// so this must never hold otherwise.
+    #[must_use]
+    fn subsumes(&self, other: &Self) -> bool;
+}
@@ -7,18 +7,1038 @@
 #![allow(
     clippy::arithmetic_side_effects,
     clippy::default_numeric_fallback,
-    clippy::panic
+    clippy::panic,
+    clippy::unwrap_used
 )]
 
 #[cfg(feature = "quickcheck")]
 quickcheck::quickcheck! {
-    // fn trace_eq_implies_equal_hashes(a: Trace, b: Trace) -> bool {
-    //     eq_implies_hash(&a, &b)
-    // }
+    fn multiset_union_matches_vec(a: Multiset<u8>, b: Multiset<u8>) -> bool {
+        let mut model = to_vec(&a);
+        model.extend(to_vec(&b));
+        model.sort_unstable();
+        to_vec(&a.union(&b)) == model
+    }
 
-    // fn split_eq_implies_equal_hashes(a: Split, b: Split) -> bool {
-    //     eq_implies_hash(&a, &b)
-    // }
+    fn multiset_extend_from_matches_union(a: Multiset<u8>, b: Multiset<u8>) -> bool {
+        let mut ms = a.clone();
+        ms.extend_from(&b);
+        ms == a.union(&b)
+    }
+
+    fn multiset_intersection_matches_vec(a: Multiset<u8>, b: Multiset<u8>) -> bool {
+        let mut rest = to_vec(&b);
+        let model: Vec<_> = to_vec(&a)
+            .into_iter()
+            .filter(|x| {
+                rest.iter()
+                    .position(|y| y == x)
+                    .map(|i| rest.remove(i))
+                    .is_some()
+            })
+            .collect();
+        to_vec(&a.intersection(&b)) == model
+    }
+
+    fn multiset_difference_matches_vec(a: Multiset<u8>, b: Multiset<u8>) -> bool {
+        let mut model = to_vec(&a);
+        for x in to_vec(&b) {
+            if let Some(i) = model.iter().position(|&y| y == x) {
+                let _ = model.remove(i);
+            }
+        }
+        to_vec(&a.difference(&b)) == model
+    }
+
+    fn multiset_algebra_has_no_zero_counts(a: Multiset<u8>, b: Multiset<u8>) -> bool {
+        [a.union(&b), a.intersection(&b), a.difference(&b)]
+            .iter()
+            .all(|ms| ms.iter_unique().all(|(_, n)| n.get() > 0) && ms.len() == ms.iter_repeat().count())
+    }
+
+    fn multiset_cached_len_matches_counts(start: Multiset<u8>, ops: Vec<(u8, u8)>) -> bool {
+        let mut ms = start;
+        ops.into_iter().all(|(op, element)| {
+            match op & 7 {
+                0..=2 => {
+                    let _ = ms.insert(element);
+                }
+                3 => {
+                    let _ = ms.take(&element);
+                }
+                4 => {
+                    let _ = ms.remove_all(&element);
+                }
+                5 => ms.retain(|&x| x != element),
+                _ => ms = ms.union(&ms.with([element])).difference(&ms),
+            }
+            ms.len() == ms.iter_unique().map(|(_, n)| n.get()).sum::<usize>()
+        })
+    }
+
+    fn rule_eq_implies_equal_hashes(a: Rule<Tiny>, b: Rule<Tiny>) -> bool {
+        eq_implies_hash(&a, &b) && eq_implies_hash(&a, &a.clone())
+    }
+
+    fn rule_cmp_consistent_with_eq(a: Rule<Tiny>, b: Rule<Tiny>) -> bool {
+        cmp_consistent_with_eq(&a, &b)
+    }
+
+    fn inference_eq_implies_equal_hashes(a: Inference<Tiny>, b: Inference<Tiny>) -> bool {
+        eq_implies_hash(&a, &b) && eq_implies_hash(&a, &a.clone())
+    }
+
+    fn inference_cmp_consistent_with_eq(a: Inference<Tiny>, b: Inference<Tiny>) -> bool {
+        let same_rule = Inference { rule: a.rule.clone(), below: Shared::clone(&b.below) };
+        cmp_consistent_with_eq(&a, &b) && cmp_consistent_with_eq(&a, &same_rule)
+    }
+
+    fn arbitrary_rules_have_names(rule: Rule<Tiny>) -> bool {
+        !rule.name.is_empty()
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+use crate::{inference::Inference, parallel::Shared, sequents::RhsOnlyWithExchange, Infer, Rule};
+
+#[cfg(feature = "proptest")]
+proptest::proptest! {
+    #[test]
+    fn proptest_multiset_len_counts_every_copy(ms in proptest::prelude::any::<Multiset<u8>>()) {
+        proptest::prop_assert_eq!(ms.len(), ms.iter_repeat().count());
+    }
+}
+
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+use crate::Multiset;
+
+// Only the serialization tests use it.
+#[cfg(not(feature = "serde"))]
+use serde_json as _;
+
+// Only the benchmarks use it.
+use criterion as _;
+
+/// Flatten a multiset into a sorted vector, duplicates included.
+#[cfg(feature = "quickcheck")]
+fn to_vec<T: Clone + Ord>(ms: &Multiset<T>) -> Vec<T> {
+    ms.iter_repeat().cloned().collect()
+}
+
+mod multiset {
+    use crate::Multiset;
+    use core::num::NonZeroUsize;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn count_absent_is_zero() {
+        let ms: Multiset<u8> = Multiset::new();
+        assert_eq!(ms.count(&0), 0);
+    }
+
+    #[test]
+    fn count_tracks_insert_and_take() {
+        let mut ms = Multiset::new();
+        assert_eq!(ms.insert(7_u8).get(), 1);
+        assert_eq!(ms.insert(7).get(), 2);
+        assert_eq!(ms.count(&7), 2);
+        assert!(ms.take(&7));
+        assert_eq!(ms.count(&7), 1);
+        assert!(ms.take(&7));
+        assert_eq!(ms.count(&7), 0);
+        assert!(!ms.take(&7));
+        assert_eq!(ms.count(&7), 0);
+    }
+
+    #[test]
+    fn most_copies_of_any_element() {
+        assert_eq!(Multiset::<u8>::new().most_copies(), 0);
+        let ms: Multiset<u8> = [1, 2, 2, 3, 3, 3, 4].into_iter().collect();
+        assert_eq!(ms.most_copies(), 3);
+    }
+
+    #[test]
+    fn taking_last_copy_removes_key() {
+        let mut ms: Multiset<u8> = core::iter::once(3).collect();
+        assert!(ms.take(&3));
+        assert!(!ms.contains(&3));
+        assert!(ms.is_empty());
+        assert_eq!(ms, Multiset::new());
+    }
+
+    #[test]
+    fn clones_share_until_changed() {
+        let ms: Multiset<u8> = [1, 2, 2].into_iter().collect();
+        let mut clone = ms.clone();
+        assert!(std::sync::Arc::ptr_eq(&ms.map, &clone.map));
+        assert!(clone.take(&2));
+        assert!(!std::sync::Arc::ptr_eq(&ms.map, &clone.map));
+        assert_eq!(ms.count(&2), 2);
+        assert_eq!(clone.count(&2), 1);
+        assert_eq!(ms.with([3]).count(&3), 1);
+        assert!(!ms.contains(&3));
+    }
+
+    #[test]
+    fn partitions_of_empty() {
+        let ms: Multiset<u8> = Multiset::new();
+        assert_eq!(
+            ms.partitions().collect::<Vec<_>>(),
+            vec![(Multiset::new(), Multiset::new())],
+        );
+        assert_eq!(ms.unordered_partitions().count(), 1);
+    }
+
+    #[test]
+    fn partitions_are_distinct_and_complementary() {
+        let ms: Multiset<char> = "aab".chars().collect();
+        let splits: Vec<_> = ms.partitions().collect();
+        assert_eq!(splits.len(), 6);
+        assert_eq!(splits.iter().collect::<BTreeSet<_>>().len(), 6);
+        for &(ref lhs, ref rhs) in &splits {
+            assert_eq!(&lhs.union(rhs), &ms);
+        }
+    }
+
+    #[test]
+    fn unordered_partitions_skip_mirrors() {
+        let ms: Multiset<char> = "aab".chars().collect();
+        let splits: Vec<_> = ms.unordered_partitions().collect();
+        assert_eq!(splits.len(), 3);
+        for &(ref lhs, ref rhs) in &splits {
+            assert!(!splits.contains(&(rhs.clone(), lhs.clone())));
+        }
+    }
+
+    #[test]
+    fn unordered_partitions_keep_self_mirrors() {
+        let ms: Multiset<char> = "aabb".chars().collect();
+        // 3 * 3 = 9 ordered splits, one of which ({a, b}, {a, b}) is its own mirror.
+        assert_eq!(ms.partitions().count(), 9);
+        assert_eq!(ms.unordered_partitions().count(), 5);
+    }
+
+    #[test]
+    fn partitions_of_huge_multiset() {
+        let mut ms = Multiset::new();
+        let _ = ms.insert_n(None, NonZeroUsize::new(1_000).unwrap());
+        assert_eq!(ms.partitions().count(), 1_001);
+        assert_eq!(ms.unordered_partitions().count(), 501);
+        for i in 0..100_u8 {
+            let _ = ms.insert(Some(i));
+        }
+        assert_eq!(ms.partitions().take(10).count(), 10);
+    }
+
+    #[test]
+    fn display_empty() {
+        let ms: Multiset<char> = Multiset::new();
+        assert_eq!(format!("{ms}"), "{ }");
+        assert_eq!(format!("{ms:#}"), "{ }");
+    }
+
+    #[test]
+    fn display_singleton() {
+        let ms: Multiset<char> = "A".chars().collect();
+        assert_eq!(format!("{ms}"), "{ A }");
+        assert_eq!(format!("{ms:#}"), "{ A }");
+    }
+
+    #[test]
+    fn display_multiple() {
+        let ms: Multiset<char> = "BABCB".chars().collect();
+        assert_eq!(format!("{ms}"), "{ A, 3\u{d7}B, C }");
+        assert_eq!(format!("{ms:#}"), "{ A, B, B, B, C }");
+        let doubled: Multiset<char> = "BAABCB".chars().collect();
+        assert_eq!(format!("{doubled}"), "{ 2\u{d7}A, 3\u{d7}B, C }");
+    }
+
+    #[test]
+    fn remove_all_removes_every_copy() {
+        let mut ms: Multiset<char> = "abbb".chars().collect();
+        assert_eq!(ms.remove_all(&'b'), 3);
+        assert!(!ms.contains(&'b'));
+        assert_eq!(ms.remove_all(&'b'), 0);
+        assert_eq!(ms, "a".chars().collect());
+    }
+
+    #[test]
+    fn retain_drops_whole_keys() {
+        let mut ms: Multiset<char> = "aAbBBc".chars().collect();
+        ms.retain(char::is_ascii_uppercase);
+        assert_eq!(ms, "ABB".chars().collect());
+        assert_eq!(ms.len(), 3);
+        ms.retain(|_| false);
+        assert!(ms.is_empty());
+    }
+
+    #[test]
+    fn all_checks_every_element() {
+        let ms: Multiset<char> = "ABB".chars().collect();
+        assert!(ms.all(char::is_ascii_uppercase));
+        assert!(!ms.all(|&c| c == 'A'));
+        assert!(Multiset::<char>::new().all(|_| false));
+    }
+
+    #[test]
+    fn exactly_counts_duplicates() {
+        let empty: Multiset<char> = Multiset::new();
+        assert_eq!(empty.exactly(), Some([]));
+        assert_eq!(empty.exactly::<1>(), None);
+        let ms: Multiset<char> = "BAB".chars().collect();
+        assert_eq!(ms.exactly::<0>(), None);
+        assert_eq!(ms.exactly::<2>(), None);
+        assert_eq!(ms.exactly(), Some([&'A', &'B', &'B']));
+        assert_eq!(ms.exactly::<4>(), None);
+        let four: Multiset<char> = "CCCC".chars().collect();
+        assert_eq!(four.exactly(), Some([&'C'; 4]));
+        assert_eq!(four.only(), None);
+        assert_eq!(four.pair(), None);
+    }
+
+    #[test]
+    fn only_and_pair_respect_multiplicity() {
+        let one: Multiset<char> = "A".chars().collect();
+        assert_eq!(one.only(), Some(&'A'));
+        assert_eq!(one.pair(), None);
+        let two: Multiset<char> = "AA".chars().collect();
+        assert_eq!(two.only(), None);
+        assert_eq!(two.pair(), Some((&'A', &'A')));
+    }
+
+    #[test]
+    fn extend_adds_every_copy() {
+        let mut ms: Multiset<char> = "AB".chars().collect();
+        ms.extend("BBC".chars());
+        assert_eq!(ms, "ABBBC".chars().collect());
+        assert_eq!(ms.len(), 5);
+    }
+
+    #[test]
+    fn counted_pairs_sum_duplicates() {
+        let two = NonZeroUsize::new(2).unwrap();
+        let ms: Multiset<char> = [('a', two), ('b', NonZeroUsize::MIN), ('a', two)]
+            .into_iter()
+            .collect();
+        assert_eq!(ms, "aaaab".chars().collect());
+        assert_eq!(ms.len(), 5);
+    }
+
+    #[test]
+    fn counts_round_trip() {
+        let ms: Multiset<char> = "BABCB".chars().collect();
+        let counts = ms.counts().clone();
+        assert_eq!(counts.get(&'B').map(|n| n.get()), Some(3));
+        let rebuilt = Multiset::from(counts);
+        assert_eq!(rebuilt, ms);
+        assert_eq!(rebuilt.len(), 5);
+        assert_eq!(rebuilt.into_counts(), *ms.counts());
+        assert_eq!(
+            ms.counts()
+                .iter()
+                .map(|(&c, &n)| (c, n))
+                .collect::<Multiset<char>>(),
+            ms,
+        );
+    }
+
+    /// Counts how many times it's been cloned on this thread.
+    #[derive(Debug, Eq, Ord, PartialEq, PartialOrd)]
+    struct CountClones(u8);
+
+    std::thread_local! {
+        /// Clones of `CountClones` made so far on this thread.
+        static CLONES: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+    }
+
+    impl Clone for CountClones {
+        #[inline]
+        fn clone(&self) -> Self {
+            CLONES.with(|clones| clones.set(clones.get().saturating_add(1)));
+            Self(self.0)
+        }
+    }
+
+    #[test]
+    fn borrowed_iteration_matches_iter_repeat() {
+        let ms: Multiset<char> = "BABCB".chars().collect();
+        let mut borrowed = vec![];
+        for c in &ms {
+            borrowed.push(c);
+        }
+        assert_eq!(borrowed, ms.iter_repeat().collect::<Vec<_>>());
+        assert_eq!(borrowed, [&'A', &'B', &'B', &'B', &'C']);
+        assert_eq!(ms.iter().len(), 5);
+        assert_eq!(ms.keys().collect::<Vec<_>>(), [&'A', &'B', &'C']);
+    }
+
+    #[test]
+    fn borrowed_iteration_never_clones() {
+        let mut ms = Multiset::new();
+        let _ = ms.insert_n(CountClones(1), NonZeroUsize::new(3).unwrap());
+        let _ = ms.insert(CountClones(0));
+        let before = CLONES.with(core::cell::Cell::get);
+        assert_eq!((&ms).into_iter().map(|c| c.0).sum::<u8>(), 3);
+        assert_eq!(ms.keys().count(), 2);
+        assert_eq!(CLONES.with(core::cell::Cell::get), before);
+    }
+
+    #[test]
+    fn insert_n_adds_copies() {
+        let mut ms = Multiset::new();
+        let three = NonZeroUsize::new(3).unwrap();
+        assert_eq!(ms.insert_n('a', three).get(), 3);
+        assert_eq!(ms.insert('a').get(), 4);
+        assert_eq!(ms.insert_n('a', three).get(), 7);
+        assert_eq!(ms.count(&'a'), 7);
+        assert_eq!(ms.len(), 7);
+        assert_eq!(ms, core::iter::repeat_n('a', 7).collect());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serializes_as_counts() {
+        let ms: Multiset<u8> = [3, 1, 3].into_iter().collect();
+        let json = serde_json::to_string(&ms).unwrap();
+        assert_eq!(json, "[[1,1],[3,2]]");
+        assert_eq!(serde_json::from_str::<Multiset<u8>>(&json).unwrap(), ms);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn rejects_zero_counts() {
+        drop(serde_json::from_str::<Multiset<u8>>("[[1,0]]").unwrap_err());
+    }
+}
+
+mod search {
+    use crate::{
+        paused::Paused,
+        proof::{resume, Budget},
+        prove, prove_dfs, prove_guided, prove_or_explain, prove_with_config, prove_with_lemmas,
+        prove_with_limit,
+        sequents::RhsOnlyWithExchange,
+        thunk::{CacheError, Qed, Thunk},
+        Error, Heuristic, Infer, Multiset, Rule, SearchConfig, SearchEvent, Sequent as _, Tree,
+    };
+
+    /// Formula whose only rule claims a premise that nothing can ever prove.
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    struct Liar;
+
+    impl core::fmt::Display for Liar {
+        #[inline]
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "liar")
+        }
+    }
+
+    impl Infer<RhsOnlyWithExchange<Self>> for Liar {
+        #[inline]
+        fn above(
+            &self,
+            context: RhsOnlyWithExchange<Self>,
+        ) -> Vec<Rule<RhsOnlyWithExchange<Self>>> {
+            vec![Rule::new("lie", [context])]
+        }
+    }
+
+    #[test]
+    fn never_seen_is_unproven() {
+        let thunk = Thunk::new(RhsOnlyWithExchange::from_rhs(Liar));
+        assert!(thunk.proven(&RhsOnlyWithExchange::from_rhs(Liar)).is_none());
+        assert!(thunk
+            .proven(&RhsOnlyWithExchange::new(Multiset::new()))
+            .is_none());
+    }
+
+    #[test]
+    fn caching_twice_keeps_the_first_proof() {
+        let goal = RhsOnlyWithExchange::from_rhs(Liar);
+        let empty = RhsOnlyWithExchange::new(Multiset::new());
+        let mut thunk = Thunk::new(goal.clone());
+        thunk.extend_above(&goal, [empty.clone()], 0);
+        assert!(matches!(
+            thunk.cache(empty.clone(), Rule::new("first", [])),
+            Ok(None)
+        ));
+        assert!(matches!(
+            thunk.cache(empty.clone(), Rule::new("second", [])),
+            Ok(None)
+        ));
+        assert_eq!(thunk.proven(&empty).map(|rule| &*rule.name), Some("first"));
+        let finished = |result| match result {
+            Ok(Some(Qed { proof })) => Some(proof.name),
+            Ok(None) | Err(_) => None,
+        };
+        assert_eq!(
+            finished(thunk.cache(goal.clone(), Rule::new("lie", [empty.clone()]))).as_deref(),
+            Some("lie"),
+        );
+        assert_eq!(
+            finished(thunk.cache(goal, Rule::new("again", [empty]))).as_deref(),
+            Some("lie"),
+        );
+    }
+
+    #[test]
+    fn caching_the_never_seen_hands_the_proof_back() {
+        let mut thunk = Thunk::new(RhsOnlyWithExchange::from_rhs(Liar));
+        let stranger = RhsOnlyWithExchange::new(Multiset::new());
+        let Err(CacheError { sequent, proof }) = thunk.cache(stranger.clone(), Rule::new("1", []))
+        else {
+            panic!("cached a sequent we had never seen");
+        };
+        assert_eq!(sequent, stranger);
+        assert_eq!(proof.name, "1");
+        assert!(thunk.proven(&stranger).is_none());
+    }
+
+    #[test]
+    fn unprovable_premise_runs_out_of_paths() {
+        assert_eq!(
+            prove::<_, RhsOnlyWithExchange<Liar>>(Liar),
+            Err(Error::RanOutOfPaths),
+        );
+    }
+
+    /// Formula whose only rule claims it follows from itself.
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    struct Loop;
+
+    impl core::fmt::Display for Loop {
+        #[inline]
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "loop")
+        }
+    }
+
+    impl Infer<RhsOnlyWithExchange<Self>> for Loop {
+        #[inline]
+        fn above(&self, _: RhsOnlyWithExchange<Self>) -> Vec<Rule<RhsOnlyWithExchange<Self>>> {
+            vec![Rule::new("again", [RhsOnlyWithExchange::from_rhs(Self)])]
+        }
+    }
+
+    #[test]
+    fn dfs_detects_loops() {
+        assert_eq!(
+            prove_dfs::<_, RhsOnlyWithExchange<Loop>>(Loop, usize::MAX),
+            Err(Error::RanOutOfPaths),
+        );
+        assert_eq!(
+            prove_dfs::<_, RhsOnlyWithExchange<Liar>>(Liar, usize::MAX),
+            Err(Error::RanOutOfPaths),
+        );
+    }
+
+    /// Two different sequents that each follow from the same single premise.
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    enum Fork {
+        /// Needs both branches.
+        Root,
+        /// Follows from the base.
+        Left,
+        /// Also follows from the base.
+        Right,
+        /// Provable outright.
+        Base,
+    }
+
+    impl core::fmt::Display for Fork {
+        #[inline]
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            match *self {
+                Self::Root => write!(f, "root"),
+                Self::Left => write!(f, "left"),
+                Self::Right => write!(f, "right"),
+                Self::Base => write!(f, "base"),
+            }
+        }
+    }
+
+    impl Infer<RhsOnlyWithExchange<Self>> for Fork {
+        #[inline]
+        fn above(&self, _: RhsOnlyWithExchange<Self>) -> Vec<Rule<RhsOnlyWithExchange<Self>>> {
+            match *self {
+                Self::Root => vec![Rule::new(
+                    "both",
+                    [
+                        RhsOnlyWithExchange::from_rhs(Self::Left),
+                        RhsOnlyWithExchange::from_rhs(Self::Right),
+                    ],
+                )],
+                Self::Left | Self::Right => vec![Rule::new(
+                    "branch",
+                    [RhsOnlyWithExchange::from_rhs(Self::Base)],
+                )],
+                Self::Base => vec![Rule::new("base", [])],
+            }
+        }
+    }
+
+    /// Two ways in, either of which works.
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    enum Maze {
+        /// Provable from either door.
+        Entrance,
+        /// Provable outright, and smaller, so searched first by default.
+        Near,
+        /// Also provable outright.
+        Far,
+    }
+
+    impl core::fmt::Display for Maze {
+        #[inline]
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            match *self {
+                Self::Entrance => write!(f, "entrance"),
+                Self::Near => write!(f, "near"),
+                Self::Far => write!(f, "far"),
+            }
+        }
+    }
+
+    impl Infer<RhsOnlyWithExchange<Self>> for Maze {
+        #[inline]
+        fn above(&self, _: RhsOnlyWithExchange<Self>) -> Vec<Rule<RhsOnlyWithExchange<Self>>> {
+            match *self {
+                Self::Entrance => vec![
+                    Rule::new("near door", [RhsOnlyWithExchange::from_rhs(Self::Near)]),
+                    Rule::new("far door", [RhsOnlyWithExchange::from_rhs(Self::Far)]),
+                ],
+                Self::Near | Self::Far => vec![Rule::new("exit", [])],
+            }
+        }
+    }
+
+    /// Every sequent a guided search takes out of the queue, in order, and the proof it finds.
+    fn guided_order<G: Heuristic<RhsOnlyWithExchange<Maze>>>(
+        heuristic: &G,
+    ) -> (
+        Vec<RhsOnlyWithExchange<Maze>>,
+        Tree<RhsOnlyWithExchange<Maze>>,
+    ) {
+        let goal = RhsOnlyWithExchange::from_rhs(Maze::Entrance);
+        let mut events = vec![];
+        let proof = resume(
+            &goal,
+            &mut Thunk::new(goal.clone()),
+            &mut Paused::new(),
+            Budget::UNLIMITED,
+            &mut events,
+            heuristic,
+        )
+        .unwrap();
+        let order = events
+            .into_iter()
+            .filter_map(|event| match event {
+                SearchEvent::Dequeued(sequent) => Some(sequent),
+                SearchEvent::Paused { .. } | SearchEvent::Proved { .. } => None,
+            })
+            .collect();
+        (order, proof)
+    }
+
+    #[test]
+    fn unguided_heuristic_changes_nothing() {
+        assert_eq!(prove_guided(Maze::Entrance, &()), prove(Maze::Entrance));
+        assert_eq!(prove_guided(Fork::Root, &()), prove(Fork::Root));
+        assert_eq!(
+            prove_guided::<_, RhsOnlyWithExchange<Liar>, _>(Liar, &()),
+            Err(Error::RanOutOfPaths),
+        );
+    }
+
+    #[test]
+    fn adversarial_heuristic_reorders_without_changing_provability() {
+        let (unguided, proof) = guided_order(&());
+        assert_eq!(proof.rule, "near door");
+        let (guided, other_proof) =
+            guided_order(&|_: &RhsOnlyWithExchange<Maze>, rule: &Rule<_>| {
+                if rule.name == "far door" {
+                    1
+                } else {
+                    -1
+                }
+            });
+        assert_eq!(other_proof.rule, "far door");
+        assert_ne!(unguided, guided);
+        assert_eq!(
+            guided.get(1),
+            Some(&RhsOnlyWithExchange::from_rhs(Maze::Far))
+        );
+        other_proof.validate().unwrap();
+    }
+
+    /// Counts down to zero, naming each step after where it started.
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    struct Countdown(u8);
+
+    impl core::fmt::Display for Countdown {
+        #[inline]
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "t-{}", self.0)
+        }
+    }
+
+    impl Infer<RhsOnlyWithExchange<Self>> for Countdown {
+        #[inline]
+        fn above(&self, _: RhsOnlyWithExchange<Self>) -> Vec<Rule<RhsOnlyWithExchange<Self>>> {
+            vec![self.0.checked_sub(1).map_or_else(
+                || Rule::new("liftoff", []),
+                |next| {
+                    Rule::new(
+                        format!("tick {}", self.0),
+                        [RhsOnlyWithExchange::from_rhs(Self(next))],
+                    )
+                },
+            )]
+        }
+    }
+
+    #[test]
+    fn generated_rule_names_survive_into_the_tree() {
+        let tree = prove::<_, RhsOnlyWithExchange<Countdown>>(Countdown(3)).unwrap();
+        assert_eq!(tree.rule, "tick 3");
+        let printed = tree.to_string();
+        for name in ["tick 3", "tick 2", "tick 1", "liftoff"] {
+            assert!(printed.contains(name), "{name} missing from {printed}");
+        }
+    }
+
+    /// Counts down to zero, where nothing can go off.
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    struct Fuse(u8);
+
+    impl core::fmt::Display for Fuse {
+        #[inline]
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "fuse {}", self.0)
+        }
+    }
+
+    impl Infer<RhsOnlyWithExchange<Self>> for Fuse {
+        #[inline]
+        fn above(&self, _: RhsOnlyWithExchange<Self>) -> Vec<Rule<RhsOnlyWithExchange<Self>>> {
+            self.0.checked_sub(1).map_or_else(Vec::new, |next| {
+                vec![Rule::new(
+                    "burn",
+                    [RhsOnlyWithExchange::from_rhs(Self(next))],
+                )]
+            })
+        }
+    }
+
+    #[test]
+    fn failure_report_traces_each_dead_end_to_the_original() {
+        let report = prove_or_explain::<_, RhsOnlyWithExchange<Fuse>>(Fuse(2)).unwrap_err();
+        let path: Vec<_> = (0..=2)
+            .rev()
+            .map(|i| RhsOnlyWithExchange::from_rhs(Fuse(i)))
+            .collect();
+        let dead_end = RhsOnlyWithExchange::from_rhs(Fuse(0));
+        assert_eq!(report.dead_ends, [dead_end.clone()].into());
+        assert_eq!(report.paths.get(&dead_end), Some(&path));
+        assert_eq!(
+            report.to_string(),
+            "\u{22a2} fuse 2 \u{27f6} \u{22a2} fuse 1 \u{27f6} \u{22a2} fuse 0\n"
+        );
+    }
+
+    #[test]
+    fn same_premises_different_conclusions() {
+        let tree = prove::<_, RhsOnlyWithExchange<Fork>>(Fork::Root).unwrap();
+        assert_eq!(tree.rule, "both");
+        assert_eq!(tree.above.len(), 2);
+    }
+
+    /// Needs the same premise three times over.
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    enum Triple {
+        /// Provable from three copies of `Base`.
+        Root,
+        /// Provable outright.
+        Base,
+    }
+
+    impl core::fmt::Display for Triple {
+        #[inline]
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            match *self {
+                Self::Root => write!(f, "root"),
+                Self::Base => write!(f, "base"),
+            }
+        }
+    }
+
+    impl Infer<RhsOnlyWithExchange<Self>> for Triple {
+        #[inline]
+        fn above(&self, _: RhsOnlyWithExchange<Self>) -> Vec<Rule<RhsOnlyWithExchange<Self>>> {
+            match *self {
+                Self::Root => vec![Rule::new(
+                    "thrice",
+                    core::iter::repeat_n(RhsOnlyWithExchange::from_rhs(Self::Base), 3),
+                )],
+                Self::Base => vec![Rule::axiom("base")],
+            }
+        }
+    }
+
+    #[test]
+    fn every_copy_of_a_premise_keeps_its_proof() {
+        let base = RhsOnlyWithExchange::from_rhs(Triple::Base);
+        for mut tree in [
+            prove::<_, RhsOnlyWithExchange<Triple>>(Triple::Root).unwrap(),
+            prove_dfs::<_, RhsOnlyWithExchange<Triple>>(Triple::Root, usize::MAX).unwrap(),
+        ] {
+            tree.validate().unwrap();
+            assert_eq!(tree.rule, "thrice");
+            assert_eq!(tree.above.len(), 3);
+            assert!(tree
+                .above
+                .iter()
+                .all(|premise| premise.below == base && premise.rule == "base"));
+            let before = tree.clone();
+            tree.expand_shared();
+            assert_eq!(tree, before);
+        }
+    }
+
+    /// Also run with `--no-default-features`, where the default hasher comes from `hashbrown`.
+    #[test]
+    fn search_without_std() {
+        let tree = prove::<_, RhsOnlyWithExchange<Fork>>(Fork::Root).unwrap();
+        assert_eq!(tree.rule, "both");
+        assert_eq!(prove_with_config(Fork::Root, SearchConfig::new()), Ok(tree));
+        assert_eq!(
+            prove_with_config::<_, RhsOnlyWithExchange<Liar>, _>(Liar, SearchConfig::new()),
+            Err(Error::RanOutOfPaths),
+        );
+    }
+
+    /// Long ladder with a bridge over it, for lemmas to shortcut.
+    /// Bridges sort first, so the search tries them before climbing any further.
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    enum Ladder {
+        /// Provable alongside only the anchor.
+        Bridge,
+        /// Provable alongside any single rung.
+        CoBridge,
+        /// Where the ladder is fixed, with no rules of its own.
+        Anchor,
+        /// Provable alongside the anchor only by climbing down one rung at a time.
+        Rung(u16),
+        /// Provable by anchoring a ladder this tall.
+        Start(u16),
+    }
+
+    impl core::fmt::Display for Ladder {
+        #[inline]
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            match *self {
+                Self::Bridge => write!(f, "bridge"),
+                Self::CoBridge => write!(f, "~bridge"),
+                Self::Anchor => write!(f, "anchor"),
+                Self::Rung(n) => write!(f, "rung {n}"),
+                Self::Start(n) => write!(f, "start {n}"),
+            }
+        }
+    }
+
+    impl Infer<RhsOnlyWithExchange<Self>> for Ladder {
+        #[inline]
+        fn above(
+            &self,
+            context: RhsOnlyWithExchange<Self>,
+        ) -> Vec<Rule<RhsOnlyWithExchange<Self>>> {
+            match *self {
+                Self::Start(n) => vec![Rule::new(
+                    "start",
+                    [context.with([Self::Anchor, Self::Rung(n)])],
+                )],
+                Self::Rung(0) | Self::Bridge if context.only() == Some(&Self::Anchor) => {
+                    vec![Rule::new("base", [])]
+                }
+                Self::Rung(n @ 1..) => vec![Rule::new(
+                    "climb",
+                    [context.with([Self::Rung(n.saturating_sub(1))])],
+                )],
+                Self::CoBridge if matches!(context.only(), Some(&Self::Rung(_))) => {
+                    vec![Rule::new("jump", [])]
+                }
+                Self::Bridge | Self::CoBridge | Self::Anchor | Self::Rung(_) => vec![],
+            }
+        }
+    }
+
+    #[test]
+    fn lemma_shortcuts_search() {
+        let goal = Ladder::Start(1_000);
+        assert_eq!(
+            prove_with_limit::<_, RhsOnlyWithExchange<Ladder>>(goal.clone(), 100),
+            Err(Error::LimitExceeded { explored: 100 }),
+        );
+        let tree =
+            prove_with_lemmas::<_, RhsOnlyWithExchange<Ladder>, _>(goal, &[Ladder::Bridge], |_| {
+                Ladder::CoBridge
+            })
+            .unwrap();
+        assert_eq!(tree.above.first().unwrap().rule, "cut");
+        assert_eq!(tree.depth(), 3);
+    }
+}
+
+mod tree {
+    use crate::{sequents::RhsOnlyWithExchange, Infer, Rule, Sequent as _, Tree};
+
+    /// Formula that only ever appears in trees built by hand.
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    struct Step(u32);
+
+    impl core::fmt::Display for Step {
+        #[inline]
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "s{}", self.0)
+        }
+    }
+
+    impl Infer<RhsOnlyWithExchange<Self>> for Step {
+        #[inline]
+        fn above(&self, _: RhsOnlyWithExchange<Self>) -> Vec<Rule<RhsOnlyWithExchange<Self>>> {
+            vec![]
+        }
+    }
+
+    /// Single chain of inference lines, this many deep.
+    fn chain(depth: u32) -> Tree<RhsOnlyWithExchange<Step>> {
+        (1..depth).fold(
+            Tree {
+                above: vec![],
+                rule: "start".into(),
+                principal: None,
+                below: RhsOnlyWithExchange::from_rhs(Step(0)),
+            },
+            |tree, i| Tree {
+                above: core::iter::once(tree).collect(),
+                rule: "step".into(),
+                principal: None,
+                below: RhsOnlyWithExchange::from_rhs(Step(i)),
+            },
+        )
+    }
+
+    #[test]
+    fn drop_deep_tree() {
+        drop(chain(100_000));
+    }
+
+    #[test]
+    fn display_deep_tree() {
+        let tree = chain(100_000);
+        let printed = tree.to_string();
+        assert_eq!(printed.lines().count(), 200_001);
+        assert_eq!(tree.depth(), 100_000);
+    }
+
+    #[test]
+    fn diff_deep_tree() {
+        assert!(chain(100_000).diff(&chain(100_000)).is_none());
+        let renamed = chain(100_000).map_rules(|name| {
+            if name == "start" {
+                "begin".into()
+            } else {
+                name
+            }
+        });
+        let diff = chain(100_000).diff(&renamed).unwrap();
+        assert_eq!(diff.path.len(), 99_999);
+        assert_eq!(
+            (diff.left_rule.as_ref(), diff.right_rule.as_ref()),
+            ("start", "begin")
+        );
+    }
+
+    #[test]
+    fn map_deep_tree() {
+        let tree = chain(100_000).map(|sequent| sequent).map_rules(|name| name);
+        assert_eq!(tree.depth(), 100_000);
+        assert_eq!(tree.rule, "step");
+    }
+}
+
+mod term {
+    use crate::Term;
+
+    #[test]
+    fn alpha_normalized_skips_free_variables() {
+        // λ7. 3 7, with 3 free.
+        let term = Term::lam(7, Term::Var(3).app(Term::Var(7)));
+        assert_eq!(
+            term.alpha_normalized(),
+            Term::lam(4, Term::Var(3).app(Term::Var(4)))
+        );
+        assert_eq!(term.alpha_normalized().to_string(), "\u{3bb}e. d e");
+    }
+
+    #[test]
+    fn display_parenthesizes_only_where_needed() {
+        let (a, b, c) = (Term::Var(0), Term::Var(1), Term::Var(2));
+        let nested = a.clone().app(b.clone()).app(c.clone().app(a.clone()));
+        assert_eq!(nested.to_string(), "a b (c a)");
+        let applied = Term::lam(0, a.clone()).app(Term::Fst(Box::new(b)));
+        assert_eq!(applied.to_string(), "(\u{3bb}a. a) (fst b)");
+        let case = Term::Case(
+            Box::new(c),
+            0,
+            Box::new(Term::lam(1, a.clone())),
+            1,
+            Box::new(Term::lam(0, a)),
+        );
+        assert_eq!(
+            case.to_string(),
+            "case c of inl a \u{21d2} (\u{3bb}b. a) | inr b \u{21d2} \u{3bb}a. a"
+        );
+    }
+}
+
+#[cfg(feature = "bench-internals")]
+mod bench {
+    use crate::{bench, prove, sequents::RhsOnlyWithExchange, Error};
+
+    #[test]
+    fn provable_families_prove() {
+        for n in 1..4 {
+            for formula in [
+                bench::with_chain(n),
+                bench::times_chain(n),
+                bench::lollipop_nesting(n),
+            ] {
+                prove::<_, RhsOnlyWithExchange<_>>(formula.clone())
+                    .unwrap_or_else(|e| panic!("{formula}: {e}"))
+                    .validate()
+                    .unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn unprovable_family_fails() {
+        for n in 0..3 {
+            assert_eq!(
+                prove::<_, RhsOnlyWithExchange<_>>(bench::unprovable(n)).map(|_| ()),
+                Err(Error::RanOutOfPaths)
+            );
+        }
+    }
+
+    #[test]
+    fn push_all_counts_each_sequent_once() {
+        let context = RhsOnlyWithExchange::new(crate::Multiset::new());
+        let mut sequents = bench::sequents(5, &context);
+        sequents.extend(bench::sequents(3, &context));
+        assert_eq!(bench::push_all(sequents), 5);
+    }
 }
 
 mod reduced {
@@ -52,68 +1072,38 @@ mod reduced {
     //     println!("[{post}]");
     //     assert_eq!(post.sorted(), Ok(()));
     // }
+}
 
-    // #[test]
-    // #[allow(unsafe_code)]
-    // fn split_swap_still_equal_hashes_1() {
-    //     use crate::{
-    //         {Split, Trace},
-    //         Ast, Multiset, trace,
-    //     };
-    //     use core::{
-    //         hash::{Hash, Hasher},
-    //         num::NonZeroUsize,
-    //     };
-    //     use std::collections::{hash_map::DefaultHasher, BTreeMap};
-    //     let mut btm = BTreeMap::new();
-    //     // SAFETY: duh
-    //     let _ = btm.insert(Ast::One, unsafe {
-    //         NonZeroUsize::new_unchecked(29_715_618_991_585_221)
-    //     });
-    //     // SAFETY: duh
-    //     let _ = btm.insert(Ast::Bottom, unsafe {
-    //         NonZeroUsize::new_unchecked(18_417_028_454_717_966_395)
-    //     });
-    //     let split = Split {
-    //         lhs: Trace {
-    //             current: trace {
-    //                 rhs: Multiset(BTreeMap::new()),
-    //             },
-    //             history: None,
-    //         },
-    //         rhs: Trace {
-    //             current: trace { rhs: Multiset(btm) },
-    //             history: None,
-    //         },
-    //     };
-    //     let &Split { ref lhs, ref rhs } = &split;
-    //     let swap = Split {
-    //         lhs: rhs.clone(),
-    //         rhs: lhs.clone(),
-    //     };
-    //     assert_eq!(split, swap);
-    //     let mut h = DefaultHasher::new();
-    //     split.hash(&mut h);
-    //     let hash_a = h.finish();
-    //     h = DefaultHasher::new();
-    //     swap.hash(&mut h);
-    //     let hash_b = h.finish();
-    //     assert_eq!(hash_a, hash_b);
-    // }
+/// Sequent of small numbers with no rules, for generating arbitrary proof-search state.
+#[cfg(feature = "quickcheck")]
+type Tiny = RhsOnlyWithExchange<u8>;
+
+#[cfg(feature = "quickcheck")]
+impl Infer<Tiny> for u8 {
+    #[inline]
+    fn above(&self, _: Tiny) -> Vec<Rule<Tiny>> {
+        vec![]
+    }
 }
 
-// #[inline]
-// #[cfg(feature = "quickcheck")]
-// fn eq_implies_hash<T: Eq + core::hash::Hash>(a: &T, b: &T) -> bool {
-//     use {core::hash::Hasher, std::collections::hash_map::DefaultHasher};
-//     if a != b {
-//         return true;
-//     }
-//     let mut h = DefaultHasher::new();
-//     a.hash(&mut h);
-//     let hash_a = h.finish();
-//     h = DefaultHasher::new();
-//     b.hash(&mut h);
-//     let hash_b = h.finish();
-//     hash_a == hash_b
-// }
+#[inline]
+#[cfg(feature = "quickcheck")]
+fn eq_implies_hash<T: Eq + core::hash::Hash>(a: &T, b: &T) -> bool {
+    use {core::hash::Hasher as _, std::collections::hash_map::DefaultHasher};
+    if a != b {
+        return true;
+    }
+    let mut h = DefaultHasher::new();
+    a.hash(&mut h);
+    let hash_a = h.finish();
+    h = DefaultHasher::new();
+    b.hash(&mut h);
+    let hash_b = h.finish();
+    hash_a == hash_b
+}
+
+#[inline]
+#[cfg(feature = "quickcheck")]
+fn cmp_consistent_with_eq<T: Ord>(a: &T, b: &T) -> bool {
+    (a == b) == (a.cmp(b) == core::cmp::Ordering::Equal)
+}
@@ -6,9 +6,29 @@
 
 //! Cache any finished results automatically.
 
-use crate::{Rule, Sequent};
-use core::cmp::Reverse;
-use std::collections::{hash_map::Entry, BinaryHeap, HashMap};
+use crate::{
+    config::DEFAULT_MAX_COPIES,
+    hash::{Entry, HashMap, RandomState},
+    heuristic::rank,
+    lemma::Lemmas,
+    stats::bump,
+    ProofId, Rule, SearchConfig, SearchStats, SearchStrategy, Sequent,
+};
+use alloc::{
+    collections::{BTreeSet, BinaryHeap},
+    vec,
+    vec::Vec,
+};
+use core::{cmp::Reverse, hash::BuildHasher};
+
+/// Name of the rule proving each sequent assumed by `prove_with_axioms`.
+pub(crate) const AXIOM: &str = "axiom";
+
+/// Name of the rule proving a sequent from a proven one that subsumes it.
+pub(crate) const WEAKENING: &str = "weakening";
+
+/// Where a sequent sits in the queue (lowest first): its heuristic rank, then the strategy's key.
+type Key = (u64, u64, u64);
 
 /// This specific sequent (not the whole proof) has already been proven.
 pub(crate) struct AlreadyProven;
@@ -17,143 +37,513 @@ pub(crate) struct Qed<S: Sequent> {
     /// Sequents immediately above the original expression.
     pub(crate) proof: Rule<S>,
 }
+/// A proof of a sequent we had never seen, so nothing could have been waiting on it.
+#[derive(Debug)]
+pub(crate) struct CacheError<S: Sequent> {
+    /// The sequent we never saw.
+    pub(crate) sequent: S,
+    /// The proof we were handed for it.
+    pub(crate) proof: Rule<S>,
+}
 
-/// Cache any finished results automatically.
+/// Cache any finished results automatically,
+/// hashing sequents with whatever `H` builds.
 #[derive(Clone, Debug, Default)]
-pub(crate) struct Thunk<S: Sequent> {
+pub(crate) struct Thunk<S: Sequent, H = RandomState> {
     /// Record of what we've seen and, within that set, what we've proven.
-    cache: HashMap<S, Option<Rule<S>>>,
-    /// Smallest-first queue of unproven sequents.
-    queue: BinaryHeap<Reverse<S>>,
-    /// The sequent we're trying to prove overall.
-    original: S,
+    /// Like every hash map here, only ever looked up (never iterated in order),
+    /// so which proof we find can't depend on hashing.
+    cache: HashMap<S, Option<(ProofId, Rule<S>)>, H>,
+    /// How many proofs we've cached, i.e. the next proof's ID.
+    proofs: usize,
+    /// Unproven sequents, highest heuristic score first, then lowest key, then smallest sequent.
+    queue: BinaryHeap<Reverse<(Key, S)>>,
+    /// How to order the queue.
+    strategy: SearchStrategy<S>,
+    /// How many times we've added anything to the queue.
+    queued: u64,
+    /// The sequent we're trying to prove overall (if we've been given one yet).
+    original: Option<S>,
+    /// How much work we've done so far.
+    stats: SearchStats,
+    /// Sequents at least this far above the original are never expanded (if any limit).
+    depth_limit: Option<usize>,
+    /// Fewest inference lines between each sequent and the original (only tracked with a depth limit).
+    depths: HashMap<S, usize, H>,
+    /// Whether we skipped any sequent for being too deep.
+    pruned: bool,
+    /// Each sequent we've expanded and whether that ever generated any rule.
+    fruitful: HashMap<S, bool, H>,
+    /// Formulas we may cut on.
+    lemmas: Lemmas<S>,
+    /// Sequents with more copies than this of any one item are never queued (if any limit).
+    max_copies: Option<usize>,
+    /// Sequents with more items than this are never queued (if any limit).
+    max_width: Option<usize>,
+    /// Whether proving the first sequent proves the second by weakening (if we check at all).
+    subsumes: Option<fn(&S, &S) -> bool>,
+    /// Every proven sequent, by its smallest item (only tracked if we check subsumption).
+    by_smallest: HashMap<Option<S::Item>, Vec<S>, H>,
+    /// Whether to remember where each sequent came from.
+    tracing: bool,
+    /// The sequent whose expansion first generated each sequent (only tracked if tracing).
+    /// Always seen before its child, so following parents always ends (at the original).
+    parents: HashMap<S, S, H>,
 }
 
 impl<S: Sequent> Thunk<S> {
-    /// Create a new queue with only this original expression.
+    /// Create a new queue with only this original sequent.
+    #[inline]
+    pub(crate) fn new(goal: S) -> Self {
+        Self::start(goal, None, SearchStrategy::default())
+    }
+
+    /// Create a new queue with only this original expression, ordered by `strategy`.
+    #[inline]
+    pub(crate) fn with_strategy(expression: S::Item, strategy: SearchStrategy<S>) -> Self {
+        Self::start(S::from_rhs(expression), None, strategy)
+    }
+
+    /// Create a new queue with only this original expression, free to cut on any of `lemmas`.
+    #[inline]
+    pub(crate) fn with_lemmas(expression: S::Item, lemmas: Lemmas<S>) -> Self {
+        let mut q = Self::start(S::from_rhs(expression), None, SearchStrategy::default());
+        q.lemmas = lemmas;
+        q
+    }
+
+    /// Create a new queue with only this original expression, taking every one of `axioms` as already proven,
+    /// or if the original expression is itself one of them, return `Err(AlreadyProven)`.
+    #[inline]
+    pub(crate) fn with_axioms<A: IntoIterator<Item = S>>(
+        expression: S::Item,
+        axioms: A,
+    ) -> Result<Self, AlreadyProven> {
+        let mut q = Self::empty(None);
+        for axiom in axioms {
+            q.assume(axiom);
+        }
+        q.set_original(S::from_rhs(expression))?;
+        Ok(q)
+    }
+
+    /// Create a new queue with only this original expression,
+    /// never expanding sequents `depth_limit` or more inference lines above it.
+    #[inline]
+    pub(crate) fn with_depth_limit(expression: S::Item, depth_limit: usize) -> Self {
+        Self::start(
+            S::from_rhs(expression),
+            Some(depth_limit),
+            SearchStrategy::default(),
+        )
+    }
+
+    /// Create a new queue with only this original sequent, an optional depth limit, and a strategy.
+    #[inline]
+    fn start(goal: S, depth_limit: Option<usize>, strategy: SearchStrategy<S>) -> Self {
+        let mut q = Self::empty(depth_limit);
+        q.strategy = strategy;
+        q.start_with(goal);
+        q
+    }
+
+    /// Create an empty queue with nothing to prove yet and an optional depth limit.
+    #[inline]
+    pub(crate) fn empty(depth_limit: Option<usize>) -> Self {
+        Self::empty_with_hasher(depth_limit, RandomState::default())
+    }
+}
+
+impl<S: Sequent, H: BuildHasher + Clone> Thunk<S, H> {
+    /// Create a new queue with only this original expression, tuned by `config`.
+    #[inline]
+    pub(crate) fn with_config(expression: S::Item, config: SearchConfig<S, H>) -> Self {
+        let mut q = Self::empty_with_config(config);
+        q.start_with(S::from_rhs(expression));
+        q
+    }
+
+    /// Create an empty queue with nothing to prove yet, tuned by `config`.
     #[inline]
-    pub(crate) fn new(expression: S::Item) -> Self {
-        let sequent = S::from_rhs(expression);
-        let mut q = Self {
-            cache: HashMap::new(),
+    pub(crate) fn empty_with_config(config: SearchConfig<S, H>) -> Self {
+        let mut q = Self::empty_with_hasher(None, config.hasher);
+        q.max_copies = config.max_copies;
+        q.max_width = config.max_width;
+        q.subsumes = config.subsumes;
+        q
+    }
+
+    /// Create an empty queue with nothing to prove yet, an optional depth limit,
+    /// and `hasher` for every sequent we see.
+    #[inline]
+    fn empty_with_hasher(depth_limit: Option<usize>, hasher: H) -> Self {
+        Self {
+            cache: HashMap::with_hasher(hasher.clone()),
+            proofs: 0,
             queue: BinaryHeap::new(),
-            original: sequent.clone(),
-        };
+            strategy: SearchStrategy::default(),
+            queued: 0,
+            original: None,
+            stats: SearchStats::default(),
+            depth_limit,
+            depths: HashMap::with_hasher(hasher.clone()),
+            pruned: false,
+            fruitful: HashMap::with_hasher(hasher.clone()),
+            lemmas: Lemmas::default(),
+            max_copies: Some(DEFAULT_MAX_COPIES),
+            max_width: None,
+            subsumes: None,
+            by_smallest: HashMap::with_hasher(hasher.clone()),
+            tracing: false,
+            parents: HashMap::with_hasher(hasher),
+        }
+    }
+
+    /// Aim for this original sequent in a queue that can't have proven anything yet.
+    #[inline]
+    fn start_with(&mut self, goal: S) {
         #[allow(unsafe_code)]
         // SAFETY: Empty above: can't have already been proven.
         unsafe {
-            q.push(sequent).unwrap_unchecked();
+            self.set_original(goal).unwrap_unchecked();
         }
-        q
+    }
+
+    /// What we hash sequents with, so anything searching alongside us can do the same.
+    #[inline(always)]
+    pub(crate) fn hasher(&self) -> &H {
+        self.cache.hasher()
+    }
+
+    /// Aim for a new original sequent, keeping everything we've proven so far,
+    /// or if it's already been proven, return `Err(AlreadyProven)`.
+    /// Raises the copy limit (if any) to however many copies the sequent starts with,
+    /// so a cap never rules out the original itself.
+    #[inline]
+    pub(crate) fn set_original(&mut self, sequent: S) -> Result<(), AlreadyProven> {
+        self.max_copies = self.max_copies.map(|max| max.max(sequent.most_copies()));
+        self.original = Some(sequent.clone());
+        self.push_at_depth(sequent, 0, 0)
     }
 
     /// Add a sequent to be proven, or if it's already been proven, return `Err(AlreadyProven)`.
     #[inline]
     pub(crate) fn push(&mut self, sequent: S) -> Result<(), AlreadyProven> {
+        self.push_at_depth(sequent, 0, 0)
+    }
+
+    /// Add each sequent immediately above `below` (by a rule a heuristic gave this score) to be proven,
+    /// ignoring any already proven.
+    #[inline]
+    #[allow(clippy::let_underscore_must_use)]
+    pub(crate) fn extend_above<A: IntoIterator<Item = S>>(
+        &mut self,
+        below: &S,
+        above: A,
+        score: i64,
+    ) {
+        let depth = self.depth(below).saturating_add(1);
+        for sequent in above {
+            if self.tracing && !self.cache.contains_key(&sequent) {
+                drop(self.parents.insert(sequent.clone(), below.clone()));
+            }
+            let _ = self.push_at_depth(sequent, depth, score);
+        }
+    }
+
+    /// Start remembering where each sequent came from, so `path_to` can trace it back.
+    #[inline(always)]
+    pub(crate) const fn trace(&mut self) {
+        self.tracing = true;
+    }
+
+    /// Every sequent from the original down to this one, each generated by expanding the last
+    /// (or just this one if we haven't been tracing).
+    #[inline]
+    pub(crate) fn path_to(&self, sequent: &S) -> Vec<S> {
+        let mut path = vec![sequent.clone()];
+        let mut current = sequent;
+        while let Some(parent) = self.parents.get(current) {
+            path.push(parent.clone());
+            current = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Add a sequent (this many inference lines above the original, with this heuristic score) to be proven,
+    /// or if it's already been proven, return `Err(AlreadyProven)`.
+    #[inline]
+    fn push_at_depth(&mut self, sequent: S, depth: usize, score: i64) -> Result<(), AlreadyProven> {
+        if self
+            .max_copies
+            .is_some_and(|max| sequent.most_copies() > max)
+        {
+            dbg_println!("    Skipping {sequent} (too many copies)");
+            bump(&mut self.stats.over_copy_cap);
+            return Ok(());
+        }
+        if self.max_width.is_some_and(|max| sequent.width() > max) {
+            dbg_println!("    Skipping {sequent} (too wide)");
+            bump(&mut self.stats.over_width_cap);
+            return Ok(());
+        }
         match self.cache.entry(sequent.clone()) {
             Entry::Vacant(empty) => {
                 let _ = empty.insert(None);
                 dbg_println!("    Adding {sequent}");
-                self.queue.push(Reverse(sequent));
+                if self.depth_limit.is_some() {
+                    let _ = self.depths.insert(sequent.clone(), depth);
+                }
+                self.enqueue(sequent, score);
+                bump(&mut self.stats.enqueued);
+                self.stats.peak_queue_len = self.stats.peak_queue_len.max(self.queue.len());
                 Ok(())
             }
-            Entry::Occupied(full) => match *full.get() {
-                None => Ok(()),
-                Some(_) => {
-                    // dbg_println!("    Already proved {sequent}");
-                    Err(AlreadyProven)
+            Entry::Occupied(full) => {
+                bump(&mut self.stats.cache_hits);
+                match *full.get() {
+                    None => {
+                        if self.depth_limit.is_some() {
+                            if let Some(old) = self.depths.get_mut(&sequent) {
+                                if depth < *old {
+                                    // Found a shorter path: try again with more room above.
+                                    *old = depth;
+                                    self.enqueue(sequent, score);
+                                }
+                            }
+                        }
+                        Ok(())
+                    }
+                    Some(_) => {
+                        // dbg_println!("    Already proved {sequent}");
+                        Err(AlreadyProven)
+                    }
                 }
-            },
+            }
         }
     }
 
-    /// Mark a sequent proven.
+    /// Put a sequent in the queue wherever its heuristic score and then the strategy say it goes.
     #[inline]
-    #[cfg_attr(
-        any(test, debug_assertions),
-        allow(
-            clippy::needless_pass_by_value,
-            clippy::panic,
-            clippy::panic_in_result_fn,
-            unreachable_code,
-            unused_mut,
-            unused_variables
-        )
-    )]
-    pub(crate) fn cache(&mut self, sequent: S, proof: Rule<S>) -> Result<(), Qed<S>> {
-        if sequent == self.original {
-            Err(Qed { proof })
-        } else {
-            match self.cache.entry(
-                #[cfg(any(test, debug_assertions))]
-                sequent.clone(),
-                #[cfg(not(any(test, debug_assertions)))]
-                sequent,
-            ) {
-                Entry::Vacant(empty) => {
-                    #[cfg(any(test, debug_assertions))]
-                    panic!(
-                        "Tried to mark {sequent} proven, \
-                        but we had never seen it before",
-                    );
-                    let _ = empty.insert(Some(proof));
-                }
-                Entry::Occupied(mut filled) => {
-                    #[cfg(any(test, debug_assertions))]
-                    {
-                        let old = filled.insert(Some(proof));
-                        assert!(
-                            old.is_none(),
-                            "Tried to mark {sequent} proven, \
-                            but we had already cached it as proven"
-                        );
-                    }
-                    #[cfg(not(any(test, debug_assertions)))]
-                    drop(filled.insert(Some(proof)));
-                }
-            }
+    fn enqueue(&mut self, sequent: S, score: i64) {
+        let (primary, secondary) = self.strategy.key(&sequent, self.queued);
+        let key = (rank(score), primary, secondary);
+        self.queued = self.queued.saturating_add(1);
+        self.queue.push(Reverse((key, sequent)));
+    }
+
+    /// Mark a sequent proven, or if another inference (e.g. one discharged in the same pass) already proved it,
+    /// keep that first proof and ignore this one.
+    /// If this is the original sequent, return the proof that finishes the search.
+    /// # Errors
+    /// If we had never seen this sequent before, so nothing could be waiting on its proof:
+    /// then we cache nothing and hand both back.
+    #[inline]
+    pub(crate) fn cache(
+        &mut self,
+        sequent: S,
+        proof: Rule<S>,
+    ) -> Result<Option<Qed<S>>, CacheError<S>> {
+        let Some(entry) = self.cache.get_mut(&sequent) else {
+            return Err(CacheError { sequent, proof });
+        };
+        if entry.is_none() {
+            *entry = Some((ProofId(self.proofs), proof));
+            self.proofs = self.proofs.saturating_add(1);
+            self.index(&sequent);
             dbg_println!("    Proved {sequent}");
-            Ok(())
         }
+        Ok(if self.original.as_ref() == Some(&sequent) {
+            // Still cached in case we're asked to prove something else later.
+            self.proven(&sequent).map(|first| Qed {
+                proof: first.clone(),
+            })
+        } else {
+            None
+        })
     }
 
-    /// Check if we have a cached proof of this sequent.
+    /// Take a sequent as proven by a rule named `axiom` with nothing above it.
+    /// Unlike `cache`, we never need to have seen it first.
     #[inline]
-    pub(crate) fn proven(&self, sequent: &S) -> &Option<Rule<S>> {
-        let opt = self.cache.get(sequent);
-        #[allow(unsafe_code)]
-        // SAFETY:
-        // Internal use only.
-        // Called in one place that pulls from the queue of seen sequents anyway.
-        unsafe {
-            opt.unwrap_unchecked()
+    fn assume(&mut self, sequent: S) {
+        dbg_println!("    Assuming {sequent}");
+        self.restore(sequent, Rule::new(AXIOM, []));
+    }
+
+    /// Take a sequent as proven by `proof`, e.g. from a cache saved by an earlier run.
+    /// Like `assume`, we never need to have seen it first.
+    #[inline]
+    pub(crate) fn restore(&mut self, sequent: S, proof: Rule<S>) {
+        self.index(&sequent);
+        drop(
+            self.cache
+                .insert(sequent, Some((ProofId(self.proofs), proof))),
+        );
+        self.proofs = self.proofs.saturating_add(1);
+    }
+
+    /// Remember a newly proven sequent by its smallest item, if we check subsumption.
+    #[inline]
+    fn index(&mut self, sequent: &S) {
+        if self.subsumes.is_some() {
+            let smallest = sequent.sample().map(|(item, _)| item).min();
+            self.by_smallest
+                .entry(smallest)
+                .or_default()
+                .push(sequent.clone());
         }
     }
 
-    /// Remove a cached proof of this sequent if we have one.
+    /// A rule proving this sequent by weakening a proven sequent that subsumes it,
+    /// if we check subsumption and there is one.
     #[inline]
-    pub(crate) fn yank(&mut self, sequent: &S) -> Option<Rule<S>> {
-        let opt = self.cache.remove(sequent);
-        opt.map(|maybe_rule| {
-            #[allow(unsafe_code)]
-            // SAFETY:
-            // Always proven, just maybe yanked twice
-            // (so may be `None` but not `Some(None)`).
-            unsafe {
-                maybe_rule.unwrap_unchecked()
-            }
-        })
+    pub(crate) fn weakening(&self, sequent: &S) -> Option<Rule<S>> {
+        let subsumes = self.subsumes?;
+        // A sequent can only subsume this one if its smallest item (if any) is in this one.
+        core::iter::once(None)
+            .chain(sequent.sample().map(|(item, _)| Some(item)))
+            .filter_map(|smallest| self.by_smallest.get(&smallest))
+            .flatten()
+            .find(|proven| subsumes(proven, sequent))
+            .map(|proven| Rule::new(WEAKENING, [proven.clone()]))
+    }
+
+    /// Check if we have a cached proof of this sequent (never, if we've never even seen it).
+    /// Only looks, so every copy of a repeated premise finds the same proof.
+    #[inline]
+    pub(crate) fn proven(&self, sequent: &S) -> Option<&Rule<S>> {
+        self.cache
+            .get(sequent)
+            .and_then(Option::as_ref)
+            .map(|&(_, ref proof)| proof)
+    }
+
+    /// The ID we gave this sequent's proof when we cached it (never, if it isn't proven).
+    #[inline]
+    pub(crate) fn proof_id(&self, sequent: &S) -> Option<ProofId> {
+        self.cache
+            .get(sequent)
+            .and_then(Option::as_ref)
+            .map(|&(id, _)| id)
+    }
+
+    /// How much work we've done so far.
+    #[inline(always)]
+    pub(crate) const fn stats(&self) -> SearchStats {
+        self.stats
+    }
+
+    /// How many sequents are waiting in the queue right now.
+    #[inline(always)]
+    #[cfg(feature = "std")]
+    pub(crate) fn queue_len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// How many distinct sequents we've seen, proven or not.
+    #[inline(always)]
+    #[cfg(feature = "std")]
+    pub(crate) fn cache_len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Counters for the search loop to update directly.
+    #[inline(always)]
+    pub(crate) const fn stats_mut(&mut self) -> &mut SearchStats {
+        &mut self.stats
+    }
+
+    /// Fewest inference lines we've seen between this sequent and the original
+    /// (always zero without a depth limit).
+    #[inline]
+    pub(crate) fn depth(&self, sequent: &S) -> usize {
+        self.depths.get(sequent).copied().unwrap_or(0)
+    }
+
+    /// Whether we skipped any sequent for being too deep.
+    #[inline(always)]
+    pub(crate) const fn pruned(&self) -> bool {
+        self.pruned
+    }
+
+    /// The width limit and the copy limit, each only if we skipped any sequent for going over it.
+    #[inline(always)]
+    pub(crate) fn caps_exceeded(&self) -> (Option<usize>, Option<usize>) {
+        (
+            self.max_width.filter(|_| self.stats.over_width_cap > 0),
+            self.max_copies.filter(|_| self.stats.over_copy_cap > 0),
+        )
+    }
+
+    /// Every way to conclude this sequent by cutting on a lemma.
+    #[inline]
+    pub(crate) fn cuts(&self, sequent: &S) -> Vec<Rule<S>> {
+        self.lemmas.cuts(sequent)
+    }
+
+    /// Note that we expanded this sequent and whether that generated any rule.
+    #[inline]
+    pub(crate) fn record_expansion(&mut self, sequent: &S, generated_any: bool) {
+        if let Some(fruitful) = self.fruitful.get_mut(sequent) {
+            *fruitful |= generated_any;
+        } else {
+            let _ = self.fruitful.insert(sequent.clone(), generated_any);
+        }
+    }
+
+    /// Every sequent we've expanded without generating a single rule.
+    #[inline]
+    pub(crate) fn dead_ends(&self) -> BTreeSet<S> {
+        self.fruitful
+            .iter()
+            .filter(|&(_, &fruitful)| !fruitful)
+            .map(|(sequent, _)| sequent.clone())
+            .collect()
+    }
+
+    /// How many sequents we've proven so far.
+    #[inline]
+    pub(crate) fn proven_count(&self) -> usize {
+        self.cache.values().filter(|proof| proof.is_some()).count()
+    }
+
+    /// Every sequent we've proven so far and the rule that proved it, in no particular order.
+    #[inline]
+    #[cfg(feature = "persist")]
+    pub(crate) fn proven_entries(&self) -> impl Iterator<Item = (&S, &Rule<S>)> {
+        self.cache
+            .iter()
+            .filter_map(|(sequent, proof)| proof.as_ref().map(|&(_, ref rule)| (sequent, rule)))
+    }
+
+    /// Whether proving the first sequent proves the second by weakening (if we check at all).
+    #[inline(always)]
+    #[cfg(feature = "persist")]
+    pub(crate) const fn subsumes(&self) -> Option<fn(&S, &S) -> bool> {
+        self.subsumes
     }
 }
 
-impl<S: Sequent> Iterator for Thunk<S> {
+impl<S: Sequent, H: BuildHasher + Clone> Iterator for Thunk<S, H> {
     type Item = S;
     fn next(&mut self) -> Option<Self::Item> {
-        self.queue.pop().map(|Reverse(s)| s)
+        loop {
+            let Reverse((_, s)) = self.queue.pop()?;
+            if let Some(limit) = self.depth_limit {
+                if self.depth(&s) >= limit {
+                    self.pruned = true;
+                    continue;
+                }
+            }
+            bump(&mut self.stats.dequeued);
+            return Some(s);
+        }
     }
 }
 
-impl<S: Sequent> Extend<S> for Thunk<S> {
+impl<S: Sequent, H: BuildHasher + Clone> Extend<S> for Thunk<S, H> {
     #[inline]
     #[allow(clippy::let_underscore_must_use)]
     fn extend<T: IntoIterator<Item = S>>(&mut self, iter: T) {
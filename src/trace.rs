@@ -0,0 +1,141 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+//! A complete log of one proof search, from which the proof can be rebuilt without searching again.
+
+use crate::{
+    hash::HashMap, proof::prove_observed, Error, Infer, Rule, SearchObserver, Sequent, Tree,
+};
+use alloc::vec::Vec;
+
+/// Something that happened during a traced proof search, in the order it happened.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TraceEvent<S: Sequent> {
+    /// We took this sequent out of the queue.
+    Dequeued(S),
+    /// We found an inference and set it aside until its premises are proven.
+    RuleGenerated {
+        /// Conclusion of the inference.
+        below: S,
+        /// Rule that would prove it, premises and all.
+        rule: Rule<S>,
+    },
+    /// We proved a sequent and cached the rule that did it.
+    Cached {
+        /// What we proved.
+        sequent: S,
+        /// How we proved it.
+        rule: Rule<S>,
+    },
+    /// We proved the original sequent, so the search is over.
+    Finished(S),
+}
+
+/// Record every event in order (except `Finished`, which only `prove_traced` knows about).
+impl<S: Sequent> SearchObserver<S> for Vec<TraceEvent<S>> {
+    #[inline]
+    fn on_dequeue(&mut self, sequent: &S) {
+        self.push(TraceEvent::Dequeued(sequent.clone()));
+    }
+    #[inline]
+    fn on_pause(&mut self, below: &S, rule: &Rule<S>) {
+        self.push(TraceEvent::RuleGenerated {
+            below: below.clone(),
+            rule: rule.clone(),
+        });
+    }
+    #[inline]
+    fn on_prove(&mut self, sequent: &S, rule: &Rule<S>) {
+        self.push(TraceEvent::Cached {
+            sequent: sequent.clone(),
+            rule: rule.clone(),
+        });
+    }
+}
+
+/// Why a trace couldn't be replayed into a proof.
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReplayError<S: Sequent> {
+    /// The search never finished, so there's no proof to rebuild.
+    Unfinished,
+    /// The search claims to have finished proving a sequent it never cached a proof of.
+    Unproven(S),
+    /// A sequent was cached with a rule the search never generated for it.
+    NeverGenerated(S),
+}
+
+impl<S: Sequent> core::fmt::Display for ReplayError<S> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::Unfinished => write!(f, "Trace ends before the search finished"),
+            Self::Unproven(ref sequent) => {
+                write!(f, "Trace finishes with {sequent}, which it never proved")
+            }
+            Self::NeverGenerated(ref sequent) => write!(
+                f,
+                "Trace proves {sequent} with a rule it never generated for it",
+            ),
+        }
+    }
+}
+
+impl<S: Sequent> core::error::Error for ReplayError<S> {}
+
+/// Attempt to prove this expression like `prove`, logging everything the search does along the way.
+/// Pass the log to `replay` to rebuild the same proof without searching.
+#[inline]
+pub fn prove_traced<I: Infer<S>, S: Sequent<Item = I>>(
+    expr: I,
+) -> (Result<Tree<S>, Error>, Vec<TraceEvent<S>>) {
+    let mut events = Vec::new();
+    let result = prove_observed(expr, &mut events);
+    if let Ok(ref tree) = result {
+        events.push(TraceEvent::Finished(tree.below.clone()));
+    }
+    (result, events)
+}
+
+/// Rebuild the proof a traced search found (see `prove_traced`) purely from its trace,
+/// checking along the way that every proof it cached comes from a rule it generated.
+/// # Errors
+/// If the trace never finishes, or is inconsistent with itself.
+#[inline]
+pub fn replay<S: Sequent, E: IntoIterator<Item = TraceEvent<S>>>(
+    events: E,
+) -> Result<Tree<S>, ReplayError<S>> {
+    let mut generated: HashMap<S, Vec<Rule<S>>> = HashMap::new();
+    let mut cached: HashMap<S, Rule<S>> = HashMap::new();
+    for event in events {
+        match event {
+            TraceEvent::Dequeued(_) => {}
+            TraceEvent::RuleGenerated { below, rule } => {
+                generated.entry(below).or_default().push(rule);
+            }
+            TraceEvent::Cached { sequent, rule } => {
+                if !generated
+                    .get(&sequent)
+                    .is_some_and(|rules| rules.contains(&rule))
+                {
+                    return Err(ReplayError::NeverGenerated(sequent));
+                }
+                // The search never proves the same sequent twice, so keep the first just as it did.
+                let _ = cached.entry(sequent).or_insert(rule);
+            }
+            TraceEvent::Finished(goal) => {
+                let Some(rule) = cached.get(&goal).cloned() else {
+                    return Err(ReplayError::Unproven(goal));
+                };
+                return Ok(Tree::connect_with(goal, rule, &|sequent| {
+                    cached.get(sequent)
+                }));
+            }
+        }
+    }
+    Err(ReplayError::Unfinished)
+}
@@ -6,127 +6,1096 @@
 
 //! Proof as a tree rooted at the bottom (the original expression).
 
-use crate::{thunk::Thunk, Rule, Sequent};
-use std::collections::BTreeSet;
+use crate::{
+    ascii::to_ascii,
+    hash::{HashMap, HashSet},
+    html, json,
+    latex::{escape, LATEX_SYMBOLS},
+    parallel::expand,
+    thunk::Thunk,
+    Html, Latex, Multiset, RenderOptions, Rule, Sequent,
+};
+use alloc::{
+    borrow::{Cow, ToOwned as _},
+    collections::{BTreeMap, VecDeque},
+    format,
+    string::{String, ToString as _},
+    vec,
+    vec::Vec,
+};
+use core::{convert::Infallible, hash::BuildHasher};
 
 /// Proof as a tree rooted at the bottom (the original expression).
 #[allow(clippy::exhaustive_structs)]
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+// The only `unsafe` is in printing, which doesn't rely on anything about the contents.
+#[cfg_attr(feature = "serde", allow(clippy::unsafe_derive_deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "S: serde::Serialize, S::Item: serde::Serialize",
+        deserialize = "S: serde::Deserialize<'de>, S::Item: serde::Deserialize<'de>",
+    ))
+)]
 pub struct Tree<S: Sequent> {
-    /// Proof of each sequent above the inference line.
-    pub above: BTreeSet<Self>,
+    /// Proof of each sequent above the inference line, in the order of `Rule::above`.
+    ///
+    /// One subproof per copy of each premise, so a rule that needs the same premise twice
+    /// has two identical proofs of it here (and `above.len()` is always `Rule::arity`).
+    pub above: Vec<Self>,
     /// Name of the rule that allowed this inference.
-    pub rule: &'static str,
+    pub rule: Cow<'static, str>,
+    /// Formula the rule acted on, if it came from one (see `Rule::principal`).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub principal: Option<S::Item>,
     /// Sequent below the inference line (proven by those above).
     pub below: S,
 }
 
+/// Most premises `bussproofs` can put above a single visible inference line without help.
+const MAX_PREMISES: usize = 3;
+
+/// Each line of a printed proof (bottom first), and where its conclusion sits.
+type Printed = (VecDeque<String>, Shape);
+
+/// Label on a leaf whose sequent is proven elsewhere in the same tree.
+pub(crate) const ALREADY_PROVEN: &str = "(already proven)";
+
+/// A step in a proof tree that doesn't follow from the rules.
+#[allow(clippy::exhaustive_structs)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProofCheckError<S: Sequent> {
+    /// Sequent below the offending inference line.
+    pub sequent: S,
+    /// Name of the rule the tree claimed to use.
+    pub rule: Cow<'static, str>,
+    /// Every rule that actually applies to this sequent.
+    pub available: Vec<Rule<S>>,
+}
+
+impl<S: Sequent> core::fmt::Display for ProofCheckError<S> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "No rule named {} proves {} from those premises (",
+            self.rule, self.sequent,
+        )?;
+        let mut iter = self.available.iter();
+        if let Some(first) = iter.next() {
+            write!(f, "available: {}", first.name)?;
+            for next in iter {
+                write!(f, ", {}", next.name)?;
+            }
+        } else {
+            write!(f, "no rules apply")?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl<S: Sequent> core::error::Error for ProofCheckError<S> {}
+
+/// First place two proofs of the same thing stop agreeing.
+#[allow(clippy::exhaustive_structs)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TreeDiff<S: Sequent> {
+    /// Index into `above` (in iteration order, on the left) of each premise from the root to the divergence.
+    pub path: Vec<usize>,
+    /// Sequent below the diverging inference line on the left.
+    pub left: S,
+    /// Name of the rule on the left.
+    pub left_rule: Cow<'static, str>,
+    /// Sequent below the diverging inference line on the right.
+    pub right: S,
+    /// Name of the rule on the right.
+    pub right_rule: Cow<'static, str>,
+}
+
+impl<S: Sequent> core::fmt::Display for TreeDiff<S> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut path = self.path.iter();
+        if let Some(first) = path.next() {
+            write!(f, "Proofs diverge at premise path [{first}")?;
+            for next in path {
+                write!(f, ", {next}")?;
+            }
+            writeln!(f, "]:")?;
+        } else {
+            writeln!(f, "Proofs diverge at the root:")?;
+        }
+        writeln!(f, "    left:  {} by {}", self.left, self.left_rule)?;
+        write!(f, "    right: {} by {}", self.right, self.right_rule)
+    }
+}
+
 impl<S: Sequent> Tree<S> {
     /// Chain cached proof steps together into a single proof.
     #[inline]
-    pub(crate) fn connect<Above: IntoIterator<Item = S>>(
+    pub(crate) fn connect<H: BuildHasher + Clone>(
         below: S,
-        rule: &'static str,
-        next: Above,
-        thunk: &mut Thunk<S>,
+        rule: Rule<S>,
+        thunk: &Thunk<S, H>,
     ) -> Self {
+        Self::connect_with(below, rule, &|sequent| thunk.proven(sequent))
+    }
+
+    /// Chain proof steps together into a single proof, looking up how each premise was proven with `proven`.
+    /// Depends on nothing else, so the same proven sequents always connect into the same proof.
+    #[inline]
+    pub(crate) fn connect_with<'cache, P: Fn(&S) -> Option<&'cache Rule<S>>>(
+        below: S,
+        rule: Rule<S>,
+        proven: &P,
+    ) -> Self
+    where
+        S: 'cache,
+    {
+        Self::connect_once(below, rule, proven, &mut HashSet::new())
+    }
+
+    /// Chain cached proof steps together into a single proof,
+    /// writing out the proof of each sequent only the first time we use it.
+    /// A premise this inference needs more than once gets its proof written out here
+    /// even if it's been used before, once per copy, so every copy is justified on its own.
+    #[inline]
+    fn connect_once<'cache, P: Fn(&S) -> Option<&'cache Rule<S>>>(
+        below: S,
+        rule: Rule<S>,
+        proven: &P,
+        used: &mut HashSet<S>,
+    ) -> Self
+    where
+        S: 'cache,
+    {
+        let Rule {
+            name,
+            above,
+            principal,
+            ..
+        } = rule;
+        let mut trees = Vec::with_capacity(above.len());
+        for (sequent, copies) in above.into_counts() {
+            let fresh = used.insert(sequent.clone());
+            let tree = match proven(&sequent) {
+                Some(proof) if fresh || copies.get() > 1 => {
+                    Tree::connect_once(sequent, proof.clone(), proven, used)
+                }
+                _ => Tree {
+                    above: vec![],
+                    rule: Cow::Borrowed(ALREADY_PROVEN),
+                    principal: None,
+                    below: sequent,
+                },
+            };
+            trees.extend(core::iter::repeat_n(tree, copies.get()));
+        }
         Tree {
-            above: next
-                .into_iter()
-                .map(|sequent| {
-                    thunk.yank(&sequent).map_or(
-                        Tree {
-                            above: BTreeSet::new(),
-                            rule: "(already proven)",
-                            below: sequent.clone(),
-                        },
-                        |Rule { name, above }| Tree::connect(sequent, name, above, thunk),
-                    )
-                })
-                .collect(),
-            rule,
+            above: trees,
+            rule: name,
+            principal,
             below,
         }
     }
 
-    /// Each line of printed output.
-    pub(crate) fn print_bottom_up(&self) -> (Vec<String>, usize) {
-        let mut columns: Vec<_> = self
-            .above
+    /// Check that every inference line in this tree follows from the rules,
+    /// i.e. that some item in each sequent offers a rule with the same name and the same premises
+    /// (and, if the tree names a principal formula, that it's the item offering the rule).
+    /// Leaves marked as already proven pass only if their sequent is proven elsewhere in the tree
+    /// by a proof that doesn't itself lean on that leaf, so a sequent can't be proven in terms of itself.
+    /// # Errors
+    /// At the first inference line that doesn't follow.
+    #[inline]
+    pub fn validate(&self) -> Result<(), ProofCheckError<S>> {
+        let mut grounded = HashSet::new();
+        loop {
+            let before = grounded.len();
+            let _ = self.ground(&mut grounded);
+            if grounded.len() == before {
+                break;
+            }
+        }
+        self.validate_against(&grounded)
+    }
+
+    /// Whether this subtree proves its sequent without referring to anything outside `grounded`,
+    /// adding every sequent proven that way (here or anywhere above) to `grounded`.
+    #[inline]
+    fn ground<'tree>(&'tree self, grounded: &mut HashSet<&'tree S>) -> bool {
+        if self.rule == ALREADY_PROVEN && self.above.is_empty() {
+            return grounded.contains(&self.below);
+        }
+        let mut all = true;
+        for tree in &self.above {
+            all &= tree.ground(grounded);
+        }
+        if all {
+            let _ = grounded.insert(&self.below);
+        }
+        all
+    }
+
+    /// Check every inference line, given which sequents have non-circular proofs somewhere in the tree.
+    #[inline]
+    fn validate_against(&self, proven: &HashSet<&S>) -> Result<(), ProofCheckError<S>> {
+        if self.rule == ALREADY_PROVEN && self.above.is_empty() && proven.contains(&self.below) {
+            return Ok(());
+        }
+        let available = expand(&self.below);
+        let premises: Multiset<&S> = self.above.iter().map(|tree| &tree.below).collect();
+        let claimed = &self.rule;
+        if !available.iter().any(|rule| {
+            rule.name == *claimed
+                && (self.principal.is_none() || rule.principal == self.principal)
+                && rule.above.len() == premises.len()
+                && rule
+                    .above
+                    .iter_unique()
+                    .all(|(sequent, &copies)| premises.count(&sequent) == copies.get())
+        }) {
+            return Err(ProofCheckError {
+                sequent: self.below.clone(),
+                rule: self.rule.clone(),
+                available,
+            });
+        }
+        self.above
             .iter()
-            .map(|tree| {
-                let (v, line_width) = tree.print_bottom_up();
-                let entire_width = {
-                    #[allow(unsafe_code)]
-                    // SAFETY: Base case 2 lines, each iteration lengthens, so always nonzero
-                    unsafe {
-                        v.iter().map(|s| s.chars().count()).max().unwrap_unchecked()
-                    }
-                };
-                (v, line_width, entire_width)
+            .try_for_each(|tree| tree.validate_against(proven))
+    }
+
+    /// Replace every leaf marked as already proven with a copy of the proof of its sequent
+    /// from elsewhere in the tree, so that every leaf is justified on its own.
+    /// Leaves whose sequent has no proof anywhere in the tree stay as they are.
+    ///
+    /// Shared subproofs get copied once per use, so this can make the tree much larger.
+    #[inline]
+    pub fn expand_shared(&mut self) {
+        let original = self.clone();
+        let mut proofs = HashMap::new();
+        original.collect_proofs(&mut proofs);
+        self.expand_with(&proofs, &mut HashSet::new());
+    }
+
+    /// Each sequent with a real inference line above it, alongside the subtree that proves it.
+    #[inline]
+    fn collect_proofs<'tree>(&'tree self, proofs: &mut HashMap<&'tree S, &'tree Self>) {
+        if self.rule != ALREADY_PROVEN {
+            let _ = proofs.entry(&self.below).or_insert(self);
+        }
+        for tree in &self.above {
+            tree.collect_proofs(proofs);
+        }
+    }
+
+    /// Replace every leaf marked as already proven with a copy of its proof in `proofs`,
+    /// except where that proof is still being copied further down (which only a circular tree could ask for).
+    #[inline]
+    fn expand_with(&mut self, proofs: &HashMap<&S, &Self>, copying: &mut HashSet<S>) {
+        let mut copied = None;
+        if self.rule == ALREADY_PROVEN && self.above.is_empty() && !copying.contains(&self.below) {
+            if let Some(&proof) = proofs.get(&self.below) {
+                *self = proof.clone();
+                let _ = copying.insert(self.below.clone());
+                copied = Some(&self.below);
+            }
+        }
+        self.above = core::mem::take(&mut self.above)
+            .into_iter()
+            .map(|mut tree| {
+                tree.expand_with(proofs, copying);
+                tree
             })
             .collect();
-        columns.sort_by_key(|&(_, _, entire_width)| entire_width);
-        let (line_size, maybe_stack) = columns.pop().map_or((0, None), |rightmost| {
-            let mut overall_width = 0;
-            let mut v = vec![];
-            for (stack, _, entire_width) in columns {
-                extend_upward(&mut v, stack, overall_width);
-                overall_width = overall_width.saturating_add(entire_width).saturating_add(3);
-            }
-            let (stack, line_width, _) = rightmost;
-            extend_upward(&mut v, stack, overall_width);
-            (overall_width.saturating_add(line_width), Some(v))
+        if let Some(sequent) = copied {
+            let _ = copying.remove(sequent);
+        }
+    }
+
+    /// Apply `f` to every sequent in this tree, keeping its shape and rule names.
+    ///
+    /// Principal formulas are items of the old sequent type, so they're dropped.
+    /// Walks an explicit stack, so arbitrarily deep trees won't overflow the call stack.
+    #[inline]
+    #[must_use]
+    pub fn map<T: Sequent, F: FnMut(S) -> T>(self, mut f: F) -> Tree<T> {
+        match self.try_map(|sequent| Ok::<_, Infallible>(f(sequent))) {
+            Ok(tree) => tree,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Apply `f` to every sequent in this tree, keeping its shape and rule names,
+    /// or stop at the first error (bottom first, then each premise in order).
+    ///
+    /// Principal formulas are items of the old sequent type, so they're dropped.
+    /// Walks an explicit stack, so arbitrarily deep trees won't overflow the call stack.
+    /// # Errors
+    /// Whatever `f` returns first.
+    #[inline]
+    pub fn try_map<T: Sequent, E, F: FnMut(S) -> Result<T, E>>(
+        self,
+        mut f: F,
+    ) -> Result<Tree<T>, E> {
+        self.rebuild(|rule, _, below| Ok((rule, None, f(below)?)))
+    }
+
+    /// Rename every inference line with `f`, e.g. to match a journal's notation,
+    /// keeping everything else (leaves marked as already proven keep their marker).
+    /// Walks an explicit stack, so arbitrarily deep trees won't overflow the call stack.
+    #[inline]
+    #[must_use]
+    pub fn map_rules<F: FnMut(Cow<'static, str>) -> Cow<'static, str>>(self, mut f: F) -> Self {
+        let renamed = self.rebuild(|rule, principal, below| {
+            let name = if rule == ALREADY_PROVEN {
+                rule
+            } else {
+                f(rule)
+            };
+            Ok::<_, Infallible>((name, principal, below))
         });
-        let below = self.below.to_string();
-        let max_width = line_size.max(below.chars().count());
-        let mut line = String::new();
-        for _ in 0..max_width {
+        match renamed {
+            Ok(tree) => tree,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Rebuild this tree node by node, bottom first, with whatever `node` makes of each label and sequent.
+    #[inline]
+    fn rebuild<T: Sequent, E, F>(self, mut node: F) -> Result<Tree<T>, E>
+    where
+        F: FnMut(
+            Cow<'static, str>,
+            Option<S::Item>,
+            S,
+        ) -> Result<(Cow<'static, str>, Option<T::Item>, T), E>,
+    {
+        /// Either a subtree still to visit or a node to assemble once everything above it is done.
+        enum Todo<S: Sequent, T: Sequent> {
+            /// Subtree still to visit.
+            Visit(Tree<S>),
+            /// Node whose premises are the last `premises` trees finished.
+            Assemble {
+                /// Label on its inference line.
+                rule: Cow<'static, str>,
+                /// Formula its rule acted on.
+                principal: Option<T::Item>,
+                /// Sequent below its inference line.
+                below: T,
+                /// How many finished trees belong above it.
+                premises: usize,
+            },
+        }
+        let mut todo = vec![Todo::Visit(self)];
+        let mut done: Vec<Tree<T>> = vec![];
+        while let Some(next) = todo.pop() {
+            match next {
+                Todo::Visit(mut tree) => {
+                    // `Tree` implements `Drop`, so take what we can and clone the rest.
+                    let above = core::mem::take(&mut tree.above);
+                    let (rule, principal, below) = node(
+                        core::mem::take(&mut tree.rule),
+                        tree.principal.take(),
+                        tree.below.clone(),
+                    )?;
+                    todo.push(Todo::Assemble {
+                        rule,
+                        principal,
+                        below,
+                        premises: above.len(),
+                    });
+                    todo.extend(above.into_iter().rev().map(Todo::Visit));
+                }
+                Todo::Assemble {
+                    rule,
+                    principal,
+                    below,
+                    premises,
+                } => {
+                    let above = done.split_off(done.len().saturating_sub(premises));
+                    done.push(Tree {
+                        above: above.into_iter().collect(),
+                        rule,
+                        principal,
+                        below,
+                    });
+                }
+            }
+        }
+        #[allow(unsafe_code)]
+        // SAFETY: Assembling the root is always the last thing we do.
+        unsafe {
+            Ok(done.pop().unwrap_unchecked())
+        }
+    }
+
+    /// First place this proof and `other` disagree (in pre-order), or `None` if they're the same proof.
+    ///
+    /// Two inference lines agree if they have the same sequent, rule name, principal formula,
+    /// and sequents above them; premises are matched by sequent, not by position.
+    /// Walks an explicit stack, so arbitrarily deep trees won't overflow the call stack.
+    #[inline]
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> Option<TreeDiff<S>> {
+        let mut path = vec![];
+        let mut stack: Vec<vec::IntoIter<(usize, &Self, &Self)>> = vec![];
+        let mut current = Some((self, other));
+        loop {
+            if let Some((left, right)) = current.take() {
+                let Some(pairs) = left.matching_premises(right) else {
+                    return Some(TreeDiff {
+                        path,
+                        left: left.below.clone(),
+                        left_rule: left.rule.clone(),
+                        right: right.below.clone(),
+                        right_rule: right.rule.clone(),
+                    });
+                };
+                stack.push(pairs.into_iter());
+            }
+            let (index, left, right) = loop {
+                let next = stack.last_mut()?.next();
+                if let Some(pair) = next {
+                    break pair;
+                }
+                drop(stack.pop());
+                let _ = path.pop();
+            };
+            path.push(index);
+            current = Some((left, right));
+        }
+    }
+
+    /// If this inference line agrees with `other`'s, pair up their premises by sequent,
+    /// alongside each premise's index in our `above`.
+    #[inline]
+    fn matching_premises<'tree>(
+        &'tree self,
+        other: &'tree Self,
+    ) -> Option<Vec<(usize, &'tree Self, &'tree Self)>> {
+        if self.below != other.below
+            || self.rule != other.rule
+            || self.principal != other.principal
+            || self.above.len() != other.above.len()
+        {
+            return None;
+        }
+        let mut unmatched: Vec<Option<&Self>> = other.above.iter().map(Some).collect();
+        let mut matched: Vec<Option<&Self>> = vec![None; self.above.len()];
+        // Match rule names too at first, so a premise both proven and marked as already proven
+        // doesn't pair its proof with the other side's marker.
+        for same_rule in [true, false] {
+            for (mine, slot) in self.above.iter().zip(&mut matched) {
+                if slot.is_some() {
+                    continue;
+                }
+                *slot = unmatched
+                    .iter_mut()
+                    .find(|candidate| {
+                        candidate.is_some_and(|theirs| {
+                            theirs.below == mine.below && (!same_rule || theirs.rule == mine.rule)
+                        })
+                    })
+                    .and_then(Option::take);
+            }
+        }
+        self.above
+            .iter()
+            .zip(matched)
+            .enumerate()
+            .map(|(index, (mine, theirs))| Some((index, mine, theirs?)))
+            .collect()
+    }
+
+    /// Every node in this tree, each before anything above it (pre-order).
+    /// Walks an explicit stack, so arbitrarily deep trees won't overflow the call stack.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &Self> + '_ {
+        let mut stack = vec![self];
+        core::iter::from_fn(move || {
+            let tree = stack.pop()?;
+            stack.extend(tree.above.iter().rev());
+            Some(tree)
+        })
+    }
+
+    /// Every node with nothing above it, in the same order as `iter`.
+    #[inline]
+    pub fn leaves(&self) -> impl Iterator<Item = &Self> + '_ {
+        self.iter().filter(|tree| tree.above.is_empty())
+    }
+
+    /// Number of nodes (i.e. inference lines) in this tree.
+    #[inline]
+    #[must_use]
+    #[allow(clippy::len_without_is_empty)] // Never empty: there's always at least the root.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Number of inference lines from the bottom to the farthest leaf.
+    #[inline]
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        let mut deepest = 0;
+        let mut stack = vec![(self, 1_usize)];
+        while let Some((tree, depth)) = stack.pop() {
+            deepest = deepest.max(depth);
+            let above = depth.saturating_add(1);
+            stack.extend(tree.above.iter().map(|next| (next, above)));
+        }
+        deepest
+    }
+
+    /// How many times each rule appears in this tree, by name.
+    #[inline]
+    #[must_use]
+    pub fn rule_histogram(&self) -> BTreeMap<&str, usize> {
+        let mut histogram = BTreeMap::new();
+        for tree in self.iter() {
+            let count = histogram.entry(tree.rule.as_ref()).or_insert(0_usize);
+            *count = count.saturating_add(1);
+        }
+        histogram
+    }
+
+    /// Print this proof bottom-up like `Display`, but laid out by `options`.
+    ///
+    /// Within `options.max_width`, premises go side by side wherever they fit
+    /// (labelling inference lines with just the rule wherever the formula it acted on won't fit too),
+    /// and on top of one another wherever they don't.
+    /// Works iteratively, so arbitrarily deep trees won't overflow the stack.
+    #[inline]
+    #[must_use]
+    pub fn render(&self, options: &RenderOptions) -> String {
+        let cells = self.measure(options);
+        // Parents come before their premises, so each knows its budget by the time we get to it.
+        let mut budgets = vec![options.max_width.unwrap_or(usize::MAX); cells.len()];
+        let mut stacked = vec![false; cells.len()];
+        for (id, cell) in cells.iter().enumerate() {
+            let budget = budgets.get(id).copied().unwrap_or(usize::MAX);
+            let mut premises: Vec<(usize, Shape)> = cell
+                .above
+                .iter()
+                .filter_map(|&premise| cells.get(premise).map(|above| (premise, above.short)))
+                .collect();
+            if cell.full.entire <= budget {
+                // Everything above fits too, so it can all be printed in full.
+                for (premise, _) in premises {
+                    if let Some(slot) = budgets.get_mut(premise) {
+                        *slot = usize::MAX;
+                    }
+                }
+            } else if cell.short.entire <= budget || premises.len() < 2 {
+                // Side by side as `Cell::print_side_by_side` will put them:
+                // only the rightmost can take up any more room than it absolutely needs.
+                premises.sort_by_key(|&(_, shape)| shape.entire);
+                let shapes: Vec<_> = premises.iter().map(|&(_, shape)| shape).collect();
+                let layout = arrange(&shapes, options.gap, cell.below_width());
+                let rightmost = premises.pop();
+                for &(premise, shape) in &premises {
+                    if let Some(slot) = budgets.get_mut(premise) {
+                        *slot = shape.entire;
+                    }
+                }
+                if let Some(slot) = rightmost.and_then(|(premise, _)| budgets.get_mut(premise)) {
+                    *slot = budget.saturating_sub(
+                        layout
+                            .offsets
+                            .last()
+                            .map_or(0, |&offset| offset.saturating_add(layout.shift)),
+                    );
+                }
+            } else {
+                if let Some(flag) = stacked.get_mut(id) {
+                    *flag = true;
+                }
+                for (premise, _) in premises {
+                    if let Some(slot) = budgets.get_mut(premise) {
+                        *slot = budget.saturating_sub(options.gap);
+                    }
+                }
+            }
+        }
+        // Premises come after their parents, so going backwards prints each before anything below it.
+        let mut printed: Vec<Option<Printed>> = cells.iter().map(|_| None).collect();
+        for (id, cell) in cells.iter().enumerate().rev() {
+            let columns = cell
+                .above
+                .iter()
+                .filter_map(|&premise| printed.get_mut(premise).and_then(Option::take))
+                .collect();
+            let budget = budgets.get(id).copied().unwrap_or(usize::MAX);
+            let print = if stacked.get(id).copied().unwrap_or(false) {
+                cell.print_stacked(columns, options.gap, budget)
+            } else {
+                cell.print_side_by_side(columns, options.gap, budget)
+            };
+            if let Some(slot) = printed.get_mut(id) {
+                *slot = Some(print);
+            }
+        }
+        let mut out = String::new();
+        if let Some(Some((lines, _))) = printed.into_iter().next() {
+            for line in lines.into_iter().rev() {
+                out.push_str(&line);
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// Every inference line in this proof, each before those above it,
+    /// with its text and its shape with premises side by side all the way up.
+    fn measure(&self, options: &RenderOptions) -> Vec<Cell> {
+        let mut cells: Vec<Cell> = vec![];
+        let mut todo = vec![(self, None)];
+        while let Some((tree, parent)) = todo.pop() {
+            let id = cells.len();
+            if let Some(cell) = parent.and_then(|index: usize| cells.get_mut(index)) {
+                cell.above.push(id);
+            }
+            // Translate before measuring anything, since symbols and their replacements differ in width.
+            let translate = |text: String| {
+                if options.ascii {
+                    to_ascii(&text)
+                } else {
+                    text
+                }
+            };
+            let below = if options.ascii {
+                format!("{:#}", tree.below)
+            } else {
+                tree.below.to_string()
+            };
+            let labels = options.rule_names.then(|| {
+                let full = tree.principal.as_ref().map_or_else(
+                    || tree.rule.to_string(),
+                    |principal| format!("{} on ({principal})", tree.rule),
+                );
+                (translate(full), translate(tree.rule.to_string()))
+            });
+            cells.push(Cell {
+                below,
+                labels,
+                above: vec![],
+                full: Shape::default(),
+                short: Shape::default(),
+            });
+            todo.extend(tree.above.iter().rev().map(|above| (above, Some(id))));
+        }
+        for id in (0..cells.len()).rev() {
+            let Some(cell) = cells.get(id) else {
+                continue;
+            };
+            let shape = |full: bool| {
+                let mut columns: Vec<_> = cell
+                    .above
+                    .iter()
+                    .filter_map(|&premise| {
+                        cells
+                            .get(premise)
+                            .map(|above| if full { above.full } else { above.short })
+                    })
+                    .collect();
+                columns.sort_by_key(|shape| shape.entire);
+                let layout = arrange(&columns, options.gap, cell.below_width());
+                Shape {
+                    left: layout.below_start,
+                    width: cell.below_width(),
+                    entire: layout.entire.max(
+                        layout
+                            .line_start
+                            .saturating_add(cell.line_length(layout.dashes, full)),
+                    ),
+                }
+            };
+            let (full, short) = (shape(true), shape(false));
+            if let Some(measured) = cells.get_mut(id) {
+                measured.full = full;
+                measured.short = short;
+            }
+        }
+        cells
+    }
+
+    /// Print this proof with every symbol spelled out in plain ASCII (`|-` for the turnstile, `*` for times, etc.),
+    /// exactly like the alternate form `{:#}`.
+    #[inline]
+    #[must_use]
+    pub fn to_ascii_string(&self) -> String {
+        format!("{self:#}")
+    }
+
+    /// Render this proof as JSON for tools that only need the printed sequents and rule names.
+    ///
+    /// Each inference line becomes `{"sequent": "...", "rule": "...", "premises": [...]}`,
+    /// where `sequent` is the `Display` output of the sequent below the line,
+    /// `rule` is the name of the rule, and `premises` holds one object like this for each proof above it
+    /// (empty for leaves). Works iteratively, so arbitrarily deep trees won't overflow the stack.
+    #[inline]
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        /// Either a subtree still to render or punctuation to write once we get back to it.
+        enum Todo<'tree, S: Sequent> {
+            /// Subtree still to render.
+            Tree(&'tree Tree<S>),
+            /// Punctuation between or after premises.
+            Text(&'static str),
+        }
+        let mut out = String::new();
+        let mut stack = vec![Todo::Tree(self)];
+        while let Some(todo) = stack.pop() {
+            match todo {
+                Todo::Text(text) => out.push_str(text),
+                Todo::Tree(tree) => {
+                    out.push_str("{\"sequent\":");
+                    json::push_string(&mut out, &tree.below.to_string());
+                    out.push_str(",\"rule\":");
+                    json::push_string(&mut out, &tree.rule);
+                    out.push_str(",\"premises\":[");
+                    stack.push(Todo::Text("]}"));
+                    for (i, above) in tree.above.iter().rev().enumerate() {
+                        if i != 0 {
+                            stack.push(Todo::Text(","));
+                        }
+                        stack.push(Todo::Tree(above));
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Render this proof as a standalone HTML document (styles inline, nothing to fetch),
+    /// with each inference's premises side by side above a line and its sequent below.
+    ///
+    /// Each subproof sits in a `<details>` element, open to begin with, so any of them can be collapsed.
+    /// Sequents are printed with the `Html` renderer.
+    /// Works iteratively, so arbitrarily deep trees won't overflow the stack.
+    #[inline]
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        /// Either a subtree still to render or markup to write once we get back to it.
+        enum Todo<'tree, S: Sequent> {
+            /// Subtree still to render.
+            Tree(&'tree Tree<S>),
+            /// Markup after its premises.
+            Text(String),
+        }
+        let mut out = html::HEAD.to_owned();
+        out.push_str(&self.below.render_with(&Html));
+        out.push_str(html::STYLE);
+        let mut stack = vec![Todo::Tree(self)];
+        while let Some(todo) = stack.pop() {
+            match todo {
+                Todo::Text(text) => out.push_str(&text),
+                Todo::Tree(tree) => {
+                    out.push_str("<div class=\"inference\">\n");
+                    let label = tree.principal.as_ref().map_or_else(
+                        || tree.rule.to_string(),
+                        |principal| format!("{} on ({principal})", tree.rule),
+                    );
+                    let mut after = String::new();
+                    if !tree.above.is_empty() {
+                        out.push_str("<details open>\n<summary>");
+                        out.push_str(&tree.above.len().to_string());
+                        out.push_str(if tree.above.len() == 1 {
+                            " premise"
+                        } else {
+                            " premises"
+                        });
+                        out.push_str("</summary>\n<div class=\"premises\">\n");
+                        after.push_str("</div>\n</details>\n");
+                    }
+                    after.push_str("<div class=\"conclusion\">");
+                    after.push_str(&tree.below.render_with(&Html));
+                    after.push_str("<span class=\"rule\">");
+                    after.push_str(&html::escape(&label));
+                    after.push_str("</span></div>\n</div>\n");
+                    stack.push(Todo::Text(after));
+                    stack.extend(tree.above.iter().rev().map(Todo::Tree));
+                }
+            }
+        }
+        out.push_str(html::TAIL);
+        out
+    }
+
+    /// Typeset this proof as a `bussproofs` `prooftree` environment,
+    /// translating symbols with `LATEX_SYMBOLS`.
+    #[inline]
+    #[must_use]
+    pub fn to_latex(&self) -> String {
+        self.to_latex_with(LATEX_SYMBOLS)
+    }
+
+    /// Typeset this proof as a `bussproofs` `prooftree` environment,
+    /// translating any symbol in `symbols` (checked in order) into its LaTeX equivalent.
+    #[inline]
+    #[must_use]
+    pub fn to_latex_with(&self, symbols: &[(&str, &str)]) -> String {
+        let mut out = "\\begin{prooftree}\n".to_owned();
+        self.write_latex(&mut out, symbols);
+        out.push_str("\\end{prooftree}\n");
+        out
+    }
+
+    /// Append the `bussproofs` commands for this subtree, premises first.
+    #[inline]
+    fn write_latex(&self, out: &mut String, symbols: &[(&str, &str)]) {
+        if self.above.is_empty() {
+            out.push_str("\\AxiomC{}\n");
+        }
+        for tree in &self.above {
+            tree.write_latex(out, symbols);
+        }
+        // Side by side without a line, so the last inference has few enough premises.
+        for _ in MAX_PREMISES..self.above.len() {
+            out.push_str("\\noLine\n\\BinaryInfC{}\n");
+        }
+        let command = match self.above.len() {
+            0 | 1 => "Unary",
+            2 => "Binary",
+            _ => "Trinary",
+        };
+        out.push_str("\\RightLabel{$");
+        out.push_str(&escape(&self.rule, symbols).replace(' ', "\\ "));
+        out.push_str("$}\n\\");
+        out.push_str(command);
+        out.push_str("InfC{$");
+        out.push_str(&self.below.render_with(&Latex::with(symbols)));
+        out.push_str("$}\n");
+    }
+}
+
+/// One inference line being rendered, with everything its layout depends on.
+#[derive(Debug)]
+struct Cell {
+    /// Sequent below the inference line, already printed.
+    below: String,
+    /// Label beside the inference line, in full and with just the rule,
+    /// or `None` if rule names are hidden.
+    labels: Option<(String, String)>,
+    /// Index of each premise's cell.
+    above: Vec<usize>,
+    /// Shape of its print with premises side by side all the way up and every label in full.
+    full: Shape,
+    /// The same, but with every label shortened.
+    short: Shape,
+}
+
+impl Cell {
+    /// Characters in the sequent below the inference line.
+    fn below_width(&self) -> usize {
+        self.below.chars().count()
+    }
+
+    /// Characters in the inference line with this many dashes, counting its label in full or shortened.
+    fn line_length(&self, dashes: usize, full: bool) -> usize {
+        self.labels
+            .as_ref()
+            .map_or(dashes, |&(ref long, ref short)| {
+                let label = if full { long } else { short };
+                dashes
+                    .saturating_add(1)
+                    .saturating_add(label.chars().count())
+            })
+    }
+
+    /// The inference line, starting `start` characters in with this many dashes,
+    /// then its label in full if that fits within `budget`.
+    fn line(&self, start: usize, dashes: usize, budget: usize) -> String {
+        let mut line = " ".repeat(start);
+        for _ in 0..dashes {
             line.push('-');
         }
-        line.push(' ');
-        line.push_str(self.rule);
-        let mut everything = vec![below, line];
-        if let Some(stack) = maybe_stack {
-            everything.extend(stack);
+        if let Some((ref long, ref short)) = self.labels {
+            line.push(' ');
+            let full = start.saturating_add(self.line_length(dashes, true)) <= budget;
+            line.push_str(if full { long } else { short });
+        }
+        line
+    }
+
+    /// Each line of printed output, given a print of each proof above this inference line,
+    /// which go side by side, `gap` apart, centered over the conclusion (or it under them).
+    fn print_side_by_side(&self, mut columns: Vec<Printed>, gap: usize, budget: usize) -> Printed {
+        columns.sort_by_key(|&(_, shape)| shape.entire);
+        let shapes: Vec<_> = columns.iter().map(|&(_, shape)| shape).collect();
+        let layout = arrange(&shapes, gap, self.below_width());
+        let mut everything = VecDeque::new();
+        for ((stack, _), &offset) in columns.into_iter().zip(&layout.offsets) {
+            extend_upward(&mut everything, stack, layout.shift.saturating_add(offset));
+        }
+        let line = self.line(layout.line_start, layout.dashes, budget);
+        let entire = layout.entire.max(line.chars().count());
+        everything.push_front(line);
+        everything.push_front(format!("{}{}", " ".repeat(layout.below_start), self.below));
+        (
+            everything,
+            Shape {
+                left: layout.below_start,
+                width: self.below_width(),
+                entire,
+            },
+        )
+    }
+
+    /// Each line of printed output, given a print of each proof above this inference line,
+    /// which go one on top of another (the first lowest), each indented by `gap`
+    /// and separated by a blank line so it's clear where one ends.
+    fn print_stacked(&self, columns: Vec<Printed>, gap: usize, budget: usize) -> Printed {
+        let indent = " ".repeat(gap);
+        let mut everything = VecDeque::new();
+        let mut dashes = self.below_width();
+        let mut entire = 0;
+        for (i, (stack, shape)) in columns.into_iter().enumerate() {
+            if i != 0 {
+                everything.push_back(String::new());
+            }
+            everything.extend(stack.into_iter().map(|line| format!("{indent}{line}")));
+            dashes = dashes.max(gap.saturating_add(shape.left).saturating_add(shape.width));
+            entire = entire.max(gap.saturating_add(shape.entire));
         }
-        (everything, max_width)
+        let line = self.line(0, dashes, budget);
+        let entire_width = entire.max(line.chars().count());
+        everything.push_front(line);
+        everything.push_front(self.below.clone());
+        (
+            everything,
+            Shape {
+                left: 0,
+                width: self.below_width(),
+                entire: entire_width,
+            },
+        )
+    }
+}
+
+/// Where a printed proof's conclusion sits, and how wide the whole print is.
+#[derive(Clone, Copy, Debug, Default)]
+struct Shape {
+    /// Characters before the conclusion on its line.
+    left: usize,
+    /// Characters in the conclusion.
+    width: usize,
+    /// Characters in the widest line.
+    entire: usize,
+}
+
+/// Where everything goes when proofs with these shapes go side by side, `gap` apart,
+/// over an inference line and a conclusion `below` characters wide.
+#[derive(Debug)]
+struct Layout {
+    /// How far right of the leftmost column each column starts.
+    offsets: Vec<usize>,
+    /// How far right every column moves to center them over a wider conclusion.
+    shift: usize,
+    /// Characters before the inference line.
+    line_start: usize,
+    /// Dashes in the inference line, spanning every premise's conclusion or our own, whichever is wider.
+    dashes: usize,
+    /// Characters before the conclusion, which is centered under the inference line.
+    below_start: usize,
+    /// Characters in the widest line but the inference line itself.
+    entire: usize,
+}
+
+/// Lay out proofs with these shapes side by side, `gap` apart, in order,
+/// with the inference line under them spanning from the first premise's conclusion to the last's
+/// and everything centered over or under whichever is narrower.
+fn arrange(columns: &[Shape], gap: usize, below: usize) -> Layout {
+    let mut offsets = Vec::with_capacity(columns.len());
+    let mut overall_width = 0_usize;
+    for shape in columns {
+        offsets.push(overall_width);
+        overall_width = overall_width
+            .saturating_add(shape.entire)
+            .saturating_add(gap);
+    }
+    let (start, end) = match (
+        columns.first().zip(offsets.first()),
+        columns.last().zip(offsets.last()),
+    ) {
+        (Some((first, &first_offset)), Some((last, &last_offset))) => (
+            first_offset.saturating_add(first.left),
+            last_offset
+                .saturating_add(last.left)
+                .saturating_add(last.width),
+        ),
+        _ => (0, 0),
+    };
+    let span = end.saturating_sub(start);
+    let dashes = span.max(below);
+    // Half the extra room on either side of the premises, moving them right if they're too close to the edge.
+    let before = dashes.saturating_sub(span) >> 1_u32;
+    let shift = before.saturating_sub(start);
+    let line_start = start.saturating_add(shift).saturating_sub(before);
+    let below_start = line_start.saturating_add(dashes.saturating_sub(below) >> 1_u32);
+    Layout {
+        entire: overall_width
+            .saturating_sub(gap)
+            .saturating_add(shift)
+            .max(below_start.saturating_add(below)),
+        offsets,
+        shift,
+        line_start,
+        dashes,
+        below_start,
     }
 }
 
-/// Add a column to a print of a proof, even if the previous print wasn't tall enough.
+/// Add a column starting `offset` characters in to a print of a proof,
+/// even if the previous print wasn't tall enough.
 #[inline]
 #[allow(clippy::option_if_let_else)] // Mutable borrow issues with `Option::map_or_else`
-fn extend_upward(v: &mut Vec<String>, stack: Vec<String>, overall_width: usize) {
+fn extend_upward(v: &mut VecDeque<String>, stack: VecDeque<String>, offset: usize) {
+    if v.is_empty() && offset == 0 {
+        // Nothing to line up with, so skip copying every line.
+        *v = stack;
+        return;
+    }
     for (i, line) in stack.into_iter().enumerate() {
         let acc = if let Some(s) = v.get_mut(i) {
             s
         } else {
-            v.push(" ".repeat(overall_width));
+            v.push_back(String::new());
             #[allow(unsafe_code)]
-            // SAFETY:
-            // Iterating one at a time from zero,
-            // so we can never be less than one element behind,
-            // and we just added one above.
+            // SAFETY: We just added one at the back.
             unsafe {
-                v.get_unchecked_mut(i)
+                v.back_mut().unwrap_unchecked()
             }
         };
-        for _ in acc.chars().count()..overall_width {
+        for _ in acc.chars().count()..offset {
             acc.push(' ');
         }
         acc.push_str(&line);
     }
 }
 
+impl<S: Sequent> Drop for Tree<S> {
+    /// Move everything above into a worklist before dropping it,
+    /// so arbitrarily deep trees won't overflow the stack.
+    #[inline]
+    fn drop(&mut self) {
+        let mut stack: Vec<Self> = core::mem::take(&mut self.above).into_iter().collect();
+        while let Some(mut tree) = stack.pop() {
+            stack.extend(core::mem::take(&mut tree.above));
+        }
+    }
+}
+
 impl<S: Sequent> core::fmt::Display for Tree<S> {
     #[inline]
     #[allow(clippy::arithmetic_side_effects)]
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         writeln!(f)?;
-        for line in self.print_bottom_up().0.into_iter().rev() {
-            writeln!(f, "{line}")?;
-        }
-        Ok(())
+        f.write_str(&self.render(&RenderOptions {
+            ascii: f.alternate(),
+            ..RenderOptions::default()
+        }))
     }
 }